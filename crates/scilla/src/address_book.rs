@@ -0,0 +1,230 @@
+use {
+    crate::{constants::ADDRESS_BOOK_RELATIVE_PATH, error::ScillaError},
+    anyhow::{anyhow, bail},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    solana_pubkey::Pubkey,
+    std::{collections::BTreeMap, env::home_dir, fs, path::PathBuf, str::FromStr},
+};
+
+/// Addresses from a command result mapped to their address book alias.
+pub type AddressLabels = BTreeMap<String, String>;
+
+pub fn address_book_path() -> PathBuf {
+    let mut path = home_dir().expect("Error getting home path");
+    path.push(ADDRESS_BOOK_RELATIVE_PATH);
+    path
+}
+
+/// Saved addresses, stored as `alias = "<pubkey>"` under `[addresses]`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct AddressBook {
+    #[serde(
+        default,
+        serialize_with = "serialize_addresses",
+        deserialize_with = "deserialize_addresses"
+    )]
+    addresses: BTreeMap<String, Pubkey>,
+}
+
+fn serialize_addresses<S>(
+    addresses: &BTreeMap<String, Pubkey>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_map(
+        addresses
+            .iter()
+            .map(|(alias, pubkey)| (alias, pubkey.to_string())),
+    )
+}
+
+fn deserialize_addresses<'de, D>(deserializer: D) -> Result<BTreeMap<String, Pubkey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: BTreeMap<String, String> = Deserialize::deserialize(deserializer)?;
+    raw.into_iter()
+        .map(|(alias, pubkey)| {
+            let pubkey = Pubkey::from_str(&pubkey).map_err(|e| {
+                serde::de::Error::custom(format!("invalid pubkey for \"{alias}\": {e}"))
+            })?;
+            Ok((alias, pubkey))
+        })
+        .collect()
+}
+
+impl AddressBook {
+    /// Loads the address book, treating a missing file as an empty book.
+    pub fn load() -> Result<AddressBook, ScillaError> {
+        Self::load_from_path(&address_book_path())
+    }
+
+    pub fn load_from_path(path: &std::path::Path) -> Result<AddressBook, ScillaError> {
+        if !path.exists() {
+            return Ok(AddressBook::default());
+        }
+        let data = fs::read_to_string(path)?;
+        let book: AddressBook = toml::from_str(&data)?;
+        Ok(book)
+    }
+
+    pub fn save(&self) -> Result<(), ScillaError> {
+        let path = address_book_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    pub fn aliases(&self) -> Vec<String> {
+        self.addresses.keys().cloned().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Pubkey)> {
+        self.addresses.iter()
+    }
+
+    pub fn get(&self, alias: &str) -> Option<&Pubkey> {
+        self.addresses.get(alias)
+    }
+
+    /// Adds or replaces `alias`, returning the address it pointed to before.
+    pub fn insert(&mut self, alias: &str, pubkey: Pubkey) -> anyhow::Result<Option<Pubkey>> {
+        let alias = alias.trim().trim_start_matches('@');
+        validate_alias(alias)?;
+        Ok(self.addresses.insert(alias.to_string(), pubkey))
+    }
+
+    pub fn remove(&mut self, alias: &str) -> Option<Pubkey> {
+        self.addresses.remove(alias.trim().trim_start_matches('@'))
+    }
+
+    /// Parses either a base58 pubkey or an `@alias` from the book.
+    pub fn resolve(&self, input: &str) -> anyhow::Result<Pubkey> {
+        let input = input.trim();
+        match input.strip_prefix('@') {
+            Some(alias) => self
+                .get(alias)
+                .copied()
+                .ok_or_else(|| anyhow!("No address saved as @{alias}")),
+            None => Pubkey::from_str(input).map_err(|e| anyhow!("{e}")),
+        }
+    }
+
+    /// Alias saved for `address`, if any.
+    pub fn label_for(&self, address: &str) -> Option<&str> {
+        let pubkey = Pubkey::from_str(address).ok()?;
+        self.addresses
+            .iter()
+            .find(|(_, saved)| **saved == pubkey)
+            .map(|(alias, _)| alias.as_str())
+    }
+
+    /// Collects the aliases for every address in `addresses` that is saved in
+    /// the book.
+    pub fn labels<'a>(&self, addresses: impl IntoIterator<Item = &'a str>) -> AddressLabels {
+        if self.is_empty() {
+            return AddressLabels::new();
+        }
+
+        addresses
+            .into_iter()
+            .filter_map(|address| {
+                self.label_for(address)
+                    .map(|alias| (address.to_string(), alias.to_string()))
+            })
+            .collect()
+    }
+}
+
+fn validate_alias(alias: &str) -> anyhow::Result<()> {
+    if alias.is_empty() {
+        bail!("Alias cannot be empty");
+    }
+    if !alias
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        bail!("Alias \"{alias}\" may only contain letters, digits, '-', '_' and '.'");
+    }
+    Ok(())
+}
+
+/// Appends the address book alias to `address` for display in tables.
+pub fn with_label(address: &str, labels: &AddressLabels) -> String {
+    match labels.get(address) {
+        Some(alias) => format!("{address} (@{alias})"),
+        None => address.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_load_from_path_missing_file_is_empty() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let book = AddressBook::load_from_path(&temp_dir.path().join("addresses.toml"))
+            .expect("missing book should load");
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn test_load_from_path_rejects_invalid_pubkey() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("addresses.toml");
+        fs::write(&path, "[addresses]\ntreasury = \"not-a-pubkey\"\n").expect("write book");
+
+        let result = AddressBook::load_from_path(&path);
+        assert!(matches!(result, Err(ScillaError::TomlParseError(_))));
+    }
+
+    #[test]
+    fn test_resolve_alias_and_pubkey() {
+        let treasury = Pubkey::new_unique();
+        let mut book = AddressBook::default();
+        book.insert("@treasury", treasury).expect("valid alias");
+
+        assert_eq!(book.resolve("@treasury").unwrap(), treasury);
+        assert_eq!(book.resolve(&treasury.to_string()).unwrap(), treasury);
+        assert!(book.resolve("@unknown").is_err());
+        assert!(book.resolve("treasury").is_err());
+    }
+
+    #[test]
+    fn test_insert_rejects_invalid_alias() {
+        let mut book = AddressBook::default();
+        assert!(book.insert("", Pubkey::new_unique()).is_err());
+        assert!(book.insert("my wallet", Pubkey::new_unique()).is_err());
+        assert!(book.insert("vote-1.main_net", Pubkey::new_unique()).is_ok());
+    }
+
+    #[test]
+    fn test_round_trip_and_labels() {
+        let vote = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut book = AddressBook::default();
+        book.insert("vote", vote).expect("valid alias");
+
+        let toml_string = toml::to_string_pretty(&book).expect("book serializes");
+        assert!(toml_string.contains(&format!("vote = \"{vote}\"")));
+
+        let book: AddressBook = toml::from_str(&toml_string).expect("book round-trips");
+        let vote = vote.to_string();
+        let other = other.to_string();
+        let labels = book.labels([vote.as_str(), other.as_str()]);
+
+        assert_eq!(labels.len(), 1);
+        assert_eq!(with_label(&vote, &labels), format!("{vote} (@vote)"));
+        assert_eq!(with_label(&other, &labels), other);
+    }
+}
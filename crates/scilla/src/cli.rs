@@ -0,0 +1,332 @@
+use {
+    crate::{
+        commands::{
+            account::AccountArgs,
+            address::AddressArgs,
+            cluster::ClusterArgs,
+            config::ConfigArgs,
+            keypair::KeypairArgs,
+            program::{ProgramArgs, ProgramV4Args},
+            stake::StakeArgs,
+            token::TokenArgs,
+            transaction::TransactionArgs,
+            vote::VoteArgs,
+        },
+        config::{PriorityFeeConfig, Transport},
+        context::ScillaContext,
+        output::OutputFormat,
+    },
+    clap::{Args, Parser, Subcommand},
+    solana_hash::Hash,
+    solana_pubkey::Pubkey,
+    std::path::PathBuf,
+};
+
+/// ⚡ Scilla — Hacking Through the Solana Matrix
+///
+/// Run without a subcommand to start the interactive menus. Any argument left
+/// out of a subcommand is prompted for interactively.
+#[derive(Debug, Parser)]
+#[command(name = "scilla", version)]
+pub struct Cli {
+    /// Output format for command results
+    #[arg(long, short, global = true, value_enum, default_value_t)]
+    pub output: OutputFormat,
+
+    /// Config profile to use instead of the `active-profile` in scilla.toml
+    #[arg(long, short, global = true)]
+    pub profile: Option<String>,
+
+    /// Simulate transactions against current cluster state instead of sending
+    /// them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Sign transactions with the keypairs available here and print them for
+    /// the remaining signers instead of sending them
+    #[arg(long, global = true)]
+    pub sign_only: bool,
+
+    /// Blockhash to sign with in sign-only mode instead of the latest one
+    #[arg(long, global = true, requires = "sign_only")]
+    pub blockhash: Option<Hash>,
+
+    /// Build transactions against this durable nonce account instead of a
+    /// recent blockhash
+    #[arg(long, global = true, value_name = "NONCE_PUBKEY")]
+    pub nonce: Option<Pubkey>,
+
+    /// Keypair of the nonce authority, when it is not the configured keypair
+    #[arg(long, global = true, value_name = "KEYPAIR_PATH", requires = "nonce")]
+    pub nonce_authority: Option<PathBuf>,
+
+    /// Sign and send transactions without previewing and confirming them,
+    /// like the profile's `skip-preview`
+    #[arg(long, global = true)]
+    pub skip_preview: bool,
+
+    /// How to send transactions, instead of the profile's `transport`
+    #[arg(long, global = true, value_enum)]
+    pub transport: Option<Transport>,
+
+    #[command(flatten)]
+    pub priority_fee: PriorityFeeArgs,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Overrides for the profile's `priority-fee` settings, for this run only.
+#[derive(Debug, Clone, Default, Args)]
+#[command(next_help_heading = "Priority fees")]
+pub struct PriorityFeeArgs {
+    /// Pay this percentile (0-100) of the recent prioritization fees on the
+    /// transaction's writable accounts
+    #[arg(long, global = true, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub priority_fee_percentile: Option<u8>,
+
+    /// Pay a fixed price in micro-lamports per compute unit
+    #[arg(long, global = true, conflicts_with = "priority_fee_percentile")]
+    pub compute_unit_price: Option<u64>,
+
+    /// Request this many compute units instead of simulating the transaction
+    /// to size the limit
+    #[arg(long, global = true)]
+    pub compute_unit_limit: Option<u32>,
+
+    /// Send transactions without compute budget instructions
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["priority_fee_percentile", "compute_unit_price", "compute_unit_limit"]
+    )]
+    pub no_priority_fee: bool,
+}
+
+impl PriorityFeeArgs {
+    pub fn apply(&self, config: &mut PriorityFeeConfig) {
+        if let Some(percentile) = self.priority_fee_percentile {
+            config.enabled = true;
+            config.percentile = percentile;
+            config.compute_unit_price = None;
+        }
+        if let Some(price) = self.compute_unit_price {
+            config.enabled = true;
+            config.compute_unit_price = Some(price);
+        }
+        if let Some(units) = self.compute_unit_limit {
+            config.enabled = true;
+            config.compute_unit_limit = Some(units);
+        }
+        if self.no_priority_fee {
+            config.enabled = false;
+        }
+    }
+}
+
+/// Command groups available non-interactively, mirroring
+/// [`main_menu`](crate::commands::main_command::main_menu).
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Manage wallets and on-chain accounts
+    #[command(subcommand)]
+    Account(AccountArgs),
+    /// Generate, recover, inspect and grind keypair files
+    #[command(subcommand)]
+    Keypair(KeypairArgs),
+    /// Query the state of the cluster
+    #[command(subcommand)]
+    Cluster(ClusterArgs),
+    /// Create mints, move tokens and manage SPL Token accounts
+    #[command(subcommand)]
+    Token(TokenArgs),
+    /// Stake account lifecycle management
+    #[command(subcommand)]
+    Stake(StakeArgs),
+    /// Deploy and manage upgradeable (loader v3) programs
+    #[command(subcommand)]
+    Program(ProgramArgs),
+    /// Deploy and manage loader v4 programs
+    #[command(subcommand)]
+    ProgramV4(ProgramV4Args),
+    /// Vote account management
+    #[command(subcommand)]
+    Vote(VoteArgs),
+    /// Inspect, send and simulate transactions
+    #[command(subcommand)]
+    Transaction(TransactionArgs),
+    /// Manage the address book of saved pubkeys
+    #[command(subcommand)]
+    Address(AddressArgs),
+    /// Manage the Scilla configuration
+    #[command(subcommand)]
+    Config(ConfigArgs),
+}
+
+impl CliCommand {
+    pub async fn run(self, ctx: &mut ScillaContext) -> anyhow::Result<()> {
+        match self {
+            CliCommand::Account(args) => args.run(ctx).await,
+            CliCommand::Keypair(args) => args.run(ctx),
+            CliCommand::Cluster(args) => args.run(ctx).await,
+            CliCommand::Token(args) => args.run(ctx).await,
+            CliCommand::Stake(args) => args.run(ctx).await,
+            CliCommand::Program(args) => args.run(ctx).await,
+            CliCommand::ProgramV4(args) => args.run(ctx).await,
+            CliCommand::Vote(args) => args.run(ctx).await,
+            CliCommand::Transaction(args) => args.run(ctx).await,
+            CliCommand::Address(args) => args.run(ctx),
+            CliCommand::Config(args) => args.run(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, clap::CommandFactory, solana_pubkey::Pubkey};
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_stake_delegate_with_partial_args() {
+        let stake = Pubkey::new_unique();
+        let cli =
+            Cli::try_parse_from(["scilla", "stake", "delegate", "--stake", &stake.to_string()])
+                .expect("valid arguments should parse");
+
+        let Some(CliCommand::Stake(StakeArgs::Delegate {
+            stake: parsed_stake,
+            vote,
+            stake_authority_keypair,
+        })) = cli.command
+        else {
+            panic!("expected stake delegate subcommand");
+        };
+
+        assert_eq!(parsed_stake, Some(stake));
+        // Missing arguments are left for the interactive prompts
+        assert_eq!(vote, None);
+        assert_eq!(stake_authority_keypair, None);
+    }
+
+    #[test]
+    fn test_parse_token_transfer_keeps_amount_exact() {
+        let mint = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "scilla",
+            "token",
+            "transfer",
+            &mint.to_string(),
+            "--amount",
+            "0.000001",
+        ])
+        .expect("valid arguments should parse");
+
+        let Some(CliCommand::Token(TokenArgs::Transfer {
+            mint: parsed_mint,
+            to,
+            amount: Some(amount),
+            owner,
+        })) = cli.command
+        else {
+            panic!("expected token transfer subcommand");
+        };
+
+        assert_eq!(parsed_mint, Some(mint));
+        assert_eq!(amount.to_base_units(6).unwrap(), 1);
+        assert_eq!(to, None);
+        assert_eq!(owner, None);
+    }
+
+    #[test]
+    fn test_parse_account_transfer_rejects_invalid_amounts() {
+        let to = Pubkey::new_unique().to_string();
+        for amount in ["-1", "0", "NaN", "inf"] {
+            let result = Cli::try_parse_from([
+                "scilla", "account", "transfer", "--to", &to, "--amount", amount,
+            ]);
+            assert!(result.is_err(), "{amount} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_parse_program_deploy_final() {
+        let cli = Cli::try_parse_from([
+            "scilla",
+            "program",
+            "deploy",
+            "target/deploy/counter.so",
+            "--max-len",
+            "200000",
+            "--final",
+        ])
+        .expect("valid arguments should parse");
+
+        let Some(CliCommand::Program(ProgramArgs::Deploy {
+            program_path,
+            max_len,
+            is_final,
+            buffer,
+            ..
+        })) = cli.command
+        else {
+            panic!("expected program deploy subcommand");
+        };
+
+        assert_eq!(
+            program_path,
+            Some(PathBuf::from("target/deploy/counter.so"))
+        );
+        assert_eq!(max_len, Some(200_000));
+        assert!(is_final);
+        assert_eq!(buffer, None);
+    }
+
+    #[test]
+    fn test_parse_program_v4_redeploy() {
+        let program_id = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "scilla",
+            "program-v4",
+            "redeploy",
+            "target/deploy/counter.so",
+            "--program-id",
+            &program_id.to_string(),
+        ])
+        .expect("valid arguments should parse");
+
+        let Some(CliCommand::ProgramV4(ProgramV4Args::Redeploy {
+            program_path,
+            program_id: parsed_program_id,
+            authority,
+            buffer,
+        })) = cli.command
+        else {
+            panic!("expected program-v4 redeploy subcommand");
+        };
+
+        assert_eq!(
+            program_path,
+            Some(PathBuf::from("target/deploy/counter.so"))
+        );
+        assert_eq!(parsed_program_id, Some(program_id));
+        assert_eq!(authority, None);
+        assert_eq!(buffer, None);
+    }
+
+    #[test]
+    fn test_parse_global_profile_after_subcommand() {
+        let cli = Cli::try_parse_from(["scilla", "cluster", "epoch-info", "--profile", "local"])
+            .expect("global profile flag should parse");
+        assert_eq!(cli.profile.as_deref(), Some("local"));
+    }
+
+    #[test]
+    fn test_no_subcommand_means_interactive() {
+        let cli = Cli::try_parse_from(["scilla"]).expect("no arguments should parse");
+        assert!(cli.command.is_none());
+    }
+}
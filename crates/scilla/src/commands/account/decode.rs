@@ -0,0 +1,492 @@
+use {
+    super::NonceAccountOutput,
+    crate::{
+        commands::{
+            stake::{StakeAccountOutput, fetch_stake_account},
+            token::{decode_token_state, is_token_program, token_state_tables},
+            vote::{VoteAccountOutput, decode_vote_account},
+        },
+        context::ScillaContext,
+        misc::{helpers::bincode_deserialize, nonce::decode_nonce_account},
+        output::{CommandOutput, TableView, flatten_fields, humanize},
+    },
+    anyhow::bail,
+    base64::{Engine, engine::general_purpose::STANDARD},
+    clap::ValueEnum,
+    serde::Serialize,
+    serde_json::Value,
+    solana_account::Account,
+    solana_account_decoder::{
+        parse_address_lookup_table::{LookupTableAccountType, parse_address_lookup_table},
+        parse_sysvar::{SysvarAccountType, parse_sysvar},
+        parse_token::TokenAccountType,
+    },
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::{
+        address_lookup_table, bpf_loader_upgradeable, stake, system_program, sysvar, vote,
+    },
+    std::fmt,
+};
+
+/// Sysvars such as slot hashes and stake history hold hundreds of entries,
+/// so tables only list the most recent ones. The JSON output has all of them.
+const MAX_LISTED_ENTRIES: usize = 32;
+
+/// Undecoded data drawn in tables, in bytes.
+const MAX_RAW_TABLE_BYTES: usize = 1024;
+
+/// Values longer than this, such as the slot history bitmap, are cut short in
+/// tables.
+const MAX_FIELD_CHARS: usize = 88;
+
+/// How account data no decoder understands is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RawEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+impl RawEncoding {
+    fn encode(&self, data: &[u8]) -> String {
+        match self {
+            RawEncoding::Hex => data.iter().map(|b| format!("{b:02x}")).collect(),
+            RawEncoding::Base64 => STANDARD.encode(data),
+        }
+    }
+}
+
+impl fmt::Display for RawEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encoding = match self {
+            RawEncoding::Hex => "hex",
+            RawEncoding::Base64 => "base64",
+        };
+        write!(f, "{encoding}")
+    }
+}
+
+/// Account data decoded by the program that owns the account.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "program", content = "state")]
+pub enum DecodedAccount {
+    Nonce(NonceAccountOutput),
+    Stake(StakeAccountOutput),
+    Vote(VoteAccountOutput),
+    /// Mints and token accounts of SPL Token and Token-2022
+    Token(TokenAccountType),
+    UpgradeableLoader(UpgradeableLoaderAccount),
+    AddressLookupTable(LookupTableAccountType),
+    Sysvar(SysvarAccountType),
+    /// Data of a program without a decoder, or that did not decode
+    Raw(RawAccountData),
+}
+
+impl DecodedAccount {
+    pub fn tables(&self) -> Vec<TableView> {
+        match self {
+            DecodedAccount::Nonce(nonce) => nonce.tables(),
+            DecodedAccount::Stake(stake) => stake.tables(),
+            DecodedAccount::Vote(vote) => vote.tables(),
+            DecodedAccount::Token(state) => token_state_tables(state),
+            DecodedAccount::UpgradeableLoader(account) => vec![account.table()],
+            DecodedAccount::AddressLookupTable(lookup_table) => lookup_table_tables(lookup_table),
+            DecodedAccount::Sysvar(sysvar) => sysvar_tables(sysvar),
+            DecodedAccount::Raw(raw) => vec![raw.table()],
+        }
+    }
+}
+
+/// Decodes `account` with the decoder of its owner program. Accounts without
+/// data have nothing to decode, and data that does not decode is kept raw in
+/// `encoding`.
+pub async fn decode_account(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+    account: &Account,
+    encoding: RawEncoding,
+) -> Option<DecodedAccount> {
+    if account.data.is_empty() {
+        return None;
+    }
+
+    let decoded = decode_by_owner(ctx, pubkey, account)
+        .await
+        .unwrap_or_else(|_| DecodedAccount::Raw(RawAccountData::new(&account.data, encoding)));
+    Some(decoded)
+}
+
+async fn decode_by_owner(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+    account: &Account,
+) -> anyhow::Result<DecodedAccount> {
+    let owner = &account.owner;
+    let decoded = if *owner == system_program::id() {
+        // The only system program accounts holding data are nonce accounts
+        let nonce = decode_nonce_account(account.clone())?;
+        DecodedAccount::Nonce(NonceAccountOutput::from((pubkey, nonce)).with_title("NONCE ACCOUNT"))
+    } else if *owner == stake::id() {
+        // Activation depends on the stake history and clock sysvars
+        DecodedAccount::Stake(fetch_stake_account(ctx, pubkey).await?)
+    } else if *owner == vote::id() {
+        DecodedAccount::Vote(decode_vote_account(pubkey, account)?)
+    } else if is_token_program(owner) {
+        DecodedAccount::Token(decode_token_state(ctx, account).await?)
+    } else if *owner == bpf_loader_upgradeable::id() {
+        DecodedAccount::UpgradeableLoader(decode_upgradeable_loader(ctx, account).await?)
+    } else if *owner == address_lookup_table::id() {
+        DecodedAccount::AddressLookupTable(parse_address_lookup_table(&account.data)?)
+    } else if *owner == sysvar::id() {
+        DecodedAccount::Sysvar(parse_sysvar(&account.data, pubkey)?)
+    } else {
+        bail!("No decoder for accounts owned by {owner}");
+    };
+
+    Ok(decoded)
+}
+
+/// An account of the upgradeable BPF loader. Program accounts are shown with
+/// the authority and deploy slot of their program data account.
+#[derive(Debug, Serialize)]
+#[serde(
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    tag = "type"
+)]
+pub enum UpgradeableLoaderAccount {
+    Uninitialized,
+    Buffer {
+        authority: Option<String>,
+    },
+    Program {
+        program_data: String,
+        /// `None` once the program is closed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        deployment: Option<ProgramDeployment>,
+    },
+    ProgramData(ProgramDeployment),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramDeployment {
+    /// `None` for immutable programs
+    pub authority: Option<String>,
+    pub last_deploy_slot: u64,
+    pub program_length: usize,
+}
+
+impl ProgramDeployment {
+    fn from_program_data(data: &[u8]) -> anyhow::Result<Self> {
+        let UpgradeableLoaderState::ProgramData {
+            slot,
+            upgrade_authority_address,
+        } = bincode_deserialize(data, "program data account")?
+        else {
+            bail!("This account is not a program data account");
+        };
+
+        Ok(Self {
+            authority: upgrade_authority_address.map(|authority| authority.to_string()),
+            last_deploy_slot: slot,
+            program_length: data
+                .len()
+                .saturating_sub(UpgradeableLoaderState::size_of_programdata_metadata()),
+        })
+    }
+
+    fn fields(&self, table: &mut TableView) {
+        table
+            .field(
+                "Upgrade Authority",
+                self.authority.as_deref().unwrap_or("None (immutable)"),
+            )
+            .field("Last Deploy Slot", self.last_deploy_slot)
+            .field("Program Length", format!("{} bytes", self.program_length));
+    }
+}
+
+impl UpgradeableLoaderAccount {
+    fn table(&self) -> TableView {
+        match self {
+            UpgradeableLoaderAccount::Uninitialized => {
+                let mut table = TableView::key_value("UPGRADEABLE LOADER ACCOUNT");
+                table.field("State", "Uninitialized");
+                table
+            }
+            UpgradeableLoaderAccount::Buffer { authority } => {
+                let mut table = TableView::key_value("PROGRAM BUFFER");
+                table.field("Authority", authority.as_deref().unwrap_or("None"));
+                table
+            }
+            UpgradeableLoaderAccount::Program {
+                program_data,
+                deployment,
+            } => {
+                let mut table = TableView::key_value("PROGRAM");
+                table.field("Program Data", program_data);
+                match deployment {
+                    Some(deployment) => deployment.fields(&mut table),
+                    None => {
+                        table.field("State", "Closed");
+                    }
+                }
+                table
+            }
+            UpgradeableLoaderAccount::ProgramData(deployment) => {
+                let mut table = TableView::key_value("PROGRAM DATA");
+                deployment.fields(&mut table);
+                table
+            }
+        }
+    }
+}
+
+async fn decode_upgradeable_loader(
+    ctx: &ScillaContext,
+    account: &Account,
+) -> anyhow::Result<UpgradeableLoaderAccount> {
+    let state: UpgradeableLoaderState =
+        bincode_deserialize(&account.data, "upgradeable loader account")?;
+
+    let decoded = match state {
+        UpgradeableLoaderState::Uninitialized => UpgradeableLoaderAccount::Uninitialized,
+        UpgradeableLoaderState::Buffer { authority_address } => UpgradeableLoaderAccount::Buffer {
+            authority: authority_address.map(|authority| authority.to_string()),
+        },
+        UpgradeableLoaderState::Program {
+            programdata_address,
+        } => {
+            let program_data = ctx
+                .rpc()
+                .get_account_with_commitment(&programdata_address, ctx.rpc().commitment())
+                .await?
+                .value;
+            UpgradeableLoaderAccount::Program {
+                program_data: programdata_address.to_string(),
+                deployment: program_data
+                    .map(|program_data| ProgramDeployment::from_program_data(&program_data.data))
+                    .transpose()?,
+            }
+        }
+        UpgradeableLoaderState::ProgramData { .. } => UpgradeableLoaderAccount::ProgramData(
+            ProgramDeployment::from_program_data(&account.data)?,
+        ),
+    };
+
+    Ok(decoded)
+}
+
+fn lookup_table_tables(lookup_table: &LookupTableAccountType) -> Vec<TableView> {
+    let mut table = TableView::key_value("ADDRESS LOOKUP TABLE");
+    let LookupTableAccountType::LookupTable(lookup_table) = lookup_table else {
+        table.field("State", "Uninitialized");
+        return vec![table];
+    };
+
+    let deactivation_slot = if lookup_table.deactivation_slot == u64::MAX.to_string() {
+        "Active".to_string()
+    } else {
+        lookup_table.deactivation_slot.clone()
+    };
+    table
+        .field(
+            "Authority",
+            lookup_table.authority.as_deref().unwrap_or("None (frozen)"),
+        )
+        .field("Deactivation Slot", deactivation_slot)
+        .field("Last Extended Slot", &lookup_table.last_extended_slot)
+        .field(
+            "Last Extended Start Index",
+            lookup_table.last_extended_slot_start_index,
+        )
+        .field("Addresses", lookup_table.addresses.len());
+
+    let mut addresses = TableView::new("ADDRESSES", ["Index", "Address"]);
+    for (idx, address) in lookup_table.addresses.iter().enumerate() {
+        addresses.row([idx.to_string(), address.clone()]);
+    }
+
+    vec![table, addresses]
+}
+
+/// Sysvars are laid out from their JSON form: plain sysvars as fields, and
+/// list sysvars such as slot hashes as one row per entry.
+fn sysvar_tables(sysvar: &SysvarAccountType) -> Vec<TableView> {
+    let value = serde_json::to_value(sysvar).unwrap_or(Value::Null);
+    let mut table = TableView::key_value("SYSVAR");
+    table.field(
+        "Sysvar",
+        value["type"].as_str().map(humanize).unwrap_or_default(),
+    );
+
+    let Value::Array(entries) = &value["info"] else {
+        let mut fields = Vec::new();
+        flatten_fields(None, &value["info"], &mut fields);
+        for (field, value) in fields {
+            table.field(field, shorten(value));
+        }
+        return vec![table];
+    };
+
+    let entry_fields = |entry: &Value| {
+        let mut fields = Vec::new();
+        flatten_fields(None, entry, &mut fields);
+        fields
+    };
+    let header = entries.first().map(entry_fields).unwrap_or_default();
+
+    if entries.len() > MAX_LISTED_ENTRIES {
+        table.field(
+            "Entries",
+            format!("{} (latest {MAX_LISTED_ENTRIES} listed)", entries.len()),
+        );
+    } else {
+        table.field("Entries", entries.len());
+    }
+
+    let mut list = TableView::new("ENTRIES", header.into_iter().map(|(field, _)| field));
+    for entry in entries.iter().take(MAX_LISTED_ENTRIES) {
+        list.row(entry_fields(entry).into_iter().map(|(_, value)| value));
+    }
+
+    vec![table, list]
+}
+
+fn shorten(value: String) -> String {
+    if value.chars().count() <= MAX_FIELD_CHARS {
+        return value;
+    }
+
+    let head: String = value.chars().take(MAX_FIELD_CHARS).collect();
+    format!("{head}… ({} chars)", value.chars().count())
+}
+
+/// Account data shown as-is, for programs Scilla cannot decode.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawAccountData {
+    pub encoding: RawEncoding,
+    pub data: String,
+    #[serde(skip)]
+    bytes: Vec<u8>,
+}
+
+impl RawAccountData {
+    pub fn new(data: &[u8], encoding: RawEncoding) -> Self {
+        Self {
+            encoding,
+            data: encoding.encode(data),
+            bytes: data.to_vec(),
+        }
+    }
+
+    /// Hex is laid out 32 bytes per row with offsets, base64 as a single
+    /// value. Either way only the first [`MAX_RAW_TABLE_BYTES`] are drawn.
+    fn table(&self) -> TableView {
+        let shown = &self.bytes[..self.bytes.len().min(MAX_RAW_TABLE_BYTES)];
+        let mut table = match self.encoding {
+            RawEncoding::Hex => {
+                let mut table = TableView::new("RAW DATA (HEX)", ["Offset", "Bytes"]);
+                for (idx, chunk) in shown.chunks(32).enumerate() {
+                    table.row([format!("{:#06x}", idx * 32), RawEncoding::Hex.encode(chunk)]);
+                }
+                table
+            }
+            RawEncoding::Base64 => {
+                let mut table = TableView::key_value("RAW DATA (BASE64)");
+                table.field("Data", RawEncoding::Base64.encode(shown));
+                table
+            }
+        };
+
+        let hidden = self.bytes.len() - shown.len();
+        if hidden > 0 {
+            table.row([
+                "…".to_string(),
+                format!("{hidden} more bytes, use --output json for all of them"),
+            ]);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_account_decoder::parse_sysvar::UiSlotHashEntry, solana_hash::Hash};
+
+    #[test]
+    fn test_raw_data_hex_rows_are_offset_and_capped() {
+        let raw = RawAccountData::new(&[0xab; MAX_RAW_TABLE_BYTES + 5], RawEncoding::Hex);
+        assert_eq!(raw.data.len(), (MAX_RAW_TABLE_BYTES + 5) * 2);
+
+        let csv = raw.table().to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("Offset,Bytes"));
+        assert_eq!(
+            lines.next(),
+            Some(format!("0x0000,{}", "ab".repeat(32)).as_str())
+        );
+        assert!(csv.contains("0x03e0,"));
+        assert!(!csv.contains("0x0400,"));
+        assert!(csv.ends_with("…,\"5 more bytes, use --output json for all of them\"\n"));
+    }
+
+    #[test]
+    fn test_raw_data_base64() {
+        let raw = RawAccountData::new(b"scilla", RawEncoding::Base64);
+        assert_eq!(raw.data, "c2NpbGxh");
+        assert_eq!(
+            serde_json::to_string(&raw).unwrap(),
+            r#"{"encoding":"base64","data":"c2NpbGxh"}"#
+        );
+    }
+
+    #[test]
+    fn test_list_sysvar_tables_cap_entries() {
+        let entries = (0..40)
+            .map(|slot| UiSlotHashEntry {
+                slot,
+                hash: Hash::default().to_string(),
+            })
+            .collect();
+        let tables = sysvar_tables(&SysvarAccountType::SlotHashes(entries));
+
+        assert_eq!(tables.len(), 2);
+        let summary = tables[0].to_csv();
+        assert!(summary.contains("Sysvar,Slot Hashes"));
+        assert!(summary.contains("Entries,40 (latest 32 listed)"));
+
+        let list = tables[1].to_csv();
+        assert!(list.starts_with(&format!("Hash,Slot\n{},0\n", Hash::default())));
+        assert_eq!(list.lines().count(), MAX_LISTED_ENTRIES + 1);
+    }
+
+    #[test]
+    fn test_program_deployment_from_program_data() -> anyhow::Result<()> {
+        let authority = Pubkey::new_unique();
+        let mut data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 42,
+            upgrade_authority_address: Some(authority),
+        })?;
+        data.resize(
+            UpgradeableLoaderState::size_of_programdata_metadata() + 100,
+            0,
+        );
+
+        let deployment = ProgramDeployment::from_program_data(&data)?;
+        assert_eq!(deployment.authority, Some(authority.to_string()));
+        assert_eq!(deployment.last_deploy_slot, 42);
+        assert_eq!(deployment.program_length, 100);
+
+        let buffer = bincode::serialize(&UpgradeableLoaderState::Buffer {
+            authority_address: None,
+        })?;
+        assert!(ProgramDeployment::from_program_data(&buffer).is_err());
+
+        Ok(())
+    }
+}
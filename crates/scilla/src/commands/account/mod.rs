@@ -0,0 +1,955 @@
+use {
+    crate::{
+        address_book::{AddressLabels, with_label},
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
+        context::ScillaContext,
+        misc::{
+            helpers::{
+                SolAmount, build_and_send_tx, check_minimum_balance, lamports_to_sol, read_signer,
+                sol_to_lamports,
+            },
+            nonce::{NonceAccount, load_nonce_account},
+        },
+        output::{CommandOutput, TableCell, TableView},
+        prompt::{
+            prompt_confirmation, prompt_input_data, prompt_keypair_path, prompt_pubkey,
+            prompt_select_data,
+        },
+        ui::render_with_spinner,
+    },
+    anyhow::{anyhow, bail},
+    clap::{Subcommand, ValueEnum},
+    console::style,
+    serde::Serialize,
+    solana_keypair::Signer,
+    solana_nonce::state::State as NonceState,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::config::{RpcLargestAccountsConfig, RpcLargestAccountsFilter},
+    solana_system_interface::instruction::{self as system_instruction, transfer},
+    std::{fmt, path::PathBuf},
+};
+
+mod decode;
+
+pub use decode::{DecodedAccount, RawEncoding, decode_account};
+
+/// Commands related to wallet or account management
+#[derive(Debug, Clone, Copy)]
+pub enum AccountCommand {
+    FetchAccount,
+    Balance,
+    Transfer,
+    Airdrop,
+    LargestAccounts,
+    NonceAccount,
+    CreateNonce,
+    AdvanceNonce,
+    WithdrawNonce,
+    AuthorizeNonce,
+    UpgradeNonce,
+    Rent,
+}
+
+impl fmt::Display for AccountCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = match self {
+            AccountCommand::FetchAccount => "Fetch account",
+            AccountCommand::Balance => "Check balance",
+            AccountCommand::Transfer => "Transfer SOL",
+            AccountCommand::Airdrop => "Request airdrop",
+            AccountCommand::LargestAccounts => "View largest accounts",
+            AccountCommand::NonceAccount => "View nonce account",
+            AccountCommand::CreateNonce => "Create nonce account",
+            AccountCommand::AdvanceNonce => "Advance nonce",
+            AccountCommand::WithdrawNonce => "Withdraw from nonce account",
+            AccountCommand::AuthorizeNonce => "Change nonce authority",
+            AccountCommand::UpgradeNonce => "Upgrade nonce account",
+            AccountCommand::Rent => "Check rent",
+        };
+        write!(f, "{command}")
+    }
+}
+
+impl Command for AccountCommand {
+    fn spinner_msg(&self) -> &'static str {
+        match self {
+            AccountCommand::FetchAccount => "Fetching account…",
+            AccountCommand::Balance => "Checking SOL balance…",
+            AccountCommand::Transfer => "Sending SOL…",
+            AccountCommand::Airdrop => "Requesting SOL on devnet/testnet…",
+            AccountCommand::LargestAccounts => "Fetching largest accounts on the cluster…",
+            AccountCommand::NonceAccount => "Inspecting or managing durable nonces…",
+            AccountCommand::CreateNonce => "Creating nonce account…",
+            AccountCommand::AdvanceNonce => "Advancing nonce…",
+            AccountCommand::WithdrawNonce => "Withdrawing from nonce account…",
+            AccountCommand::AuthorizeNonce => "Changing nonce authority…",
+            AccountCommand::UpgradeNonce => "Upgrading nonce account…",
+            AccountCommand::Rent => "Checking rent…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            AccountCommand::FetchAccount => AccountArgs::Fetch {
+                pubkey: None,
+                encoding: RawEncoding::default(),
+            },
+            AccountCommand::Balance => AccountArgs::Balance { pubkey: None },
+            AccountCommand::Transfer => AccountArgs::Transfer {
+                to: None,
+                amount: None,
+            },
+            AccountCommand::Airdrop => AccountArgs::Airdrop,
+            AccountCommand::LargestAccounts => AccountArgs::Largest { filter: None },
+            AccountCommand::NonceAccount => AccountArgs::Nonce { pubkey: None },
+            AccountCommand::CreateNonce => AccountArgs::CreateNonce {
+                nonce_account_keypair: None,
+                amount: None,
+                authority: None,
+            },
+            AccountCommand::AdvanceNonce => AccountArgs::AdvanceNonce {
+                pubkey: None,
+                authority_keypair: None,
+            },
+            AccountCommand::WithdrawNonce => AccountArgs::WithdrawNonce {
+                pubkey: None,
+                recipient: None,
+                amount: None,
+                authority_keypair: None,
+                yes: false,
+            },
+            AccountCommand::AuthorizeNonce => AccountArgs::AuthorizeNonce {
+                pubkey: None,
+                new_authority: None,
+                authority_keypair: None,
+            },
+            AccountCommand::UpgradeNonce => AccountArgs::UpgradeNonce { pubkey: None },
+            AccountCommand::Rent => AccountArgs::Rent { bytes: None },
+        };
+
+        Box::pin(async move { CommandFlow::from_run(args.run(ctx).await) })
+    }
+}
+
+/// The Account menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Account").commands([
+        AccountCommand::FetchAccount,
+        AccountCommand::Balance,
+        AccountCommand::Transfer,
+        AccountCommand::Airdrop,
+        AccountCommand::LargestAccounts,
+        AccountCommand::NonceAccount,
+        AccountCommand::CreateNonce,
+        AccountCommand::AdvanceNonce,
+        AccountCommand::WithdrawNonce,
+        AccountCommand::AuthorizeNonce,
+        AccountCommand::UpgradeNonce,
+        AccountCommand::Rent,
+    ])
+}
+
+/// Non-interactive arguments for [`AccountCommand`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum AccountArgs {
+    /// Fetch account info, decoded by the program that owns the account
+    Fetch {
+        pubkey: Option<Pubkey>,
+        /// Encoding for account data no decoder understands
+        #[arg(long, value_enum, default_value_t)]
+        encoding: RawEncoding,
+    },
+    /// Check SOL balance
+    Balance { pubkey: Option<Pubkey> },
+    /// Transfer SOL from the configured keypair
+    Transfer {
+        /// Recipient pubkey
+        #[arg(long)]
+        to: Option<Pubkey>,
+        /// Amount in SOL
+        #[arg(long)]
+        amount: Option<SolAmount>,
+    },
+    /// Request a 1 SOL airdrop on devnet/testnet
+    Airdrop,
+    /// View the largest accounts on the cluster
+    Largest {
+        #[arg(long, value_enum)]
+        filter: Option<LargestAccountsFilter>,
+    },
+    /// View a nonce account
+    Nonce { pubkey: Option<Pubkey> },
+    /// Create a durable nonce account
+    CreateNonce {
+        #[arg(long)]
+        nonce_account_keypair: Option<PathBuf>,
+        /// Amount to fund the nonce account with, in SOL
+        #[arg(long)]
+        amount: Option<SolAmount>,
+        /// Nonce authority pubkey
+        #[arg(long)]
+        authority: Option<Pubkey>,
+    },
+    /// Advance a nonce account to a new blockhash
+    AdvanceNonce {
+        pubkey: Option<Pubkey>,
+        #[arg(long)]
+        authority_keypair: Option<PathBuf>,
+    },
+    /// Withdraw SOL from a nonce account; withdrawing everything closes it
+    WithdrawNonce {
+        pubkey: Option<Pubkey>,
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        /// Amount to withdraw in SOL
+        #[arg(long)]
+        amount: Option<SolAmount>,
+        #[arg(long)]
+        authority_keypair: Option<PathBuf>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Change the authority of a nonce account
+    AuthorizeNonce {
+        pubkey: Option<Pubkey>,
+        #[arg(long)]
+        new_authority: Option<Pubkey>,
+        #[arg(long)]
+        authority_keypair: Option<PathBuf>,
+    },
+    /// Upgrade a legacy nonce account to the current format
+    UpgradeNonce { pubkey: Option<Pubkey> },
+    /// Check the rent-exempt minimum for a data size
+    Rent { bytes: Option<usize> },
+}
+
+impl AccountArgs {
+    pub fn command(&self) -> AccountCommand {
+        match self {
+            AccountArgs::Fetch { .. } => AccountCommand::FetchAccount,
+            AccountArgs::Balance { .. } => AccountCommand::Balance,
+            AccountArgs::Transfer { .. } => AccountCommand::Transfer,
+            AccountArgs::Airdrop => AccountCommand::Airdrop,
+            AccountArgs::Largest { .. } => AccountCommand::LargestAccounts,
+            AccountArgs::Nonce { .. } => AccountCommand::NonceAccount,
+            AccountArgs::CreateNonce { .. } => AccountCommand::CreateNonce,
+            AccountArgs::AdvanceNonce { .. } => AccountCommand::AdvanceNonce,
+            AccountArgs::WithdrawNonce { .. } => AccountCommand::WithdrawNonce,
+            AccountArgs::AuthorizeNonce { .. } => AccountCommand::AuthorizeNonce,
+            AccountArgs::UpgradeNonce { .. } => AccountCommand::UpgradeNonce,
+            AccountArgs::Rent { .. } => AccountCommand::Rent,
+        }
+    }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
+        match self {
+            AccountArgs::Fetch { pubkey, encoding } => {
+                let pubkey = pubkey.map_or_else(|| prompt_pubkey("Enter Pubkey:", ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, fetch_acc_data(ctx, &pubkey, encoding))
+                    .await?;
+            }
+            AccountArgs::Balance { pubkey } => {
+                let pubkey = pubkey.map_or_else(|| prompt_pubkey("Enter Pubkey :", ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, fetch_account_balance(ctx, &pubkey)).await?;
+            }
+            AccountArgs::Transfer { to, amount } => {
+                let to = to.map_or_else(|| prompt_pubkey("Enter recipient Pubkey:", ctx), Ok)?;
+                let amount = amount.map_or_else(|| prompt_input_data("Enter amount (SOL):"), Ok)?;
+                render_with_spinner(ctx, spinner_msg, transfer_sol(ctx, to, amount)).await?;
+            }
+            AccountArgs::Airdrop => {
+                render_with_spinner(ctx, spinner_msg, request_sol_airdrop(ctx)).await?;
+            }
+            AccountArgs::Largest { filter } => {
+                let filter = filter.map_or_else(
+                    || prompt_select_data("Filter accounts by:", LargestAccountsFilter::all()),
+                    Ok,
+                )?;
+                render_with_spinner(ctx, spinner_msg, fetch_largest_accounts(ctx, filter)).await?;
+            }
+            AccountArgs::Nonce { pubkey } => {
+                let pubkey =
+                    pubkey.map_or_else(|| prompt_pubkey("Enter nonce account pubkey:", ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, fetch_nonce_account(ctx, &pubkey)).await?;
+            }
+            AccountArgs::CreateNonce {
+                nonce_account_keypair,
+                amount,
+                authority,
+            } => {
+                let nonce_account_keypair_path = nonce_account_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Nonce Account Keypair Path:", ctx),
+                    Ok,
+                )?;
+                let amount =
+                    amount.map_or_else(|| prompt_input_data("Enter amount to fund (SOL):"), Ok)?;
+                let authority = authority.map_or_else(
+                    || {
+                        if prompt_confirmation(&format!(
+                            "Make {} the nonce authority? (y/n):",
+                            ctx.pubkey()
+                        ))? {
+                            Ok(*ctx.pubkey())
+                        } else {
+                            prompt_pubkey("Enter nonce authority Pubkey:", ctx)
+                        }
+                    },
+                    Ok,
+                )?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    create_nonce_account(ctx, nonce_account_keypair_path, amount, authority),
+                )
+                .await?;
+            }
+            AccountArgs::AdvanceNonce {
+                pubkey,
+                authority_keypair,
+            } => {
+                let pubkey =
+                    pubkey.map_or_else(|| prompt_pubkey("Enter nonce account pubkey:", ctx), Ok)?;
+                let authority_keypair_path = authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Nonce Authority Keypair Path:", ctx),
+                    Ok,
+                )?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    advance_nonce_account(ctx, &pubkey, authority_keypair_path),
+                )
+                .await?;
+            }
+            AccountArgs::WithdrawNonce {
+                pubkey,
+                recipient,
+                amount,
+                authority_keypair,
+                yes,
+            } => {
+                let pubkey =
+                    pubkey.map_or_else(|| prompt_pubkey("Enter nonce account pubkey:", ctx), Ok)?;
+                let recipient =
+                    recipient.map_or_else(|| prompt_pubkey("Enter Recipient Address:", ctx), Ok)?;
+                let amount = amount
+                    .map_or_else(|| prompt_input_data("Enter Amount to Withdraw (SOL):"), Ok)?;
+                let authority_keypair_path = authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Nonce Authority Keypair Path:", ctx),
+                    Ok,
+                )?;
+
+                if !yes
+                    && !prompt_confirmation(&format!(
+                        "Are you sure you want to withdraw {} SOL?",
+                        amount.value()
+                    ))?
+                {
+                    println!("{}", style("Withdrawal cancelled.").yellow());
+                    return Ok(());
+                }
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    withdraw_from_nonce_account(
+                        ctx,
+                        &pubkey,
+                        &recipient,
+                        amount,
+                        authority_keypair_path,
+                    ),
+                )
+                .await?;
+            }
+            AccountArgs::AuthorizeNonce {
+                pubkey,
+                new_authority,
+                authority_keypair,
+            } => {
+                let pubkey =
+                    pubkey.map_or_else(|| prompt_pubkey("Enter nonce account pubkey:", ctx), Ok)?;
+                let new_authority = new_authority.map_or_else(
+                    || prompt_pubkey("Enter new nonce authority Pubkey:", ctx),
+                    Ok,
+                )?;
+                let authority_keypair_path = authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Current Nonce Authority Keypair Path:", ctx),
+                    Ok,
+                )?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    authorize_nonce_account(ctx, &pubkey, &new_authority, authority_keypair_path),
+                )
+                .await?;
+            }
+            AccountArgs::UpgradeNonce { pubkey } => {
+                let pubkey =
+                    pubkey.map_or_else(|| prompt_pubkey("Enter nonce account pubkey:", ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, upgrade_nonce_account(ctx, &pubkey)).await?;
+            }
+            AccountArgs::Rent { bytes } => {
+                // get the rent for data bytes used in account
+                let bytes =
+                    bytes.map_or_else(|| prompt_input_data("Enter data size in bytes:"), Ok)?;
+                render_with_spinner(ctx, spinner_msg, fetch_rent(ctx, bytes)).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LargestAccountsFilter {
+    All,
+    Circulating,
+    NonCirculating,
+}
+
+impl LargestAccountsFilter {
+    fn all() -> Vec<Self> {
+        vec![
+            LargestAccountsFilter::All,
+            LargestAccountsFilter::Circulating,
+            LargestAccountsFilter::NonCirculating,
+        ]
+    }
+}
+
+impl fmt::Display for LargestAccountsFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            LargestAccountsFilter::All => "All",
+            LargestAccountsFilter::Circulating => "Circulating",
+            LargestAccountsFilter::NonCirculating => "Non-Circulating",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AirdropOutput {
+    pub signature: String,
+}
+
+impl CommandOutput for AirdropOutput {
+    fn render_table(&self) {
+        println!(
+            "{} {}",
+            style("Airdrop requested successfully!").green().bold(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+    }
+}
+
+async fn request_sol_airdrop(ctx: &ScillaContext) -> anyhow::Result<AirdropOutput> {
+    // request an airdrop worth of 1 SOL
+    let signature = ctx
+        .rpc()
+        .request_airdrop(ctx.pubkey(), sol_to_lamports(1.0))
+        .await
+        .map_err(|err| anyhow!("Airdrop failed: {err}"))?;
+
+    Ok(AirdropOutput {
+        signature: signature.to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfoOutput {
+    pub pubkey: String,
+    pub lamports: u64,
+    pub data_length: usize,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    /// The account data decoded by its owner program
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<DecodedAccount>,
+}
+
+impl CommandOutput for AccountInfoOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("ACCOUNT INFO");
+        table
+            .field("Lamports", self.lamports)
+            .field("Data Length", self.data_length)
+            .field("Owner", &self.owner)
+            .field("Executable", self.executable)
+            .field("Rent Epoch", self.rent_epoch);
+
+        let mut tables = vec![table];
+        if let Some(decoded) = &self.decoded {
+            tables.extend(decoded.tables());
+        }
+        tables
+    }
+}
+
+async fn fetch_acc_data(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+    encoding: RawEncoding,
+) -> anyhow::Result<AccountInfoOutput> {
+    let acc = ctx.rpc().get_account(pubkey).await?;
+    let decoded = decode_account(ctx, pubkey, &acc, encoding).await;
+
+    Ok(AccountInfoOutput {
+        pubkey: pubkey.to_string(),
+        lamports: acc.lamports,
+        data_length: acc.data.len(),
+        owner: acc.owner.to_string(),
+        executable: acc.executable,
+        rent_epoch: acc.rent_epoch,
+        decoded,
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceOutput {
+    pub pubkey: String,
+    pub lamports: u64,
+    pub sol: f64,
+}
+
+impl CommandOutput for BalanceOutput {
+    fn render_table(&self) {
+        println!(
+            "{} {}",
+            style("Account balance in SOL:").green().bold(),
+            style(format!("{:#?}", self.sol)).cyan()
+        );
+    }
+}
+
+async fn fetch_account_balance(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+) -> anyhow::Result<BalanceOutput> {
+    let acc = ctx.rpc().get_account(pubkey).await?;
+
+    Ok(BalanceOutput {
+        pubkey: pubkey.to_string(),
+        lamports: acc.lamports,
+        sol: lamports_to_sol(acc.lamports),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LargestAccount {
+    pub address: String,
+    pub lamports: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LargestAccountsOutput {
+    pub accounts: Vec<LargestAccount>,
+    #[serde(skip_serializing_if = "AddressLabels::is_empty")]
+    pub labels: AddressLabels,
+}
+
+impl CommandOutput for LargestAccountsOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::new("LARGEST ACCOUNTS", ["#", "Address", "Balance (SOL)"]);
+        for (idx, account) in self.accounts.iter().enumerate() {
+            let balance_sol = lamports_to_sol(account.lamports);
+            table.row([
+                TableCell::new(idx + 1),
+                TableCell::new(with_label(&account.address, &self.labels)),
+                TableCell::new(format!("{balance_sol:.2}")),
+            ]);
+        }
+
+        vec![table]
+    }
+}
+
+async fn fetch_largest_accounts(
+    ctx: &ScillaContext,
+    filter: LargestAccountsFilter,
+) -> anyhow::Result<LargestAccountsOutput> {
+    let filter = match filter {
+        LargestAccountsFilter::All => None,
+        LargestAccountsFilter::Circulating => Some(RpcLargestAccountsFilter::Circulating),
+        LargestAccountsFilter::NonCirculating => Some(RpcLargestAccountsFilter::NonCirculating),
+    };
+
+    let config = RpcLargestAccountsConfig {
+        commitment: Some(ctx.rpc().commitment()),
+        filter,
+        sort_results: Some(true),
+    };
+
+    let response = ctx.rpc().get_largest_accounts_with_config(config).await?;
+    let labels = ctx.address_book().labels(
+        response
+            .value
+            .iter()
+            .map(|account| account.address.as_str()),
+    );
+
+    Ok(LargestAccountsOutput {
+        accounts: response
+            .value
+            .into_iter()
+            .map(|account| LargestAccount {
+                address: account.address,
+                lamports: account.lamports,
+            })
+            .collect(),
+        labels,
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceAccountOutput {
+    #[serde(skip)]
+    title: &'static str,
+    #[serde(skip)]
+    success_msg: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub address: String,
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub blockhash: String,
+    pub authority: String,
+    pub legacy: bool,
+}
+
+impl NonceAccountOutput {
+    fn with_title(mut self, title: &'static str) -> Self {
+        self.title = title;
+        self
+    }
+
+    fn with_signature(mut self, success_msg: &'static str, signature: impl ToString) -> Self {
+        self.success_msg = Some(success_msg);
+        self.signature = Some(signature.to_string());
+        self
+    }
+}
+
+impl CommandOutput for NonceAccountOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value(self.title);
+        table
+            .field("Address", &self.address)
+            .field("Lamports", self.lamports)
+            .field(
+                "Balance (SOL)",
+                format!("{:.6}", lamports_to_sol(self.lamports)),
+            )
+            .field("Owner", &self.owner)
+            .field("Executable", self.executable)
+            .field("Rent Epoch", self.rent_epoch)
+            .field("Nonce blockhash", &self.blockhash)
+            .field("Authority", &self.authority)
+            .field("Version", if self.legacy { "Legacy" } else { "Current" });
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        if let (Some(success_msg), Some(signature)) = (self.success_msg, &self.signature) {
+            println!(
+                "{}\n{}",
+                style(success_msg).yellow().bold(),
+                style(format!("Signature: {signature}")).green()
+            );
+        }
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+impl From<(&Pubkey, NonceAccount)> for NonceAccountOutput {
+    fn from((pubkey, nonce): (&Pubkey, NonceAccount)) -> Self {
+        Self {
+            title: "NONCE ACCOUNT INFO",
+            success_msg: None,
+            signature: None,
+            address: pubkey.to_string(),
+            lamports: nonce.account.lamports,
+            owner: nonce.account.owner.to_string(),
+            executable: nonce.account.executable,
+            rent_epoch: nonce.account.rent_epoch,
+            blockhash: nonce.data.blockhash().to_string(),
+            authority: nonce.data.authority.to_string(),
+            legacy: nonce.legacy,
+        }
+    }
+}
+
+async fn fetch_nonce_account(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+) -> anyhow::Result<NonceAccountOutput> {
+    let nonce = load_nonce_account(ctx, pubkey).await?;
+
+    Ok((pubkey, nonce).into())
+}
+
+async fn create_nonce_account(
+    ctx: &ScillaContext,
+    nonce_account_keypair_path: PathBuf,
+    amount_sol: SolAmount,
+    authority: Pubkey,
+) -> anyhow::Result<NonceAccountOutput> {
+    let nonce_keypair = read_signer(ctx, nonce_account_keypair_path)?;
+    let nonce_pubkey = nonce_keypair.pubkey();
+    if &nonce_pubkey == ctx.pubkey() {
+        bail!("Nonce account {nonce_pubkey} cannot be the same as fee payer account");
+    }
+
+    let lamports = amount_sol.to_lamports();
+    let rent_exempt = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(NonceState::size())
+        .await?;
+    if lamports < rent_exempt {
+        bail!(
+            "A nonce account needs at least {} SOL to be rent exempt, got {} SOL",
+            lamports_to_sol(rent_exempt),
+            amount_sol.value()
+        );
+    }
+    check_minimum_balance(ctx, ctx.pubkey(), lamports).await?;
+
+    let instructions =
+        system_instruction::create_nonce_account(ctx.pubkey(), &nonce_pubkey, &authority, lamports);
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.keypair(), &nonce_keypair]).await?;
+
+    Ok(fetch_nonce_account(ctx, &nonce_pubkey)
+        .await?
+        .with_title("NEW NONCE ACCOUNT")
+        .with_signature("Nonce account created successfully!", signature))
+}
+
+async fn advance_nonce_account(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    authority_keypair_path: PathBuf,
+) -> anyhow::Result<NonceAccountOutput> {
+    let authority = read_signer(ctx, authority_keypair_path)?;
+    check_nonce_authority(ctx, nonce_pubkey, &authority.pubkey()).await?;
+
+    let instruction = system_instruction::advance_nonce_account(nonce_pubkey, &authority.pubkey());
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), &authority]).await?;
+
+    Ok(fetch_nonce_account(ctx, nonce_pubkey)
+        .await?
+        .with_signature("Nonce advanced successfully!", signature))
+}
+
+async fn withdraw_from_nonce_account(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    recipient: &Pubkey,
+    amount_sol: SolAmount,
+    authority_keypair_path: PathBuf,
+) -> anyhow::Result<NonceAccountOutput> {
+    let authority = read_signer(ctx, authority_keypair_path)?;
+    let nonce = check_nonce_authority(ctx, nonce_pubkey, &authority.pubkey()).await?;
+
+    let lamports = amount_sol.to_lamports();
+    let balance = nonce.account.lamports;
+    if lamports > balance {
+        bail!(
+            "Nonce account only holds {} SOL, cannot withdraw {} SOL",
+            lamports_to_sol(balance),
+            amount_sol.value()
+        );
+    }
+
+    // Withdrawing everything closes the account; anything else has to leave
+    // it rent exempt
+    let closing = lamports == balance;
+    if !closing {
+        let rent_exempt = ctx
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(nonce.account.data.len())
+            .await?;
+        if balance - lamports < rent_exempt {
+            bail!(
+                "The nonce account must keep at least {} SOL to stay rent exempt. Withdraw {} SOL \
+                 or less, or the full {} SOL to close it",
+                lamports_to_sol(rent_exempt),
+                lamports_to_sol(balance - rent_exempt),
+                lamports_to_sol(balance)
+            );
+        }
+    }
+
+    let instruction = system_instruction::withdraw_nonce_account(
+        nonce_pubkey,
+        &authority.pubkey(),
+        recipient,
+        lamports,
+    );
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), &authority]).await?;
+
+    if closing {
+        // The account is gone, so show what it held before
+        let mut output = NonceAccountOutput::from((nonce_pubkey, nonce));
+        output.lamports = 0;
+        return Ok(output
+            .with_title("CLOSED NONCE ACCOUNT")
+            .with_signature("Nonce account closed successfully!", signature));
+    }
+
+    Ok(fetch_nonce_account(ctx, nonce_pubkey)
+        .await?
+        .with_signature("Withdrawal from nonce account successful!", signature))
+}
+
+async fn authorize_nonce_account(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    new_authority: &Pubkey,
+    authority_keypair_path: PathBuf,
+) -> anyhow::Result<NonceAccountOutput> {
+    let authority = read_signer(ctx, authority_keypair_path)?;
+    check_nonce_authority(ctx, nonce_pubkey, &authority.pubkey()).await?;
+
+    let instruction = system_instruction::authorize_nonce_account(
+        nonce_pubkey,
+        &authority.pubkey(),
+        new_authority,
+    );
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), &authority]).await?;
+
+    Ok(fetch_nonce_account(ctx, nonce_pubkey)
+        .await?
+        .with_signature("Nonce authority changed successfully!", signature))
+}
+
+async fn upgrade_nonce_account(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+) -> anyhow::Result<NonceAccountOutput> {
+    let nonce = load_nonce_account(ctx, nonce_pubkey).await?;
+    if !nonce.legacy {
+        bail!("Nonce account {nonce_pubkey} already uses the current format");
+    }
+
+    let instruction = system_instruction::upgrade_nonce_account(*nonce_pubkey);
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+
+    Ok(fetch_nonce_account(ctx, nonce_pubkey)
+        .await?
+        .with_signature("Nonce account upgraded successfully!", signature))
+}
+
+/// Loads the nonce account and makes sure `authority` can sign for it, so a
+/// wrong keypair fails before anything is sent.
+async fn check_nonce_authority(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    authority: &Pubkey,
+) -> anyhow::Result<NonceAccount> {
+    let nonce = load_nonce_account(ctx, nonce_pubkey).await?;
+    if &nonce.data.authority != authority {
+        bail!(
+            "{authority} is not the authority of nonce account {nonce_pubkey}, {} is",
+            nonce.data.authority
+        );
+    }
+
+    Ok(nonce)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOutput {
+    pub signature: String,
+    pub recipient: String,
+    pub amount_sol: f64,
+}
+
+impl CommandOutput for TransferOutput {
+    fn render_table(&self) {
+        println!(
+            "\n{} {}\n{}\n{}",
+            style("Transfer successful!").green().bold(),
+            style(format!("Amount: {} SOL", self.amount_sol)).cyan(),
+            style(format!("Signature: {}", self.signature)).yellow(),
+            style(format!("Recipient Address: {}", self.recipient)).yellow()
+        );
+    }
+}
+
+async fn transfer_sol(
+    ctx: &ScillaContext,
+    receiver: Pubkey,
+    amount: SolAmount,
+) -> anyhow::Result<TransferOutput> {
+    let lamports = amount.to_lamports();
+
+    // Validate transfer amount
+    let balance = ctx.rpc().get_balance(ctx.pubkey()).await?;
+    if lamports > balance {
+        bail!(
+            "Insufficient balance. You have {} SOL but tried to send {} SOL",
+            lamports_to_sol(balance),
+            amount.value()
+        );
+    }
+
+    let instruction = transfer(ctx.pubkey(), &receiver, lamports);
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+
+    Ok(TransferOutput {
+        signature: signature.to_string(),
+        recipient: receiver.to_string(),
+        amount_sol: amount.value(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RentOutput {
+    pub bytes: usize,
+    pub lamports: u64,
+}
+
+impl CommandOutput for RentOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("RENT EXEMPTION");
+        table
+            .field("Data Size", format!("{} bytes", self.bytes))
+            .field("Minimum Balance (lamports)", self.lamports)
+            .field(
+                "Minimum Balance (SOL)",
+                format!("{:.9}", lamports_to_sol(self.lamports)),
+            );
+
+        vec![table]
+    }
+}
+
+async fn fetch_rent(ctx: &ScillaContext, bytes: usize) -> anyhow::Result<RentOutput> {
+    let lamports = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(bytes)
+        .await?;
+
+    Ok(RentOutput { bytes, lamports })
+}
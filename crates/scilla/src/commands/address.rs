@@ -0,0 +1,279 @@
+use {
+    crate::{
+        address_book::address_book_path,
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
+        context::ScillaContext,
+        output::{CommandOutput, TableView},
+        prompt::{prompt_confirmation, prompt_input_data, prompt_pubkey, prompt_select_data},
+        ui::{print_error, render_output},
+    },
+    anyhow::{anyhow, bail},
+    clap::Subcommand,
+    console::style,
+    serde::Serialize,
+    solana_pubkey::Pubkey,
+    std::fmt,
+};
+
+/// Commands for the address book of saved pubkeys
+#[derive(Debug, Clone, Copy)]
+pub enum AddressCommand {
+    List,
+    Add,
+    Edit,
+    Remove,
+}
+
+impl fmt::Display for AddressCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = match self {
+            AddressCommand::List => "List addresses",
+            AddressCommand::Add => "Add address",
+            AddressCommand::Edit => "Edit address",
+            AddressCommand::Remove => "Remove address",
+        };
+        write!(f, "{command}")
+    }
+}
+
+impl Command for AddressCommand {
+    fn spinner_msg(&self) -> &'static str {
+        match self {
+            AddressCommand::List => "Listing saved addresses…",
+            AddressCommand::Add => "Saving address…",
+            AddressCommand::Edit => "Updating address…",
+            AddressCommand::Remove => "Removing address…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            AddressCommand::List => AddressArgs::List,
+            AddressCommand::Add => AddressArgs::Add {
+                alias: None,
+                pubkey: None,
+            },
+            AddressCommand::Edit => AddressArgs::Edit {
+                alias: None,
+                pubkey: None,
+            },
+            AddressCommand::Remove => AddressArgs::Remove {
+                alias: None,
+                yes: false,
+            },
+        };
+
+        // A typo in an alias should not end the interactive session
+        Box::pin(async move {
+            CommandFlow::from_run(args.run(ctx)).or_else(|e| {
+                print_error(e);
+                Ok(CommandFlow::Processed)
+            })
+        })
+    }
+}
+
+/// The Address Book menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Address Book").commands([
+        AddressCommand::List,
+        AddressCommand::Add,
+        AddressCommand::Edit,
+        AddressCommand::Remove,
+    ])
+}
+
+/// Non-interactive arguments for [`AddressCommand`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum AddressArgs {
+    /// List saved addresses
+    List,
+    /// Save a new address under an alias
+    Add {
+        alias: Option<String>,
+        pubkey: Option<Pubkey>,
+    },
+    /// Point an existing alias at a different address
+    Edit {
+        alias: Option<String>,
+        pubkey: Option<Pubkey>,
+    },
+    /// Remove an alias
+    Remove {
+        alias: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+impl AddressArgs {
+    pub fn run(self, ctx: &mut ScillaContext) -> anyhow::Result<()> {
+        match self {
+            AddressArgs::List => list_addresses(ctx),
+            AddressArgs::Add { alias, pubkey } => {
+                let alias = alias.map_or_else(|| prompt_input_data("Enter alias:"), Ok)?;
+                let pubkey = pubkey.map_or_else(|| prompt_pubkey("Enter Pubkey:", ctx), Ok)?;
+                add_address(ctx, &alias, pubkey)
+            }
+            AddressArgs::Edit { alias, pubkey } => {
+                let alias = alias.map_or_else(|| prompt_saved_alias(ctx), Ok)?;
+                let pubkey = pubkey.map_or_else(|| prompt_pubkey("Enter new Pubkey:", ctx), Ok)?;
+                edit_address(ctx, &alias, pubkey)
+            }
+            AddressArgs::Remove { alias, yes } => {
+                let alias = alias.map_or_else(|| prompt_saved_alias(ctx), Ok)?;
+                if !yes && !prompt_confirmation(&format!("Remove @{alias} from the address book?"))?
+                {
+                    println!("{}", style("Removal cancelled.").yellow());
+                    return Ok(());
+                }
+                remove_address(ctx, &alias)
+            }
+        }
+    }
+}
+
+fn prompt_saved_alias(ctx: &ScillaContext) -> anyhow::Result<String> {
+    let aliases = ctx.address_book().aliases();
+    if aliases.is_empty() {
+        bail!("The address book is empty. Add an address first");
+    }
+    prompt_select_data("Select alias:", aliases)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressEntry {
+    pub alias: String,
+    pub pubkey: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookOutput {
+    pub addresses: Vec<AddressEntry>,
+}
+
+impl CommandOutput for AddressBookOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::new("ADDRESS BOOK", ["Alias", "Pubkey"]);
+        for entry in &self.addresses {
+            table.row([format!("@{}", entry.alias), entry.pubkey.clone()]);
+        }
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        if self.addresses.is_empty() {
+            println!(
+                "{}",
+                style(format!(
+                    "No saved addresses in {}",
+                    address_book_path().display()
+                ))
+                .yellow()
+            );
+            return;
+        }
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+/// Result of a change to a single address book entry.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressEntryOutput {
+    #[serde(skip)]
+    pub message: &'static str,
+    pub alias: String,
+    pub pubkey: String,
+}
+
+impl CommandOutput for AddressEntryOutput {
+    fn render_table(&self) {
+        println!(
+            "{}\n{}",
+            style(self.message).green().bold(),
+            style(format!("@{} → {}", self.alias, self.pubkey)).cyan()
+        );
+    }
+}
+
+fn list_addresses(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let output = AddressBookOutput {
+        addresses: ctx
+            .address_book()
+            .iter()
+            .map(|(alias, pubkey)| AddressEntry {
+                alias: alias.clone(),
+                pubkey: pubkey.to_string(),
+            })
+            .collect(),
+    };
+
+    render_output(ctx, &output)
+}
+
+fn add_address(ctx: &mut ScillaContext, alias: &str, pubkey: Pubkey) -> anyhow::Result<()> {
+    let alias = alias.trim().trim_start_matches('@');
+    if ctx.address_book().get(alias).is_some() {
+        bail!("@{alias} already exists. Use edit to change it");
+    }
+
+    let book = ctx.address_book_mut();
+    book.insert(alias, pubkey)?;
+    book.save()?;
+
+    render_output(
+        ctx,
+        &AddressEntryOutput {
+            message: "Address saved!",
+            alias: alias.to_string(),
+            pubkey: pubkey.to_string(),
+        },
+    )
+}
+
+fn edit_address(ctx: &mut ScillaContext, alias: &str, pubkey: Pubkey) -> anyhow::Result<()> {
+    let alias = alias.trim().trim_start_matches('@');
+    if ctx.address_book().get(alias).is_none() {
+        bail!("No address saved as @{alias}");
+    }
+
+    let book = ctx.address_book_mut();
+    book.insert(alias, pubkey)?;
+    book.save()?;
+
+    render_output(
+        ctx,
+        &AddressEntryOutput {
+            message: "Address updated!",
+            alias: alias.to_string(),
+            pubkey: pubkey.to_string(),
+        },
+    )
+}
+
+fn remove_address(ctx: &mut ScillaContext, alias: &str) -> anyhow::Result<()> {
+    let alias = alias.trim().trim_start_matches('@');
+    let book = ctx.address_book_mut();
+    let pubkey = book
+        .remove(alias)
+        .ok_or_else(|| anyhow!("No address saved as @{alias}"))?;
+    book.save()?;
+
+    render_output(
+        ctx,
+        &AddressEntryOutput {
+            message: "Address removed!",
+            alias: alias.to_string(),
+            pubkey: pubkey.to_string(),
+        },
+    )
+}
@@ -1,15 +1,14 @@
 use {
     crate::{
-        commands::{
-            Command, CommandFlow,
-            navigation::{NavigationSection, NavigationTarget},
-        },
+        address_book::{AddressLabels, with_label},
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
         constants::LAMPORTS_PER_SOL,
         context::ScillaContext,
-        ui::show_spinner,
+        output::{CommandOutput, TableCell, TableView},
+        ui::render_with_spinner,
     },
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
+    clap::Subcommand,
+    serde::Serialize,
     std::{cmp::Reverse, fmt, ops::Div},
 };
 
@@ -24,23 +23,6 @@ pub enum ClusterCommand {
     SupplyInfo,
     Inflation,
     ClusterVersion,
-    GoBack,
-}
-
-impl ClusterCommand {
-    pub fn spinner_msg(&self) -> &'static str {
-        match self {
-            ClusterCommand::EpochInfo => "Fetching current epoch and progress…",
-            ClusterCommand::CurrentSlot => "Fetching latest confirmed slot…",
-            ClusterCommand::BlockHeight => "Fetching current block height…",
-            ClusterCommand::BlockTime => "Fetching block timestamp…",
-            ClusterCommand::Validators => "Fetching active validators…",
-            ClusterCommand::ClusterVersion => "Fetching cluster Solana version…",
-            ClusterCommand::SupplyInfo => "Fetching total and circulating supply…",
-            ClusterCommand::Inflation => "Fetching inflation parameters…",
-            ClusterCommand::GoBack => "Going back…",
-        }
-    }
 }
 
 impl fmt::Display for ClusterCommand {
@@ -54,50 +36,152 @@ impl fmt::Display for ClusterCommand {
             ClusterCommand::ClusterVersion => "Cluster Version",
             ClusterCommand::SupplyInfo => "Supply Info",
             ClusterCommand::Inflation => "Inflation",
-            ClusterCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
     }
 }
 
 impl Command for ClusterCommand {
-    async fn process_command(&self, ctx: &mut ScillaContext) -> anyhow::Result<CommandFlow> {
-        ctx.get_nav_context_mut()
-            .checked_push(NavigationSection::Cluster);
+    fn spinner_msg(&self) -> &'static str {
         match self {
-            ClusterCommand::EpochInfo => {
-                show_spinner(self.spinner_msg(), fetch_epoch_info(ctx)).await;
-            }
-            ClusterCommand::CurrentSlot => {
-                show_spinner(self.spinner_msg(), fetch_current_slot(ctx)).await;
+            ClusterCommand::EpochInfo => "Fetching current epoch and progress…",
+            ClusterCommand::CurrentSlot => "Fetching latest confirmed slot…",
+            ClusterCommand::BlockHeight => "Fetching current block height…",
+            ClusterCommand::BlockTime => "Fetching block timestamp…",
+            ClusterCommand::Validators => "Fetching active validators…",
+            ClusterCommand::ClusterVersion => "Fetching cluster Solana version…",
+            ClusterCommand::SupplyInfo => "Fetching total and circulating supply…",
+            ClusterCommand::Inflation => "Fetching inflation parameters…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            ClusterCommand::EpochInfo => ClusterArgs::EpochInfo,
+            ClusterCommand::CurrentSlot => ClusterArgs::CurrentSlot,
+            ClusterCommand::BlockHeight => ClusterArgs::BlockHeight,
+            ClusterCommand::BlockTime => ClusterArgs::BlockTime,
+            ClusterCommand::Validators => ClusterArgs::Validators,
+            ClusterCommand::SupplyInfo => ClusterArgs::Supply,
+            ClusterCommand::Inflation => ClusterArgs::Inflation,
+            ClusterCommand::ClusterVersion => ClusterArgs::Version,
+        };
+
+        Box::pin(async move { CommandFlow::from_run(args.run(ctx).await) })
+    }
+}
+
+/// The Cluster menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Cluster").commands([
+        ClusterCommand::EpochInfo,
+        ClusterCommand::CurrentSlot,
+        ClusterCommand::BlockHeight,
+        ClusterCommand::BlockTime,
+        ClusterCommand::Validators,
+        ClusterCommand::ClusterVersion,
+        ClusterCommand::SupplyInfo,
+        ClusterCommand::Inflation,
+    ])
+}
+
+/// Non-interactive arguments for [`ClusterCommand`].
+#[derive(Debug, Clone, Subcommand)]
+pub enum ClusterArgs {
+    /// Current epoch and progress
+    EpochInfo,
+    /// Latest confirmed slot
+    CurrentSlot,
+    /// Current block height
+    BlockHeight,
+    /// Timestamp of the latest block
+    BlockTime,
+    /// Top validators by stake
+    Validators,
+    /// Total and circulating supply
+    Supply,
+    /// Current inflation parameters
+    Inflation,
+    /// Solana version running on the cluster
+    Version,
+}
+
+impl ClusterArgs {
+    pub fn command(&self) -> ClusterCommand {
+        match self {
+            ClusterArgs::EpochInfo => ClusterCommand::EpochInfo,
+            ClusterArgs::CurrentSlot => ClusterCommand::CurrentSlot,
+            ClusterArgs::BlockHeight => ClusterCommand::BlockHeight,
+            ClusterArgs::BlockTime => ClusterCommand::BlockTime,
+            ClusterArgs::Validators => ClusterCommand::Validators,
+            ClusterArgs::Supply => ClusterCommand::SupplyInfo,
+            ClusterArgs::Inflation => ClusterCommand::Inflation,
+            ClusterArgs::Version => ClusterCommand::ClusterVersion,
+        }
+    }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
+        match self {
+            ClusterArgs::EpochInfo => {
+                render_with_spinner(ctx, spinner_msg, fetch_epoch_info(ctx)).await?;
             }
-            ClusterCommand::BlockHeight => {
-                show_spinner(self.spinner_msg(), fetch_block_height(ctx)).await;
+            ClusterArgs::CurrentSlot => {
+                render_with_spinner(ctx, spinner_msg, fetch_current_slot(ctx)).await?;
             }
-            ClusterCommand::BlockTime => {
-                show_spinner(self.spinner_msg(), fetch_block_time(ctx)).await;
+            ClusterArgs::BlockHeight => {
+                render_with_spinner(ctx, spinner_msg, fetch_block_height(ctx)).await?;
             }
-            ClusterCommand::Validators => {
-                show_spinner(self.spinner_msg(), fetch_validators(ctx)).await;
+            ClusterArgs::BlockTime => {
+                render_with_spinner(ctx, spinner_msg, fetch_block_time(ctx)).await?;
             }
-            ClusterCommand::SupplyInfo => {
-                show_spinner(self.spinner_msg(), fetch_supply_info(ctx)).await;
+            ClusterArgs::Validators => {
+                render_with_spinner(ctx, spinner_msg, fetch_validators(ctx)).await?;
             }
-            ClusterCommand::Inflation => {
-                show_spinner(self.spinner_msg(), fetch_inflation_info(ctx)).await;
+            ClusterArgs::Supply => {
+                render_with_spinner(ctx, spinner_msg, fetch_supply_info(ctx)).await?;
             }
-            ClusterCommand::ClusterVersion => {
-                show_spinner(self.spinner_msg(), fetch_cluster_version(ctx)).await;
+            ClusterArgs::Inflation => {
+                render_with_spinner(ctx, spinner_msg, fetch_inflation_info(ctx)).await?;
             }
-            ClusterCommand::GoBack => {
-                return Ok(CommandFlow::NavigateTo(NavigationTarget::PreviousSection));
+            ClusterArgs::Version => {
+                render_with_spinner(ctx, spinner_msg, fetch_cluster_version(ctx)).await?;
             }
-        };
-        Ok(CommandFlow::Processed)
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochInfoOutput {
+    pub epoch: u64,
+    pub epoch_progress: f64,
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+    pub absolute_slot: u64,
+    pub block_height: u64,
+    pub transaction_count: Option<u64>,
+}
+
+impl CommandOutput for EpochInfoOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("EPOCH INFORMATION");
+        table
+            .field("Epoch", self.epoch)
+            .field("Epoch Progress", format!("{:.2}%", self.epoch_progress))
+            .field("Slot Index", self.slot_index)
+            .field("Slots in Epoch", self.slots_in_epoch)
+            .field("Absolute Slot", self.absolute_slot)
+            .field("Block Height", self.block_height)
+            .field("Transaction Count", self.transaction_count.unwrap_or(0));
+
+        vec![table]
     }
 }
 
-async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<EpochInfoOutput> {
     let epoch_info = ctx.rpc().get_epoch_info().await?;
 
     let epoch_progress = if epoch_info.slots_in_epoch > 0 {
@@ -106,296 +190,294 @@ async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
         0.0
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch"),
-            Cell::new(format!("{}", epoch_info.epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch Progress"),
-            Cell::new(format!("{epoch_progress:.2}%")),
-        ])
-        .add_row(vec![
-            Cell::new("Slot Index"),
-            Cell::new(format!("{}", epoch_info.slot_index)),
-        ])
-        .add_row(vec![
-            Cell::new("Slots in Epoch"),
-            Cell::new(format!("{}", epoch_info.slots_in_epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Absolute Slot"),
-            Cell::new(format!("{}", epoch_info.absolute_slot)),
-        ])
-        .add_row(vec![
-            Cell::new("Block Height"),
-            Cell::new(format!("{}", epoch_info.block_height)),
-        ])
-        .add_row(vec![
-            Cell::new("Transaction Count"),
-            Cell::new(format!("{}", epoch_info.transaction_count.unwrap_or(0))),
-        ]);
-
-    println!("\n{}", style("EPOCH INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(EpochInfoOutput {
+        epoch: epoch_info.epoch,
+        epoch_progress,
+        slot_index: epoch_info.slot_index,
+        slots_in_epoch: epoch_info.slots_in_epoch,
+        absolute_slot: epoch_info.absolute_slot,
+        block_height: epoch_info.block_height,
+        transaction_count: epoch_info.transaction_count,
+    })
 }
 
-async fn fetch_current_slot(ctx: &ScillaContext) -> anyhow::Result<()> {
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentSlotOutput {
+    pub slot: u64,
+}
+
+impl CommandOutput for CurrentSlotOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("CURRENT SLOT");
+        table.field("Current Slot", self.slot);
+
+        vec![table]
+    }
+}
+
+async fn fetch_current_slot(ctx: &ScillaContext) -> anyhow::Result<CurrentSlotOutput> {
     let slot = ctx.rpc().get_slot().await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Current Slot"),
-            Cell::new(format!("{slot}")),
-        ]);
-
-    println!("\n{}", style("CURRENT SLOT").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(CurrentSlotOutput { slot })
 }
 
-async fn fetch_block_height(ctx: &ScillaContext) -> anyhow::Result<()> {
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHeightOutput {
+    pub block_height: u64,
+}
+
+impl CommandOutput for BlockHeightOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("BLOCK HEIGHT");
+        table.field("Block Height", self.block_height);
+
+        vec![table]
+    }
+}
+
+async fn fetch_block_height(ctx: &ScillaContext) -> anyhow::Result<BlockHeightOutput> {
     let block_height = ctx.rpc().get_block_height().await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Block Height"),
-            Cell::new(format!("{block_height}")),
-        ]);
-
-    println!("\n{}", style("BLOCK HEIGHT").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(BlockHeightOutput { block_height })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTimeOutput {
+    pub slot: u64,
+    pub unix_timestamp: i64,
+    pub date_time: Option<String>,
+}
+
+impl CommandOutput for BlockTimeOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("BLOCK TIME");
+        table
+            .field("Slot", self.slot)
+            .field("Unix Timestamp", self.unix_timestamp)
+            .field(
+                "Date/Time",
+                self.date_time.as_deref().unwrap_or("Invalid timestamp"),
+            );
+
+        vec![table]
+    }
 }
 
-async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<()> {
+async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<BlockTimeOutput> {
     let slot = ctx.rpc().get_slot().await?;
     let block_time = ctx.rpc().get_block_time(slot).await?;
 
-    let datetime = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
-        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-        .unwrap_or_else(|| "Invalid timestamp".to_string());
-
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Slot"), Cell::new(format!("{slot}"))])
-        .add_row(vec![
-            Cell::new("Unix Timestamp"),
-            Cell::new(format!("{block_time}")),
-        ])
-        .add_row(vec![Cell::new("Date/Time"), Cell::new(datetime)]);
-
-    println!("\n{}", style("BLOCK TIME").green().bold());
-    println!("{table}");
-
-    Ok(())
+    let date_time = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string());
+
+    Ok(BlockTimeOutput {
+        slot,
+        unix_timestamp: block_time,
+        date_time,
+    })
 }
 
-async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let validators = ctx.rpc().get_vote_accounts().await?;
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorOutput {
+    pub node_pubkey: String,
+    pub vote_pubkey: String,
+    pub activated_stake: u64,
+}
 
-    // Summary table
-    let mut summary_table = Table::new();
-    summary_table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Current Validators"),
-            Cell::new(format!("{}", validators.current.len())),
-        ])
-        .add_row(vec![
-            Cell::new("Delinquent Validators"),
-            Cell::new(format!("{}", validators.delinquent.len())),
-        ]);
-
-    println!("\n{}", style("VALIDATORS SUMMARY").green().bold());
-    println!("{summary_table}");
-
-    // Validators detail table
-    if !validators.current.is_empty() {
-        let mut validators = validators.current;
-        validators.sort_by_key(|b| Reverse(b.activated_stake)); // descending
-
-        let mut validators_table = Table::new();
-        validators_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Node Pubkey").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Vote Account").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Activated Stake (SOL)").add_attribute(comfy_table::Attribute::Bold),
-        ]);
-
-        for (idx, validator) in validators.iter().take(10).enumerate() {
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorsOutput {
+    pub current_count: usize,
+    pub delinquent_count: usize,
+    /// Top validators by activated stake, descending
+    pub top_validators: Vec<ValidatorOutput>,
+    #[serde(skip_serializing_if = "AddressLabels::is_empty")]
+    pub labels: AddressLabels,
+}
+
+impl CommandOutput for ValidatorsOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut summary_table = TableView::key_value("VALIDATORS SUMMARY");
+        summary_table
+            .field("Current Validators", self.current_count)
+            .field("Delinquent Validators", self.delinquent_count);
+
+        let mut validators_table = TableView::new(
+            "TOP 10 VALIDATORS BY STAKE",
+            ["#", "Node Pubkey", "Vote Account", "Activated Stake (SOL)"],
+        );
+        for (idx, validator) in self.top_validators.iter().enumerate() {
             let stake_sol = (validator.activated_stake as f64) / (LAMPORTS_PER_SOL as f64);
 
-            validators_table.add_row(vec![
-                Cell::new(idx + 1),
-                Cell::new(&validator.node_pubkey),
-                Cell::new(&validator.vote_pubkey),
-                Cell::new(format!("{stake_sol:.2}")),
+            validators_table.row([
+                TableCell::new(idx + 1),
+                TableCell::new(with_label(&validator.node_pubkey, &self.labels)),
+                TableCell::new(with_label(&validator.vote_pubkey, &self.labels)),
+                TableCell::new(format!("{stake_sol:.2}")),
             ]);
         }
 
-        println!("\n{}", style("TOP 10 VALIDATORS BY STAKE").green().bold());
-        println!("{validators_table}");
+        [summary_table, validators_table]
+            .into_iter()
+            .filter(|table| !table.is_empty())
+            .collect()
     }
+}
+
+async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<ValidatorsOutput> {
+    let validators = ctx.rpc().get_vote_accounts().await?;
+
+    let current_count = validators.current.len();
+    let delinquent_count = validators.delinquent.len();
+
+    let mut current = validators.current;
+    current.sort_by_key(|b| Reverse(b.activated_stake)); // descending
+
+    let top_validators: Vec<ValidatorOutput> = current
+        .into_iter()
+        .take(10)
+        .map(|validator| ValidatorOutput {
+            node_pubkey: validator.node_pubkey,
+            vote_pubkey: validator.vote_pubkey,
+            activated_stake: validator.activated_stake,
+        })
+        .collect();
+    let labels = ctx
+        .address_book()
+        .labels(top_validators.iter().flat_map(|validator| {
+            [
+                validator.node_pubkey.as_str(),
+                validator.vote_pubkey.as_str(),
+            ]
+        }));
+
+    Ok(ValidatorsOutput {
+        current_count,
+        delinquent_count,
+        top_validators,
+        labels,
+    })
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplyOutput {
+    pub total: u64,
+    pub circulating: u64,
+    pub non_circulating: u64,
 }
 
-async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+impl CommandOutput for SupplyOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let total_sol = (self.total as f64).div(LAMPORTS_PER_SOL as f64);
+        let circulating_sol = (self.circulating as f64).div(LAMPORTS_PER_SOL as f64);
+        let non_circulating_sol = (self.non_circulating as f64).div(LAMPORTS_PER_SOL as f64);
+        let circulating_pct = (circulating_sol / total_sol) * 100.0;
+
+        let mut table =
+            TableView::new("SUPPLY INFORMATION", ["Field", "Value (SOL)", "Percentage"]);
+        table
+            .row([
+                "Total Supply".to_string(),
+                format!("{total_sol:.2}"),
+                "100.00%".to_string(),
+            ])
+            .row([
+                "Circulating".to_string(),
+                format!("{circulating_sol:.2}"),
+                format!("{circulating_pct:.2}%"),
+            ])
+            .row([
+                "Non-Circulating".to_string(),
+                format!("{non_circulating_sol:.2}"),
+                format!("{:.2}%", 100.0 - circulating_pct),
+            ]);
+
+        vec![table]
+    }
+}
+
+async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<SupplyOutput> {
     let supply = ctx.rpc().supply().await?;
 
-    let total_sol = (supply.value.total as f64).div(LAMPORTS_PER_SOL as f64);
-    let circulating_sol = (supply.value.circulating as f64).div(LAMPORTS_PER_SOL as f64);
-    let non_circulating_sol = (supply.value.non_circulating as f64).div(LAMPORTS_PER_SOL as f64);
-    let circulating_pct = (circulating_sol / total_sol) * 100.0;
-
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value (SOL)").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Percentage").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Total Supply"),
-            Cell::new(format!("{total_sol:.2}")),
-            Cell::new("100.00%"),
-        ])
-        .add_row(vec![
-            Cell::new("Circulating"),
-            Cell::new(format!("{circulating_sol:.2}")),
-            Cell::new(format!("{circulating_pct:.2}%")),
-        ])
-        .add_row(vec![
-            Cell::new("Non-Circulating"),
-            Cell::new(format!("{non_circulating_sol:.2}")),
-            Cell::new(format!("{:.2}%", 100.0 - circulating_pct)),
-        ]);
-
-    println!("\n{}", style("SUPPLY INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(SupplyOutput {
+        total: supply.value.total,
+        circulating: supply.value.circulating,
+        non_circulating: supply.value.non_circulating,
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InflationOutput {
+    pub epoch: u64,
+    pub total: f64,
+    pub validator: f64,
+    pub foundation: f64,
+}
+
+impl CommandOutput for InflationOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("INFLATION INFORMATION");
+        table
+            .field("Epoch", self.epoch)
+            .field(
+                "Total Inflation Rate",
+                format!("{:.4}%", self.total * 100.0),
+            )
+            .field(
+                "Validator Inflation",
+                format!("{:.4}%", self.validator * 100.0),
+            )
+            .field(
+                "Foundation Inflation",
+                format!("{:.4}%", self.foundation * 100.0),
+            );
+
+        vec![table]
+    }
 }
 
-async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<InflationOutput> {
     let inflation = ctx.rpc().get_inflation_rate().await?;
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch"),
-            Cell::new(format!("{}", inflation.epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Total Inflation Rate"),
-            Cell::new(format!("{:.4}%", inflation.total * 100.0)),
-        ])
-        .add_row(vec![
-            Cell::new("Validator Inflation"),
-            Cell::new(format!("{:.4}%", inflation.validator * 100.0)),
-        ])
-        .add_row(vec![
-            Cell::new("Foundation Inflation"),
-            Cell::new(format!("{:.4}%", inflation.foundation * 100.0)),
-        ]);
-
-    println!("\n{}", style("INFLATION INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+
+    Ok(InflationOutput {
+        epoch: inflation.epoch,
+        total: inflation.total,
+        validator: inflation.validator,
+        foundation: inflation.foundation,
+    })
 }
 
-async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let version = ctx.rpc().get_version().await?;
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterVersionOutput {
+    pub solana_core: String,
+    pub feature_set: Option<u32>,
+}
+
+impl CommandOutput for ClusterVersionOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("CLUSTER VERSION");
+        table.field("Solana Core", &self.solana_core);
+
+        if let Some(feature_set) = self.feature_set {
+            table.field("Feature Set", feature_set);
+        }
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Solana Core"),
-            Cell::new(version.solana_core),
-        ]);
-
-    if let Some(feature_set) = version.feature_set {
-        table.add_row(vec![
-            Cell::new("Feature Set"),
-            Cell::new(format!("{feature_set}")),
-        ]);
+        vec![table]
     }
+}
 
-    println!("\n{}", style("CLUSTER VERSION").green().bold());
-    println!("{table}");
+async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<ClusterVersionOutput> {
+    let version = ctx.rpc().get_version().await?;
 
-    Ok(())
+    Ok(ClusterVersionOutput {
+        solana_core: version.solana_core,
+        feature_set: version.feature_set,
+    })
 }
@@ -1,46 +1,54 @@
 use {
     crate::{
-        commands::{
-            Command, CommandFlow,
-            navigation::{NavigationSection, NavigationTarget},
-        },
-        config::{ScillaConfig, scilla_config_path},
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
+        config::{HistoryConfig, PriorityFeeConfig, ScillaConfig, Transport, scilla_config_path},
         context::ScillaContext,
-        misc::helpers::short_pubkey,
-        prompt::{prompt_input_data, prompt_keypair_path, prompt_network_rpc_url},
+        history::{self, HistoryKind},
+        misc::{
+            helpers::{read_signer, short_pubkey, trim_and_parse},
+            nonce::DurableNonce,
+            signer::SignerSource,
+        },
+        output::{CommandOutput, TableView},
+        prompt::{
+            prompt_input_data, prompt_keypair_path, prompt_network_rpc_url, prompt_pubkey,
+            prompt_select_data,
+        },
+        ui::{print_error, render_output, show_spinner},
     },
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    clap::Subcommand,
     console::style,
-    inquire::{Confirm, Select},
+    inquire::{Confirm, CustomType, MultiSelect, Select, Text, validator::Validation},
     serde::{Deserialize, Serialize},
     solana_commitment_config::CommitmentLevel,
-    std::{fmt, fs, path::PathBuf},
+    std::{
+        collections::BTreeMap,
+        fmt,
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
 };
 
 /// Commands related to configuration like RPC_URL , KEYAPAIR_PATH etc
 #[derive(Debug, Clone, Copy)]
 pub enum ConfigCommand {
     Show,
+    SwitchProfile,
     Edit,
-    GoBack,
-}
-
-impl ConfigCommand {
-    pub fn spinner_msg(&self) -> &'static str {
-        match self {
-            ConfigCommand::Show => "Displaying current Scilla configuration…",
-            ConfigCommand::Edit => "Editing existing Scilla configuration…",
-            ConfigCommand::GoBack => "Going back…",
-        }
-    }
+    DryRun,
+    SignOnly,
+    DurableNonce,
 }
 
 impl fmt::Display for ConfigCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
             ConfigCommand::Show => "View ScillaConfig",
+            ConfigCommand::SwitchProfile => "Switch Profile",
             ConfigCommand::Edit => "Edit ScillaConfig",
-            ConfigCommand::GoBack => "Go back",
+            ConfigCommand::DryRun => "Toggle Dry Run",
+            ConfigCommand::SignOnly => "Toggle Sign Only",
+            ConfigCommand::DurableNonce => "Use Durable Nonce",
         };
         write!(f, "{command}")
     }
@@ -51,6 +59,10 @@ enum ConfigField {
     RpcUrl,
     CommitmentLevel,
     KeypairPath,
+    TransactionPreview,
+    PriorityFee,
+    Transport,
+    History,
     None,
 }
 
@@ -60,6 +72,10 @@ impl fmt::Display for ConfigField {
             ConfigField::RpcUrl => write!(f, "RPC URL"),
             ConfigField::CommitmentLevel => write!(f, "Commitment Level"),
             ConfigField::KeypairPath => write!(f, "Keypair Path"),
+            ConfigField::TransactionPreview => write!(f, "Transaction Preview"),
+            ConfigField::PriorityFee => write!(f, "Priority Fees"),
+            ConfigField::Transport => write!(f, "Transport"),
+            ConfigField::History => write!(f, "Prompt History"),
             ConfigField::None => write!(f, "None"),
         }
     }
@@ -71,6 +87,10 @@ impl ConfigField {
             ConfigField::RpcUrl,
             ConfigField::CommitmentLevel,
             ConfigField::KeypairPath,
+            ConfigField::TransactionPreview,
+            ConfigField::PriorityFee,
+            ConfigField::Transport,
+            ConfigField::History,
             ConfigField::None,
         ]
     }
@@ -101,54 +121,286 @@ fn get_commitment_levels() -> Vec<UICommitmentOptions> {
 }
 
 impl Command for ConfigCommand {
-    async fn process_command(&self, ctx: &mut ScillaContext) -> anyhow::Result<CommandFlow> {
-        ctx.get_nav_context_mut()
-            .checked_push(NavigationSection::ScillaConfig);
+    fn spinner_msg(&self) -> &'static str {
         match self {
-            ConfigCommand::Show => show_config(ctx)?,
-            ConfigCommand::Edit => edit_config(ctx)?,
-            ConfigCommand::GoBack => {
-                return Ok(CommandFlow::NavigateTo(NavigationTarget::PreviousSection));
+            ConfigCommand::Show => "Displaying current Scilla configuration…",
+            ConfigCommand::SwitchProfile => "Switching Scilla profile…",
+            ConfigCommand::Edit => "Editing existing Scilla configuration…",
+            ConfigCommand::DryRun => "Toggling dry run…",
+            ConfigCommand::SignOnly => "Toggling sign only…",
+            ConfigCommand::DurableNonce => "Checking nonce account…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            ConfigCommand::Show => ConfigArgs::Show,
+            ConfigCommand::SwitchProfile => ConfigArgs::UseProfile { name: None },
+            ConfigCommand::Edit => ConfigArgs::Edit,
+            // Session-only, so there is no subcommand; use `--dry-run` instead
+            ConfigCommand::DryRun => {
+                return Box::pin(async move {
+                    toggle_dry_run(ctx);
+                    Ok(CommandFlow::Processed)
+                });
+            }
+            // Session-only as well; use `--sign-only` instead
+            ConfigCommand::SignOnly => {
+                return Box::pin(async move {
+                    toggle_sign_only(ctx);
+                    Ok(CommandFlow::Processed)
+                });
+            }
+            // Session-only as well; use `--nonce` instead
+            ConfigCommand::DurableNonce => {
+                return Box::pin(async move {
+                    // A bad nonce authority should not end the interactive
+                    // session
+                    let result = use_durable_nonce(ctx, self.spinner_msg()).await;
+                    CommandFlow::from_run(result).or_else(|e| {
+                        print_error(e);
+                        Ok(CommandFlow::Processed)
+                    })
+                });
             }
         };
 
-        Ok(CommandFlow::Processed)
+        // A profile that cannot be loaded should not end the interactive
+        // session, the current one is kept
+        Box::pin(async move {
+            CommandFlow::from_run(args.run(ctx)).or_else(|e| {
+                print_error(e);
+                Ok(CommandFlow::Processed)
+            })
+        })
+    }
+}
+
+/// The Scilla Config menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Scilla Config").commands([
+        ConfigCommand::Show,
+        ConfigCommand::SwitchProfile,
+        ConfigCommand::Edit,
+        ConfigCommand::DryRun,
+        ConfigCommand::SignOnly,
+        ConfigCommand::DurableNonce,
+    ])
+}
+
+/// Non-interactive arguments for [`ConfigCommand`].
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigArgs {
+    /// Show the current configuration
+    Show,
+    /// Set the profile Scilla starts with and switch to it
+    UseProfile {
+        /// Profile name from scilla.toml, or `default` for the top-level
+        /// settings
+        name: Option<String>,
+    },
+    /// Edit the current profile interactively
+    Edit,
+}
+
+impl ConfigArgs {
+    pub fn run(self, ctx: &mut ScillaContext) -> anyhow::Result<()> {
+        match self {
+            ConfigArgs::Show => show_config(ctx),
+            ConfigArgs::UseProfile { name } => use_profile(ctx, name),
+            ConfigArgs::Edit => edit_config(ctx),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigOutput {
+    pub profile: String,
+    pub profiles: Vec<String>,
+    pub rpc_url: String,
+    pub commitment_level: CommitmentLevel,
+    pub keypair_path: PathBuf,
+    pub pubkey: String,
+    pub skip_preview: bool,
+    pub priority_fee: PriorityFeeConfig,
+    pub transport: Transport,
+}
+
+impl CommandOutput for ConfigOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let keypair_display = format!(
+            "{} ({})",
+            self.keypair_path.display(),
+            short_pubkey(&self.pubkey),
+        );
+
+        let mut table = TableView::key_value("SCILLA CONFIG");
+        table
+            .field("Profile", &self.profile)
+            .field("Available Profiles", self.profiles.join(", "))
+            .field("RPC URL", &self.rpc_url)
+            .field("Commitment Level", self.commitment_level.to_string())
+            .field("Keypair Path", keypair_display)
+            .field("Transaction Preview", preview_label(!self.skip_preview))
+            .field("Priority Fees", priority_fee_label(&self.priority_fee))
+            .field("Transport", self.transport.to_string());
+
+        vec![table]
+    }
+}
+
+fn preview_label(enabled: bool) -> &'static str {
+    if enabled { "On" } else { "Off" }
+}
+
+fn priority_fee_label(settings: &PriorityFeeConfig) -> String {
+    if !settings.enabled {
+        return "Off".to_string();
+    }
+
+    let price = match settings.compute_unit_price {
+        Some(price) => format!("{price} micro-lamports/CU"),
+        None => format!("p{} of recent fees", settings.percentile),
+    };
+    let price = match settings.max_compute_unit_price {
+        Some(max) => format!("{price}, at most {max} micro-lamports/CU"),
+        None => price,
+    };
+    match settings.compute_unit_limit {
+        Some(units) => format!("{price}, {units} CU limit"),
+        None => price,
     }
 }
 
 fn show_config(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let mut table = Table::new();
     let config = ScillaConfig::load()?;
+    let profile = config.profile(ctx.profile())?;
+
+    let output = ConfigOutput {
+        profile: ctx.profile().to_string(),
+        profiles: config.profile_names(),
+        rpc_url: profile.rpc_url,
+        commitment_level: profile.commitment_level,
+        keypair_path: profile.keypair_path,
+        pubkey: ctx.pubkey().to_string(),
+        skip_preview: profile.skip_preview,
+        priority_fee: profile.priority_fee,
+        transport: profile.transport,
+    };
+
+    render_output(ctx, &output)
+}
+
+fn use_profile(ctx: &mut ScillaContext, name: Option<String>) -> anyhow::Result<()> {
+    let mut config = ScillaConfig::load()?;
+
+    let name = name.map_or_else(
+        || {
+            prompt_select_data(
+                &format!("Select profile (current: {}):", ctx.profile()),
+                config.profile_names(),
+            )
+        },
+        Ok,
+    )?;
+
+    // Switch first so a profile with a broken keypair path is never saved as
+    // the active one
+    ctx.reload(&config, &name)?;
+    config.set_active_profile(&name)?;
+    config.save()?;
+
+    println!(
+        "{}",
+        style(format!("Switched to profile \"{name}\""))
+            .green()
+            .bold()
+    );
+
+    Ok(())
+}
+
+fn toggle_dry_run(ctx: &mut ScillaContext) {
+    ctx.set_dry_run(!ctx.is_dry_run());
+
+    let message = if ctx.is_dry_run() {
+        "Dry run on: transactions are simulated for the rest of the session, never sent"
+    } else {
+        "Dry run off: transactions are sent again"
+    };
+    println!("{}", style(message).green().bold());
+}
+
+fn toggle_sign_only(ctx: &mut ScillaContext) {
+    ctx.set_sign_only(!ctx.is_sign_only());
 
-    let wallet_pubkey = ctx.pubkey();
-    let keypair_display = format!(
-        "{} ({})",
-        config.keypair_path.display(),
-        short_pubkey(wallet_pubkey),
+    let message = if ctx.is_sign_only() {
+        "Sign only on: transactions are signed and shown for the missing signers, never sent"
+    } else {
+        "Sign only off: transactions are sent again"
+    };
+    println!("{}", style(message).green().bold());
+}
+
+/// Picks the nonce account, and its authority, that transactions are built
+/// against for the rest of the session, or stops using one.
+async fn use_durable_nonce(ctx: &mut ScillaContext, spinner_msg: &str) -> anyhow::Result<()> {
+    if let Some(nonce) = ctx.durable_nonce() {
+        let stop = Confirm::new(&format!(
+            "Transactions use nonce account {}. Stop using it?",
+            nonce.account
+        ))
+        .with_default(false)
+        .prompt()?;
+        if stop {
+            ctx.set_durable_nonce(None);
+            println!(
+                "{}",
+                style("Transactions use a recent blockhash again")
+                    .green()
+                    .bold()
+            );
+            return Ok(());
+        }
+    }
+
+    let account = prompt_pubkey("Enter nonce account pubkey:", ctx)?;
+    let authority_path = prompt_keypair_path("Enter nonce authority keypair path:", ctx)?;
+    let authority =
+        (&authority_path != ctx.keypair_path()).then(|| read_signer(ctx, &authority_path));
+    let nonce = DurableNonce {
+        account,
+        authority: authority.transpose()?,
+    };
+
+    // Catch a wrong account or authority now rather than on the next send
+    let Some(blockhash) = show_spinner(ctx, spinner_msg, nonce.blockhash(ctx)).await? else {
+        return Ok(());
+    };
+    ctx.set_durable_nonce(Some(nonce));
+
+    println!(
+        "{}\n{}",
+        style(format!(
+            "Transactions now use nonce account {account} for the rest of the session"
+        ))
+        .green()
+        .bold(),
+        style(format!("Nonce blockhash: {blockhash}")).cyan()
     );
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("RPC URL"), Cell::new(config.rpc_url)])
-        .add_row(vec![
-            Cell::new("Commitment Level"),
-            Cell::new(config.commitment_level),
-        ])
-        .add_row(vec![Cell::new("Keypair Path"), Cell::new(keypair_display)]);
-
-    println!("\n{}", style("SCILLA CONFIG").green().bold());
-    println!("{table}");
 
     Ok(())
 }
 
+/// Whether `path` should name a keypair file but none is there. Other signer
+/// URIs, such as `env:VAR`, are only checked once they are used.
+fn is_missing_keypair_file(path: &Path) -> bool {
+    SignerSource::parse(path).map_or(true, |source| {
+        source.file().is_some_and(|file| !file.exists())
+    })
+}
+
 pub fn generate_config() -> anyhow::Result<()> {
     // Check if config already exists
     let config_path = scilla_config_path();
@@ -182,7 +434,7 @@ pub fn generate_config() -> anyhow::Result<()> {
 
         config
     } else {
-        let rpc_url: String = prompt_input_data("Enter RPC URL:");
+        let rpc_url: String = prompt_input_data("Enter RPC URL:")?;
 
         let commitment_level =
             match Select::new("Select commitment level:", get_commitment_levels()).prompt()? {
@@ -191,9 +443,9 @@ pub fn generate_config() -> anyhow::Result<()> {
             };
 
         let keypair_path = loop {
-            let keypair_input: PathBuf = prompt_input_data("Enter keypair path:");
+            let keypair_input: PathBuf = prompt_input_data("Enter keypair path:")?;
 
-            if !keypair_input.exists() {
+            if is_missing_keypair_file(&keypair_input) {
                 println!(
                     "{}",
                     style(format!(
@@ -212,17 +464,16 @@ pub fn generate_config() -> anyhow::Result<()> {
             rpc_url,
             commitment_level,
             keypair_path,
+            skip_preview: false,
+            active_profile: None,
+            priority_fee: Default::default(),
+            transport: Default::default(),
+            history: Default::default(),
+            profiles: BTreeMap::new(),
         }
     };
 
-    // Write config
-    let config_path = scilla_config_path();
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    let toml_string = toml::to_string_pretty(&config)?;
-    fs::write(&config_path, toml_string)?;
+    config.save()?;
 
     println!("{}", style("Config generated successfully!").green().bold());
     println!(
@@ -233,23 +484,161 @@ pub fn generate_config() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Asks whether to pay priority fees and how to price them.
+fn edit_priority_fee(settings: &mut PriorityFeeConfig) -> anyhow::Result<()> {
+    settings.enabled = Confirm::new("Add priority fees to transactions?")
+        .with_default(settings.enabled)
+        .prompt()?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    settings.compute_unit_price = prompt_optional_number(
+        "Fixed price in micro-lamports per compute unit:",
+        "Leave empty to follow recent fees on the writable accounts",
+        "price",
+    )?;
+
+    if settings.compute_unit_price.is_none() {
+        settings.percentile = CustomType::<u8>::new("Percentile of recent fees to pay (0-100):")
+            .with_default(settings.percentile)
+            .with_validator(|percentile: &u8| {
+                Ok(if *percentile <= 100 {
+                    Validation::Valid
+                } else {
+                    Validation::Invalid("Must be between 0 and 100".into())
+                })
+            })
+            .prompt()?;
+
+        settings.max_compute_unit_price = prompt_optional_number(
+            "Maximum price in micro-lamports per compute unit:",
+            "Leave empty for no limit",
+            "price",
+        )?;
+    }
+
+    settings.compute_unit_limit = prompt_optional_number(
+        "Compute unit limit:",
+        "Leave empty to size it by simulating each transaction",
+        "limit",
+    )?;
+
+    Ok(())
+}
+
+fn prompt_optional_number<T: FromStr + 'static>(
+    msg: &str,
+    help: &str,
+    field_name: &'static str,
+) -> anyhow::Result<Option<T>> {
+    let input = Text::new(msg)
+        .with_help_message(help)
+        .with_validator(move |input: &str| {
+            Ok(match trim_and_parse::<T>(input, field_name) {
+                Ok(_) => Validation::Valid,
+                Err(e) => Validation::Invalid(e.to_string().into()),
+            })
+        })
+        .prompt()?;
+
+    trim_and_parse(&input, field_name)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum HistoryAction {
+    Enable,
+    Disable,
+    Exclude,
+    Clear,
+}
+
+impl fmt::Display for HistoryAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryAction::Enable => write!(f, "Turn history on"),
+            HistoryAction::Disable => write!(f, "Turn history off"),
+            HistoryAction::Exclude => write!(f, "Choose prompt kinds to never remember"),
+            HistoryAction::Clear => write!(f, "Clear saved history"),
+        }
+    }
+}
+
+/// Updates the prompt history privacy settings. Returns whether the settings
+/// changed and need to be saved.
+fn edit_history(settings: &mut HistoryConfig) -> anyhow::Result<bool> {
+    let toggle = if settings.enabled {
+        HistoryAction::Disable
+    } else {
+        HistoryAction::Enable
+    };
+    let action = Select::new(
+        "Prompt history:",
+        vec![toggle, HistoryAction::Exclude, HistoryAction::Clear],
+    )
+    .prompt()?;
+
+    match action {
+        HistoryAction::Enable => settings.enabled = true,
+        HistoryAction::Disable => settings.enabled = false,
+        HistoryAction::Exclude => {
+            let kinds = HistoryKind::all();
+            let excluded: Vec<usize> = kinds
+                .iter()
+                .enumerate()
+                .filter(|(_, kind)| settings.exclude.contains(kind))
+                .map(|(idx, _)| idx)
+                .collect();
+
+            settings.exclude = MultiSelect::new("Never remember:", kinds)
+                .with_default(&excluded)
+                .prompt()?;
+        }
+        HistoryAction::Clear => {
+            history::clear()?;
+            println!("{}", style("Prompt history cleared").green().bold());
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
 fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
     let mut config = ScillaConfig::load()?;
+    let profile_name = ctx.profile().to_string();
+    let mut profile = config.profile(&profile_name)?;
 
-    println!("\n{}", style("Edit Config").green().bold());
+    println!(
+        "\n{}",
+        style(format!("Edit Config (profile: {profile_name})"))
+            .green()
+            .bold()
+    );
 
     // Show current configuration
-    println!("\n{} {}", style("Current RPC URL:").cyan(), config.rpc_url);
+    println!("\n{} {}", style("Current RPC URL:").cyan(), profile.rpc_url);
     println!(
         "{} {:?}",
         style("Current Commitment Level:").cyan(),
-        config.commitment_level
+        profile.commitment_level
     );
     println!(
         "{} {}",
         style("Current Keypair Path:").cyan(),
-        config.keypair_path.display()
+        profile.keypair_path.display()
+    );
+    println!(
+        "{} {}",
+        style("Transaction Preview:").cyan(),
+        preview_label(!profile.skip_preview)
+    );
+    println!(
+        "{} {}",
+        style("Priority Fees:").cyan(),
+        priority_fee_label(&profile.priority_fee)
     );
+    println!("{} {}", style("Transport:").cyan(), profile.transport);
 
     // Prompt user to select which field to edit
     let field_options = ConfigField::all();
@@ -264,11 +653,11 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
             .prompt()?;
             let new_rpc_url = match choice {
                 "Default" => prompt_network_rpc_url()?,
-                "Custom" => prompt_input_data::<String>("Enter custom RPC URL:"),
+                "Custom" => prompt_input_data::<String>("Enter custom RPC URL:")?,
                 _ => unreachable!(),
             };
 
-            config.rpc_url = new_rpc_url;
+            profile.rpc_url = new_rpc_url;
         }
         ConfigField::CommitmentLevel => {
             let selected =
@@ -279,12 +668,12 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
                 UICommitmentOptions::None => return Ok(()),
             };
 
-            config.commitment_level = level
+            profile.commitment_level = level
         }
         ConfigField::KeypairPath => loop {
-            let keypair_input = prompt_keypair_path("Enter new keypair path:", ctx);
+            let keypair_input = prompt_keypair_path("Enter new keypair path:", ctx)?;
 
-            if !keypair_input.exists() {
+            if is_missing_keypair_file(&keypair_input) {
                 println!(
                     "{}",
                     style(format!(
@@ -296,19 +685,40 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
                 continue;
             }
 
-            config.keypair_path = keypair_input;
+            profile.keypair_path = keypair_input;
             break;
         },
+        ConfigField::TransactionPreview => {
+            profile.skip_preview =
+                !Confirm::new("Preview and confirm transactions before sending?")
+                    .with_default(!profile.skip_preview)
+                    .prompt()?;
+        }
+        ConfigField::PriorityFee => edit_priority_fee(&mut profile.priority_fee)?,
+        ConfigField::Transport => {
+            profile.transport = Select::new(
+                "Send transactions through:",
+                vec![Transport::Rpc, Transport::Tpu],
+            )
+            .with_help_message("Transactions are always confirmed through the RPC")
+            .prompt()?;
+        }
+        ConfigField::History => {
+            if !edit_history(&mut config.history)? {
+                return Ok(());
+            }
+            history::init(config.history.clone());
+        }
         ConfigField::None => return Ok(()),
     }
 
     // Write updated config
-    let config_path = scilla_config_path();
-    let toml_string = toml::to_string_pretty(&config)?;
-    fs::write(&config_path, toml_string)?;
+    config.set_profile(&profile_name, profile);
+    config.save()?;
 
-    ctx.reload(config)?;
+    ctx.reload(&config, &profile_name)?;
 
+    let config_path = scilla_config_path();
     println!("{}", style("Config updated successfully!").green().bold());
     println!(
         "{}",
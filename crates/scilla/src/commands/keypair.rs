@@ -0,0 +1,1453 @@
+use {
+    crate::{
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
+        config::expand_tilde,
+        context::ScillaContext,
+        misc::{
+            helpers::{read_keypair_from_path, write_private_file},
+            keystore::{
+                KDF_ROUNDS, Keystore, prompt_new_password, remember_password, unlock_keystore,
+            },
+            signer::{load_signer, read_stdin},
+        },
+        output::{Color, CommandOutput, TableCell, TableView},
+        prompt::{
+            prompt_confirmation, prompt_input_data, prompt_keypair_path, prompt_pubkey,
+            prompt_select_data,
+        },
+        ui::{print_error, render_output},
+    },
+    anyhow::{Context, anyhow, bail},
+    bip39::{Language, Mnemonic, MnemonicType, Seed},
+    clap::{Subcommand, ValueEnum},
+    console::style,
+    indicatif::{ProgressBar, ProgressStyle},
+    inquire::{Password, PasswordDisplayMode, Text, validator::Validation},
+    serde::Serialize,
+    solana_derivation_path::DerivationPath,
+    solana_keypair::{
+        Keypair, Signer, keypair_from_seed, keypair_from_seed_phrase_and_passphrase,
+        seed_derivable::keypair_from_seed_and_derivation_path,
+    },
+    solana_pubkey::Pubkey,
+    std::{
+        borrow::Cow,
+        fmt,
+        io::{self, IsTerminal},
+        path::{Path, PathBuf},
+        sync::{
+            Mutex, OnceLock, PoisonError,
+            atomic::{AtomicBool, AtomicU64, Ordering},
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+const DEFAULT_WORD_COUNT: usize = 12;
+
+/// Secret key followed by the pubkey.
+const KEYPAIR_LENGTH: usize = 64;
+
+/// Keys are generated in batches between updates of the shared counter.
+const GRIND_BATCH: u64 = 256;
+
+/// Commands to create, recover and inspect keypair files
+#[derive(Debug, Clone, Copy)]
+pub enum KeypairCommand {
+    Generate,
+    Recover,
+    ShowPubkey,
+    Verify,
+    Grind,
+    Import,
+    Export,
+    Encrypt,
+    Decrypt,
+    ChangePassword,
+}
+
+impl fmt::Display for KeypairCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KeypairCommand::Generate => "Generate Keypair",
+            KeypairCommand::Recover => "Recover from Seed Phrase",
+            KeypairCommand::ShowPubkey => "Show Pubkey",
+            KeypairCommand::Verify => "Verify Keypair",
+            KeypairCommand::Grind => "Grind Vanity Address",
+            KeypairCommand::Import => "Import Keypair",
+            KeypairCommand::Export => "Export Keypair",
+            KeypairCommand::Encrypt => "Encrypt Keypair",
+            KeypairCommand::Decrypt => "Decrypt Keypair",
+            KeypairCommand::ChangePassword => "Change Keystore Password",
+        })
+    }
+}
+
+impl Command for KeypairCommand {
+    fn spinner_msg(&self) -> &'static str {
+        match self {
+            KeypairCommand::Generate => "Generating keypair…",
+            KeypairCommand::Recover => "Recovering keypair…",
+            KeypairCommand::ShowPubkey => "Reading keypair…",
+            KeypairCommand::Verify => "Verifying keypair…",
+            KeypairCommand::Grind => "Grinding vanity address…",
+            KeypairCommand::Import => "Importing keypair…",
+            KeypairCommand::Export => "Exporting keypair…",
+            KeypairCommand::Encrypt => "Encrypting keypair…",
+            KeypairCommand::Decrypt => "Decrypting keypair…",
+            KeypairCommand::ChangePassword => "Changing keystore password…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            KeypairCommand::Generate => KeypairArgs::New {
+                outfile: None,
+                word_count: DEFAULT_WORD_COUNT,
+                no_passphrase: false,
+                force: false,
+            },
+            KeypairCommand::Recover => KeypairArgs::Recover {
+                outfile: None,
+                derivation_path: None,
+                force: false,
+            },
+            KeypairCommand::ShowPubkey => KeypairArgs::Pubkey { keypair: None },
+            KeypairCommand::Verify => KeypairArgs::Verify {
+                pubkey: None,
+                keypair: None,
+            },
+            KeypairCommand::Grind => KeypairArgs::Grind {
+                starts_with: None,
+                ends_with: None,
+                ignore_case: false,
+                count: None,
+                threads: None,
+                outdir: None,
+            },
+            KeypairCommand::Import => KeypairArgs::Import {
+                format: None,
+                outfile: None,
+                force: false,
+            },
+            KeypairCommand::Export => KeypairArgs::Export {
+                keypair: None,
+                format: None,
+                outfile: None,
+                force: false,
+            },
+            KeypairCommand::Encrypt => KeypairArgs::Encrypt {
+                keypair: None,
+                outfile: None,
+                force: false,
+            },
+            KeypairCommand::Decrypt => KeypairArgs::Decrypt {
+                keystore: None,
+                outfile: None,
+                force: false,
+            },
+            KeypairCommand::ChangePassword => KeypairArgs::ChangePassword { keystore: None },
+        };
+
+        // A wrong password or an existing file should not end the
+        // interactive session
+        Box::pin(async move {
+            CommandFlow::from_run(args.run(ctx)).or_else(|e| {
+                print_error(e);
+                Ok(CommandFlow::Processed)
+            })
+        })
+    }
+}
+
+/// The Keypair menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Keypair").commands([
+        KeypairCommand::Generate,
+        KeypairCommand::Recover,
+        KeypairCommand::ShowPubkey,
+        KeypairCommand::Verify,
+        KeypairCommand::Grind,
+        KeypairCommand::Import,
+        KeypairCommand::Export,
+        KeypairCommand::Encrypt,
+        KeypairCommand::Decrypt,
+        KeypairCommand::ChangePassword,
+    ])
+}
+
+/// Non-interactive arguments for [`KeypairCommand`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum KeypairArgs {
+    /// Generate a new keypair file with a BIP39 seed phrase
+    New {
+        /// Where to write the keypair
+        #[arg(long, short = 'f')]
+        outfile: Option<PathBuf>,
+        /// Number of words in the seed phrase
+        #[arg(long, default_value_t = DEFAULT_WORD_COUNT, value_parser = parse_word_count)]
+        word_count: usize,
+        /// Do not ask for a BIP39 passphrase
+        #[arg(long)]
+        no_passphrase: bool,
+        /// Overwrite the keypair file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Recover a keypair file from its seed phrase
+    Recover {
+        /// Where to write the keypair
+        #[arg(long, short = 'f')]
+        outfile: Option<PathBuf>,
+        /// Derive the key along a BIP44 path, e.g. `0/0` or `m/44'/501'/0'/0'`,
+        /// as wallets do. Without a value, `m/44'/501'` is used
+        #[arg(
+            long,
+            num_args = 0..=1,
+            default_missing_value = "m/44'/501'",
+            value_parser = parse_derivation_path
+        )]
+        derivation_path: Option<DerivationPath>,
+        /// Overwrite the keypair file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the pubkey of a keypair file or signer URI
+    Pubkey { keypair: Option<PathBuf> },
+    /// Check that a keypair file or signer URI holds the key for a pubkey
+    Verify {
+        pubkey: Option<Pubkey>,
+        keypair: Option<PathBuf>,
+    },
+    /// Grind keypairs until their pubkey starts or ends with a pattern
+    Grind {
+        #[arg(long)]
+        starts_with: Option<String>,
+        #[arg(long)]
+        ends_with: Option<String>,
+        /// Match the pattern in any letter case
+        #[arg(long)]
+        ignore_case: bool,
+        /// Number of matching keypairs to find
+        #[arg(long)]
+        count: Option<usize>,
+        /// Threads to grind on, all CPUs by default
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Directory the keypairs are written to, as `<PUBKEY>.json`
+        #[arg(long)]
+        outdir: Option<PathBuf>,
+    },
+    /// Import a secret key handed over as text into a keypair file. The key
+    /// is read from stdin when piped, or asked for
+    Import {
+        /// Format of the key, detected when left out
+        #[arg(long, value_enum)]
+        format: Option<KeypairFormat>,
+        /// Where to write the keypair
+        #[arg(long, short = 'f')]
+        outfile: Option<PathBuf>,
+        /// Overwrite the keypair file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Export a keypair file as text, or only its pubkey
+    Export {
+        keypair: Option<PathBuf>,
+        #[arg(long, value_enum)]
+        format: Option<KeypairFormat>,
+        /// Write the export to a file instead of printing it
+        #[arg(long, short = 'f')]
+        outfile: Option<PathBuf>,
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Encrypt a keypair file with a password
+    Encrypt {
+        keypair: Option<PathBuf>,
+        /// Where to write the keystore, over the keypair file by default
+        #[arg(long, short = 'f')]
+        outfile: Option<PathBuf>,
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Decrypt a keystore back to a plain keypair file
+    Decrypt {
+        keystore: Option<PathBuf>,
+        /// Where to write the plain keypair
+        #[arg(long, short = 'f')]
+        outfile: Option<PathBuf>,
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Change the password of a keystore
+    ChangePassword { keystore: Option<PathBuf> },
+}
+
+fn parse_word_count(s: &str) -> Result<usize, String> {
+    let count = s.parse::<usize>().map_err(|e| e.to_string())?;
+    MnemonicType::for_word_count(count)
+        .map(|_| count)
+        .map_err(|_| "Must be 12, 15, 18, 21 or 24".to_string())
+}
+
+fn parse_derivation_path(s: &str) -> Result<DerivationPath, String> {
+    let s = s.trim();
+    let path = if s.starts_with('m') {
+        DerivationPath::from_absolute_path_str(s)
+    } else {
+        DerivationPath::from_key_str(s)
+    };
+    path.map_err(|e| e.to_string())
+}
+
+impl KeypairArgs {
+    pub fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            KeypairArgs::New {
+                outfile,
+                word_count,
+                no_passphrase,
+                force,
+            } => {
+                let outfile = outfile
+                    .map_or_else(|| prompt_outfile("Where to save the keypair:", None), Ok)?;
+                check_outfile(&outfile, force)?;
+                let passphrase = if no_passphrase {
+                    String::new()
+                } else {
+                    prompt_new_passphrase()?
+                };
+
+                let output = generate_keypair(&outfile, word_count, &passphrase)?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::Recover {
+                outfile,
+                derivation_path,
+                force,
+            } => {
+                let outfile = outfile
+                    .map_or_else(|| prompt_outfile("Where to save the keypair:", None), Ok)?;
+                check_outfile(&outfile, force)?;
+                let seed_phrase = prompt_seed_phrase()?;
+                let passphrase = prompt_passphrase()?;
+                let derivation_path = match derivation_path {
+                    Some(path) => Some(path),
+                    None if ctx.is_interactive() => prompt_derivation_path()?,
+                    None => None,
+                };
+
+                let output = recover_keypair(&outfile, &seed_phrase, &passphrase, derivation_path)?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::Pubkey { keypair } => {
+                let path = keypair.map_or_else(|| prompt_keypair_path("Keypair path:", ctx), Ok)?;
+                let signer = load_signer(&path)?;
+
+                render_output(ctx, &KeypairOutput::new("KEYPAIR", signer.as_ref(), &path))
+            }
+            KeypairArgs::Verify { pubkey, keypair } => {
+                let pubkey = pubkey.map_or_else(|| prompt_pubkey("Expected pubkey:", ctx), Ok)?;
+                let path = keypair.map_or_else(|| prompt_keypair_path("Keypair path:", ctx), Ok)?;
+
+                let output = verify_keypair(&path, &pubkey)?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::Grind {
+                starts_with,
+                ends_with,
+                ignore_case,
+                count,
+                threads,
+                outdir,
+            } => {
+                let matcher = if starts_with.is_none() && ends_with.is_none() {
+                    prompt_vanity_matcher()?
+                } else {
+                    let ignore_case = ignore_case
+                        || (ctx.is_interactive() && prompt_confirmation("Ignore case? (y/n):")?);
+                    VanityMatcher::new(
+                        starts_with.unwrap_or_default(),
+                        ends_with.unwrap_or_default(),
+                        ignore_case,
+                    )?
+                };
+                let count = count
+                    .map_or_else(|| prompt_input_data("How many keypairs to find:"), Ok)?
+                    .max(1);
+                let outdir = outdir.unwrap_or_else(|| PathBuf::from("."));
+                let threads = threads.unwrap_or_else(num_cpus::get).max(1);
+
+                let output = grind_keypairs(&matcher, count, threads, &outdir)?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::Import {
+                format,
+                outfile,
+                force,
+            } => {
+                let outfile = outfile
+                    .map_or_else(|| prompt_outfile("Where to save the keypair:", None), Ok)?;
+                check_outfile(&outfile, force)?;
+                let secret = if io::stdin().is_terminal() {
+                    Password::new("Secret key:")
+                        .with_display_mode(PasswordDisplayMode::Masked)
+                        .with_help_message("A JSON byte array, a base58 or a hex secret key")
+                        .without_confirmation()
+                        .prompt()?
+                } else {
+                    read_stdin()?
+                };
+
+                let output = import_keypair(&secret, format, &outfile)?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::Export {
+                keypair,
+                format,
+                outfile,
+                force,
+            } => {
+                let path = keypair.map_or_else(|| prompt_keypair_path("Keypair path:", ctx), Ok)?;
+                let format = format.map_or_else(
+                    || prompt_select_data("Export as:", KeypairFormat::all()),
+                    Ok,
+                )?;
+                let outfile = match outfile {
+                    Some(outfile) => Some(outfile),
+                    None if ctx.is_interactive() && format.is_secret() => {
+                        prompt_optional_outfile()?
+                    }
+                    None => None,
+                };
+                if let Some(outfile) = &outfile {
+                    check_outfile(outfile, force)?;
+                } else if format.is_secret()
+                    && ctx.is_interactive()
+                    && !prompt_confirmation(
+                        "This shows the secret key on screen. Anyone who sees it controls the \
+                         keypair. Continue? (y/n):",
+                    )?
+                {
+                    return Ok(());
+                }
+
+                let output = export_keypair(&path, format, outfile.as_deref())?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::Encrypt {
+                keypair,
+                outfile,
+                force,
+            } => {
+                let path = keypair.map_or_else(|| prompt_keypair_path("Keypair path:", ctx), Ok)?;
+                if Keystore::read(&path)?.is_some() {
+                    bail!("{} is already encrypted", path.display());
+                }
+                let outfile = match outfile {
+                    Some(outfile) => outfile,
+                    None if ctx.is_interactive() => {
+                        prompt_outfile("Where to save the keystore:", Some(&path))?
+                    }
+                    None => path.clone(),
+                };
+                if outfile != path {
+                    check_outfile(&outfile, force)?;
+                }
+                let password = prompt_new_password()?;
+
+                let output = encrypt_keypair(&path, &outfile, &password, KDF_ROUNDS)?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::Decrypt {
+                keystore,
+                outfile,
+                force,
+            } => {
+                let path =
+                    keystore.map_or_else(|| prompt_keypair_path("Keystore path:", ctx), Ok)?;
+                let outfile = outfile
+                    .map_or_else(|| prompt_outfile("Where to save the keypair:", None), Ok)?;
+                if outfile != path {
+                    check_outfile(&outfile, force)?;
+                }
+
+                let output = decrypt_keystore(&path, &outfile)?;
+                render_output(ctx, &output)
+            }
+            KeypairArgs::ChangePassword { keystore } => {
+                let path =
+                    keystore.map_or_else(|| prompt_keypair_path("Keystore path:", ctx), Ok)?;
+                if Keystore::read(&path)?.is_none() {
+                    bail!("{} is not an encrypted keystore", path.display());
+                }
+                let current = Password::new("Current password:")
+                    .without_confirmation()
+                    .prompt()?;
+                let password = prompt_new_password()?;
+
+                let output = change_password(&path, &current, &password, KDF_ROUNDS)?;
+                render_output(ctx, &output)
+            }
+        }
+    }
+}
+
+fn prompt_outfile(msg: &str, default: Option<&Path>) -> anyhow::Result<PathBuf> {
+    let input = match default {
+        Some(default) => Text::new(msg)
+            .with_default(&default.display().to_string())
+            .prompt()?,
+        None => prompt_input_data::<String>(msg)?,
+    };
+    Ok(expand_tilde(input.trim()))
+}
+
+/// Refuses to replace an existing keypair, which would lose the key it holds,
+/// unless `force` is set or the user confirms it.
+fn check_outfile(outfile: &Path, force: bool) -> anyhow::Result<()> {
+    if !outfile.exists() || force {
+        return Ok(());
+    }
+    if prompt_confirmation(&format!(
+        "{} already exists. Overwrite it? The key it holds is lost unless backed up (y/n):",
+        outfile.display()
+    ))? {
+        return Ok(());
+    }
+    bail!(
+        "{} already exists. Pass --force to overwrite it",
+        outfile.display()
+    )
+}
+
+fn prompt_optional_outfile() -> anyhow::Result<Option<PathBuf>> {
+    let input = Text::new("Save to file:")
+        .with_help_message("Leave empty to show it on screen instead")
+        .prompt()?;
+
+    Ok(match input.trim() {
+        "" => None,
+        path => Some(expand_tilde(path)),
+    })
+}
+
+fn prompt_new_passphrase() -> anyhow::Result<String> {
+    Ok(Password::new("BIP39 passphrase:")
+        .with_help_message("Optional extra word protecting the seed phrase. Leave empty for none")
+        .with_custom_confirmation_message("Confirm passphrase:")
+        .prompt()?)
+}
+
+pub fn prompt_seed_phrase() -> anyhow::Result<String> {
+    let phrase = Password::new("Seed phrase:")
+        .with_display_mode(PasswordDisplayMode::Masked)
+        .without_confirmation()
+        .with_validator(|phrase: &str| {
+            Ok(
+                match Mnemonic::validate(&normalize_phrase(phrase), Language::English) {
+                    Ok(()) => Validation::Valid,
+                    Err(e) => Validation::Invalid(e.to_string().into()),
+                },
+            )
+        })
+        .prompt()?;
+
+    Ok(normalize_phrase(&phrase))
+}
+
+/// Asks for the BIP39 passphrase of an existing seed phrase.
+pub fn prompt_passphrase() -> anyhow::Result<String> {
+    Ok(Password::new("BIP39 passphrase:")
+        .with_help_message("Leave empty if the seed phrase has none")
+        .without_confirmation()
+        .prompt()?)
+}
+
+/// Lowercases the words and joins them with single spaces, the form BIP39
+/// seeds are derived from.
+fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn prompt_derivation_path() -> anyhow::Result<Option<DerivationPath>> {
+    let input = Text::new("Derivation path:")
+        .with_help_message(
+            "e.g. 0/0 or m/44'/501'/0'/0' for wallet-derived keys. Leave empty for none",
+        )
+        .with_validator(|input: &str| {
+            Ok(match input.trim() {
+                "" => Validation::Valid,
+                path => match parse_derivation_path(path) {
+                    Ok(_) => Validation::Valid,
+                    Err(e) => Validation::Invalid(e.into()),
+                },
+            })
+        })
+        .prompt()?;
+
+    match input.trim() {
+        "" => Ok(None),
+        path => parse_derivation_path(path)
+            .map(Some)
+            .map_err(|e| anyhow!(e)),
+    }
+}
+
+fn prompt_vanity_matcher() -> anyhow::Result<VanityMatcher> {
+    loop {
+        let prefix = Text::new("Starts with:")
+            .with_help_message("Leave empty to match only the end")
+            .prompt()?;
+        let suffix = Text::new("Ends with:")
+            .with_help_message("Leave empty to match only the start")
+            .prompt()?;
+        let ignore_case = prompt_confirmation("Ignore case? (y/n):")?;
+
+        match VanityMatcher::new(prefix, suffix, ignore_case) {
+            Ok(matcher) => return Ok(matcher),
+            Err(e) => print_error(format!("{e}. Please try again.")),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeypairOutput {
+    #[serde(skip)]
+    title: &'static str,
+    pub pubkey: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_phrase: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+}
+
+impl KeypairOutput {
+    fn new(title: &'static str, signer: &dyn Signer, path: &Path) -> Self {
+        Self {
+            title,
+            pubkey: signer.pubkey().to_string(),
+            path: path.display().to_string(),
+            seed_phrase: None,
+            derivation_path: None,
+        }
+    }
+}
+
+impl CommandOutput for KeypairOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value(self.title);
+        table
+            .field("Pubkey", &self.pubkey)
+            .field("File", &self.path);
+        if let Some(path) = &self.derivation_path {
+            table.field("Derivation Path", path);
+        }
+
+        let mut seed = TableView::key_value("SEED PHRASE");
+        if let Some(phrase) = &self.seed_phrase {
+            seed.field("Words", TableCell::new(phrase).fg(Color::Yellow));
+        }
+
+        [table, seed]
+            .into_iter()
+            .filter(|table| !table.is_empty())
+            .collect()
+    }
+
+    fn render_table(&self) {
+        for table in self.tables() {
+            table.print();
+        }
+        if self.seed_phrase.is_some() {
+            println!(
+                "{}",
+                style(
+                    "Write the seed phrase down and keep it offline, with the passphrase if you \
+                     set one. Anyone who has them controls this keypair."
+                )
+                .yellow()
+                .bold()
+            );
+        }
+    }
+
+    fn has_secrets(&self) -> bool {
+        self.seed_phrase.is_some()
+    }
+}
+
+/// Writes `keypair` as a JSON byte array, the format of Solana keypair files,
+/// readable by the owner only.
+pub(crate) fn write_keypair(keypair: &Keypair, outfile: &Path) -> anyhow::Result<()> {
+    write_private_file(outfile, &KeypairFormat::Json.encode(keypair))
+        .with_context(|| format!("Failed to write keypair to {}", outfile.display()))
+}
+
+/// Same derivation as `solana-keygen new`: the keypair is the first 32 bytes
+/// of the BIP39 seed, without a derivation path.
+fn generate_keypair(
+    outfile: &Path,
+    word_count: usize,
+    passphrase: &str,
+) -> anyhow::Result<KeypairOutput> {
+    let mnemonic_type = MnemonicType::for_word_count(word_count)?;
+    let mnemonic = Mnemonic::new(mnemonic_type, Language::English);
+    let seed = Seed::new(&mnemonic, passphrase);
+    let keypair = keypair_from_seed(seed.as_bytes()).map_err(|e| anyhow!("{e}"))?;
+
+    write_keypair(&keypair, outfile)?;
+
+    Ok(KeypairOutput {
+        seed_phrase: Some(mnemonic.into_phrase()),
+        ..KeypairOutput::new("NEW KEYPAIR", &keypair, outfile)
+    })
+}
+
+pub fn derive_keypair(
+    seed_phrase: &str,
+    passphrase: &str,
+    derivation_path: Option<DerivationPath>,
+) -> anyhow::Result<Keypair> {
+    let keypair = match derivation_path {
+        Some(path) => {
+            let seed = Seed::new(
+                &Mnemonic::from_phrase(seed_phrase, Language::English)?,
+                passphrase,
+            );
+            keypair_from_seed_and_derivation_path(seed.as_bytes(), Some(path))
+        }
+        None => keypair_from_seed_phrase_and_passphrase(seed_phrase, passphrase),
+    };
+    keypair.map_err(|e| anyhow!("Failed to derive keypair: {e}"))
+}
+
+fn recover_keypair(
+    outfile: &Path,
+    seed_phrase: &str,
+    passphrase: &str,
+    derivation_path: Option<DerivationPath>,
+) -> anyhow::Result<KeypairOutput> {
+    let path_label = derivation_path.as_ref().map(|path| format!("{path:?}"));
+    let keypair = derive_keypair(seed_phrase, passphrase, derivation_path)?;
+
+    write_keypair(&keypair, outfile)?;
+
+    Ok(KeypairOutput {
+        derivation_path: path_label,
+        ..KeypairOutput::new("RECOVERED KEYPAIR", &keypair, outfile)
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyOutput {
+    pub pubkey: String,
+    pub path: String,
+}
+
+impl CommandOutput for VerifyOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("KEYPAIR VERIFIED");
+        table
+            .field("Pubkey", &self.pubkey)
+            .field("File", &self.path)
+            .field("Result", TableCell::new("Match").fg(Color::Green));
+
+        vec![table]
+    }
+}
+
+/// Signs a message with the signer at `path` and checks the signature
+/// against `pubkey`, failing when they do not match.
+fn verify_keypair(path: &Path, pubkey: &Pubkey) -> anyhow::Result<VerifyOutput> {
+    let signer = load_signer(path)?;
+
+    let message = b"scilla keypair verification";
+    let signature = signer.try_sign_message(message)?;
+    if !signature.verify(pubkey.as_ref(), message) {
+        bail!(
+            "{} holds the keypair for {}, not {pubkey}",
+            path.display(),
+            signer.pubkey()
+        );
+    }
+
+    Ok(VerifyOutput {
+        pubkey: pubkey.to_string(),
+        path: path.display().to_string(),
+    })
+}
+
+/// Text forms a keypair is handed around in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeypairFormat {
+    /// A JSON array of the 64 keypair bytes, as in Solana keypair files
+    Json,
+    /// The 64 keypair bytes in base58, as browser wallets export them
+    Base58,
+    /// The 64 keypair bytes in hex
+    Hex,
+    /// Only the pubkey, safe to share
+    Pubkey,
+}
+
+impl fmt::Display for KeypairFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KeypairFormat::Json => "JSON byte array",
+            KeypairFormat::Base58 => "Base58 secret key",
+            KeypairFormat::Hex => "Hex secret key",
+            KeypairFormat::Pubkey => "Pubkey only",
+        })
+    }
+}
+
+impl KeypairFormat {
+    pub fn all() -> Vec<Self> {
+        vec![
+            KeypairFormat::Json,
+            KeypairFormat::Base58,
+            KeypairFormat::Hex,
+            KeypairFormat::Pubkey,
+        ]
+    }
+
+    /// Whether this form holds the secret key.
+    pub fn is_secret(self) -> bool {
+        self != KeypairFormat::Pubkey
+    }
+
+    /// Guesses the format of a secret key.
+    fn detect(s: &str) -> Self {
+        if s.starts_with('[') {
+            KeypairFormat::Json
+        } else if s.len() == 2 * KEYPAIR_LENGTH && s.chars().all(|c| c.is_ascii_hexdigit()) {
+            KeypairFormat::Hex
+        } else {
+            KeypairFormat::Base58
+        }
+    }
+
+    pub fn encode(self, keypair: &Keypair) -> String {
+        let bytes = keypair.to_bytes();
+        match self {
+            KeypairFormat::Json => {
+                serde_json::to_string(&bytes.to_vec()).expect("bytes always serialize")
+            }
+            KeypairFormat::Base58 => keypair.to_base58_string(),
+            KeypairFormat::Hex => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+            KeypairFormat::Pubkey => keypair.pubkey().to_string(),
+        }
+    }
+
+    pub fn decode(self, s: &str) -> anyhow::Result<Keypair> {
+        let s = s.trim();
+        let bytes = match self {
+            KeypairFormat::Json => {
+                serde_json::from_str::<Vec<u8>>(s).context("Invalid JSON byte array")?
+            }
+            KeypairFormat::Base58 => bs58::decode(s)
+                .into_vec()
+                .map_err(|_| anyhow!("Invalid base58 secret key"))?,
+            KeypairFormat::Hex => decode_hex(s)?,
+            KeypairFormat::Pubkey => bail!("A pubkey cannot be turned back into a keypair"),
+        };
+        if bytes.len() != KEYPAIR_LENGTH {
+            bail!(
+                "Expected a {KEYPAIR_LENGTH}-byte secret key, got {} bytes",
+                bytes.len()
+            );
+        }
+
+        Keypair::try_from(bytes.as_slice()).map_err(|e| anyhow!("Invalid keypair: {e}"))
+    }
+}
+
+fn decode_hex(s: &str) -> anyhow::Result<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if !s.len().is_multiple_of(2) {
+        bail!("Invalid hex secret key");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| anyhow!("Invalid hex secret key"))
+        })
+        .collect()
+}
+
+/// Parses a secret key in any [`KeypairFormat`], telling which one from its
+/// shape.
+pub fn parse_keypair(s: &str) -> anyhow::Result<Keypair> {
+    let s = s.trim();
+    KeypairFormat::detect(s).decode(s)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOutput {
+    pub pubkey: String,
+    pub format: String,
+    /// The export itself, when it is not written to a file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip)]
+    secret: bool,
+}
+
+impl CommandOutput for ExportOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("EXPORTED KEYPAIR");
+        table
+            .field("Pubkey", &self.pubkey)
+            .field("Format", &self.format);
+        if let Some(value) = &self.value {
+            let cell = TableCell::new(value);
+            table.field(
+                "Value",
+                if self.secret {
+                    cell.fg(Color::Yellow)
+                } else {
+                    cell
+                },
+            );
+        }
+        if let Some(path) = &self.path {
+            table.field("File", path);
+        }
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        for table in self.tables() {
+            table.print();
+        }
+        if self.secret {
+            println!(
+                "{}",
+                style(
+                    "This is the secret key. Anyone who has it controls the keypair: never paste \
+                     it into websites or chats, and delete copies you no longer need."
+                )
+                .yellow()
+                .bold()
+            );
+        }
+    }
+
+    fn has_secrets(&self) -> bool {
+        self.secret && self.value.is_some()
+    }
+}
+
+/// Writes a secret key given as text to a keypair file.
+fn import_keypair(
+    secret: &str,
+    format: Option<KeypairFormat>,
+    outfile: &Path,
+) -> anyhow::Result<KeypairOutput> {
+    let secret = secret.trim();
+    if secret.split_whitespace().count() >= 12 {
+        bail!("This looks like a seed phrase. Use `scilla keypair recover` for those");
+    }
+    let keypair = match format {
+        Some(format) => format.decode(secret)?,
+        None => parse_keypair(secret)?,
+    };
+
+    write_keypair(&keypair, outfile)?;
+
+    Ok(KeypairOutput::new("IMPORTED KEYPAIR", &keypair, outfile))
+}
+
+fn export_keypair(
+    path: &Path,
+    format: KeypairFormat,
+    outfile: Option<&Path>,
+) -> anyhow::Result<ExportOutput> {
+    let keypair = read_keypair_from_path(path)?;
+    let value = format.encode(&keypair);
+
+    if let Some(outfile) = outfile {
+        write_private_file(outfile, &value)
+            .with_context(|| format!("Failed to write {}", outfile.display()))?;
+    }
+
+    Ok(ExportOutput {
+        pubkey: keypair.pubkey().to_string(),
+        format: format.to_string(),
+        value: outfile.is_none().then_some(value),
+        path: outfile.map(|outfile| outfile.display().to_string()),
+        secret: format.is_secret(),
+    })
+}
+
+/// Encrypts the plain keypair at `path` into a keystore at `outfile`, which
+/// may be `path` itself.
+fn encrypt_keypair(
+    path: &Path,
+    outfile: &Path,
+    password: &str,
+    rounds: u32,
+) -> anyhow::Result<KeypairOutput> {
+    let keypair = read_keypair_from_path(path)?;
+
+    Keystore::encrypt(&keypair, password, rounds)?.write(outfile)?;
+    remember_password(outfile, password);
+
+    Ok(KeypairOutput::new("ENCRYPTED KEYPAIR", &keypair, outfile))
+}
+
+fn decrypt_keystore(path: &Path, outfile: &Path) -> anyhow::Result<KeypairOutput> {
+    let Some(keystore) = Keystore::read(path)? else {
+        bail!("{} is not an encrypted keystore", path.display());
+    };
+    let keypair = unlock_keystore(path, &keystore)?;
+
+    write_keypair(&keypair, outfile)?;
+
+    Ok(KeypairOutput::new("DECRYPTED KEYPAIR", &keypair, outfile))
+}
+
+/// Encrypts the keystore at `path` again under `password`, with a fresh salt
+/// and nonce.
+fn change_password(
+    path: &Path,
+    current: &str,
+    password: &str,
+    rounds: u32,
+) -> anyhow::Result<KeypairOutput> {
+    let Some(keystore) = Keystore::read(path)? else {
+        bail!("{} is not an encrypted keystore", path.display());
+    };
+    let keypair = keystore.decrypt(current)?;
+
+    Keystore::encrypt(&keypair, password, rounds)?.write(path)?;
+    remember_password(path, password);
+
+    Ok(KeypairOutput::new("PASSWORD CHANGED", &keypair, path))
+}
+
+/// A vanity pattern for the base58 pubkey.
+#[derive(Debug, Clone, PartialEq)]
+struct VanityMatcher {
+    prefix: String,
+    suffix: String,
+    ignore_case: bool,
+}
+
+impl VanityMatcher {
+    fn new(prefix: String, suffix: String, ignore_case: bool) -> anyhow::Result<Self> {
+        let (prefix, suffix) = (prefix.trim(), suffix.trim());
+        if prefix.is_empty() && suffix.is_empty() {
+            bail!("Give a prefix, a suffix or both");
+        }
+        // 0, O, I and l are left out of base58 to avoid look-alikes
+        if let Some(c) = prefix
+            .chars()
+            .chain(suffix.chars())
+            .find(|c| !is_base58(*c, ignore_case))
+        {
+            bail!("'{c}' never appears in a pubkey, which is base58 encoded");
+        }
+        if prefix.len() + suffix.len() > 44 {
+            bail!("A pubkey is at most 44 characters long");
+        }
+
+        let fold = |s: &str| {
+            if ignore_case {
+                s.to_lowercase()
+            } else {
+                s.to_string()
+            }
+        };
+        Ok(Self {
+            prefix: fold(prefix),
+            suffix: fold(suffix),
+            ignore_case,
+        })
+    }
+
+    fn matches(&self, pubkey: &str) -> bool {
+        let pubkey = if self.ignore_case {
+            Cow::Owned(pubkey.to_lowercase())
+        } else {
+            Cow::Borrowed(pubkey)
+        };
+        pubkey.starts_with(&self.prefix) && pubkey.ends_with(&self.suffix)
+    }
+
+    fn describe(&self) -> String {
+        let pattern = match (self.prefix.is_empty(), self.suffix.is_empty()) {
+            (false, true) => format!("{}…", self.prefix),
+            (true, false) => format!("…{}", self.suffix),
+            _ => format!("{}…{}", self.prefix, self.suffix),
+        };
+        if self.ignore_case {
+            format!("{pattern} (any case)")
+        } else {
+            pattern
+        }
+    }
+}
+
+fn is_base58(c: char, ignore_case: bool) -> bool {
+    let in_alphabet = |c: char| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l');
+    if ignore_case {
+        in_alphabet(c.to_ascii_lowercase()) || in_alphabet(c.to_ascii_uppercase())
+    } else {
+        in_alphabet(c)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrindOutput {
+    pub pattern: String,
+    pub attempts: u64,
+    pub seconds: f64,
+    pub keypairs: Vec<GrindMatch>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrindMatch {
+    pub pubkey: String,
+    pub path: String,
+}
+
+impl CommandOutput for GrindOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut summary = TableView::key_value("VANITY GRIND");
+        summary
+            .field("Pattern", &self.pattern)
+            .field("Keys Tried", self.attempts)
+            .field("Time", format!("{:.1}s", self.seconds));
+
+        let mut keypairs = TableView::new("KEYPAIRS", ["Pubkey", "File"]);
+        for found in &self.keypairs {
+            keypairs.row([
+                TableCell::new(&found.pubkey).fg(Color::Green),
+                TableCell::new(&found.path),
+            ]);
+        }
+
+        vec![summary, keypairs]
+    }
+}
+
+/// Generates keypairs on `threads` threads until `count` of them match, and
+/// writes each match to `outdir` as soon as it is found so that an
+/// interrupted grind keeps what it found.
+fn grind_keypairs(
+    matcher: &VanityMatcher,
+    count: usize,
+    threads: usize,
+    outdir: &Path,
+) -> anyhow::Result<GrindOutput> {
+    let progress = ProgressBar::new(count as u64);
+    progress.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.cyan} [{elapsed_precise}] {pos}/{len} found for {prefix} · {msg}",
+        )
+        .unwrap()
+        .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "),
+    );
+    progress.set_prefix(matcher.describe());
+
+    let attempts = AtomicU64::new(0);
+    let done = AtomicBool::new(false);
+    let found = Mutex::new(Vec::with_capacity(count));
+    let failure = OnceLock::new();
+    let started = Instant::now();
+
+    let panicked = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    while !done.load(Ordering::Relaxed) {
+                        for _ in 0..GRIND_BATCH {
+                            let keypair = Keypair::new();
+                            let pubkey = keypair.pubkey().to_string();
+                            if !matcher.matches(&pubkey) {
+                                continue;
+                            }
+
+                            // The list stays consistent even if another
+                            // worker panicked while holding the lock
+                            let mut found = found.lock().unwrap_or_else(PoisonError::into_inner);
+                            if found.len() < count {
+                                let path = outdir.join(format!("{pubkey}.json"));
+                                if let Err(e) = write_keypair(&keypair, &path) {
+                                    let _ = failure.set(e);
+                                    done.store(true, Ordering::Relaxed);
+                                    break;
+                                }
+                                found.push(GrindMatch {
+                                    pubkey,
+                                    path: path.display().to_string(),
+                                });
+                                progress.inc(1);
+                            }
+                            if found.len() >= count {
+                                done.store(true, Ordering::Relaxed);
+                                break;
+                            }
+                        }
+                        attempts.fetch_add(GRIND_BATCH, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+
+        // Stops waiting once every worker is gone, even if none of them
+        // could finish the grind
+        while !done.load(Ordering::Relaxed) && !workers.iter().all(|w| w.is_finished()) {
+            thread::sleep(Duration::from_millis(100));
+            let tried = attempts.load(Ordering::Relaxed);
+            let rate = tried as f64 / started.elapsed().as_secs_f64().max(f64::EPSILON);
+            progress.set_message(format!("{tried} keys tried, {rate:.0}/s"));
+        }
+        done.store(true, Ordering::Relaxed);
+
+        // Every worker is joined so that a panic is reported instead of
+        // being resumed when the scope ends
+        let joined: Vec<_> = workers.into_iter().map(|worker| worker.join()).collect();
+        joined.iter().any(Result::is_err)
+    });
+    progress.finish_and_clear();
+
+    let keypairs = found.into_inner().unwrap_or_else(PoisonError::into_inner);
+    if let Some(e) = failure.into_inner() {
+        return Err(e.context(format!("{} keypair(s) were written", keypairs.len())));
+    }
+    if panicked && keypairs.len() < count {
+        bail!(
+            "A grinding thread panicked, {} keypair(s) were written to {}",
+            keypairs.len(),
+            outdir.display()
+        );
+    }
+
+    Ok(GrindOutput {
+        pattern: matcher.describe(),
+        attempts: attempts.into_inner(),
+        seconds: started.elapsed().as_secs_f64(),
+        keypairs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::misc::{helpers::is_world_readable, keystore::MIN_KDF_ROUNDS},
+        tempfile::TempDir,
+    };
+
+    // BIP39 test vector from the Trezor reference implementation
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                          abandon abandon about";
+
+    #[test]
+    fn test_recover_matches_generated_derivation() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE, Language::English).unwrap();
+        let seed = Seed::new(&mnemonic, "TREZOR");
+        let generated = keypair_from_seed(seed.as_bytes()).unwrap();
+
+        let recovered = derive_keypair(PHRASE, "TREZOR", None).unwrap();
+        assert_eq!(recovered.pubkey(), generated.pubkey());
+
+        // A passphrase or a derivation path gives a different key
+        assert_ne!(
+            derive_keypair(PHRASE, "", None).unwrap().pubkey(),
+            generated.pubkey()
+        );
+        let derived = derive_keypair(PHRASE, "TREZOR", Some(DerivationPath::default())).unwrap();
+        assert_ne!(derived.pubkey(), generated.pubkey());
+    }
+
+    #[test]
+    fn test_parse_derivation_path() {
+        let key_path = parse_derivation_path("0/0").unwrap();
+        let absolute = parse_derivation_path("m/44'/501'/0'/0'").unwrap();
+        assert_eq!(key_path, absolute);
+        assert_eq!(
+            parse_derivation_path("m/44'/501'").unwrap(),
+            DerivationPath::default()
+        );
+        assert!(parse_derivation_path("0/0/0").is_err());
+    }
+
+    #[test]
+    fn test_normalize_phrase() {
+        assert_eq!(
+            normalize_phrase("  Abandon   ABOUT\n zoo "),
+            "abandon about zoo"
+        );
+    }
+
+    #[test]
+    fn test_vanity_matcher() {
+        let matcher = VanityMatcher::new("ab".into(), "".into(), false).unwrap();
+        assert!(matcher.matches("abc1"));
+        assert!(!matcher.matches("Abc1"));
+
+        let matcher = VanityMatcher::new("AB".into(), "Z".into(), true).unwrap();
+        assert!(matcher.matches("aBc1z"));
+        assert!(!matcher.matches("aBc1y"));
+
+        assert!(VanityMatcher::new("".into(), " ".into(), false).is_err());
+        // 0, O, I and l are not in the base58 alphabet
+        assert!(VanityMatcher::new("S0L".into(), "".into(), false).is_err());
+        assert!(VanityMatcher::new("Sol".into(), "".into(), false).is_err());
+        // ...but `l` can still match an `L` when the case is ignored
+        assert!(VanityMatcher::new("Sol".into(), "".into(), true).is_ok());
+    }
+
+    #[test]
+    fn test_grind_writes_matches() {
+        let dir = TempDir::new().unwrap();
+        // One base58 character matches about one key in 58
+        let matcher = VanityMatcher::new("".into(), "A".into(), false).unwrap();
+
+        let output = grind_keypairs(&matcher, 2, 2, dir.path()).unwrap();
+        assert_eq!(output.keypairs.len(), 2);
+        for found in &output.keypairs {
+            assert!(found.pubkey.ends_with('A'));
+            let keypair = read_keypair_from_path(&found.path).unwrap();
+            assert_eq!(keypair.pubkey().to_string(), found.pubkey);
+        }
+    }
+
+    #[test]
+    fn test_verify_keypair() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("id.json");
+        let keypair = Keypair::new();
+        write_keypair(&keypair, &path).unwrap();
+
+        assert!(verify_keypair(&path, &keypair.pubkey()).is_ok());
+        assert!(verify_keypair(&path, &Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_keypair_formats() {
+        let keypair = Keypair::new();
+        for format in [
+            KeypairFormat::Json,
+            KeypairFormat::Base58,
+            KeypairFormat::Hex,
+        ] {
+            let encoded = format.encode(&keypair);
+            assert_eq!(KeypairFormat::detect(&encoded), format);
+            assert_eq!(format.decode(&encoded).unwrap(), keypair);
+            assert_eq!(parse_keypair(&format!(" {encoded}\n")).unwrap(), keypair);
+        }
+
+        assert_eq!(
+            KeypairFormat::Pubkey.encode(&keypair),
+            keypair.pubkey().to_string()
+        );
+        assert!(
+            KeypairFormat::Pubkey
+                .decode(&keypair.pubkey().to_string())
+                .is_err()
+        );
+        assert!(parse_keypair("[1, 2, 3]").is_err());
+        assert!(parse_keypair("not a key").is_err());
+        assert!(KeypairFormat::Hex.decode("zz").is_err());
+    }
+
+    #[test]
+    fn test_import_export() {
+        let dir = TempDir::new().unwrap();
+        let keypair = Keypair::new();
+        let path = dir.path().join("imported.json");
+
+        import_keypair(&keypair.to_base58_string(), None, &path).unwrap();
+        assert_eq!(read_keypair_from_path(&path).unwrap(), keypair);
+        assert!(import_keypair(PHRASE, None, &path).is_err());
+
+        let output = export_keypair(&path, KeypairFormat::Hex, None).unwrap();
+        assert_eq!(output.value, Some(KeypairFormat::Hex.encode(&keypair)));
+        assert!(output.has_secrets());
+
+        let exported = dir.path().join("pubkey.txt");
+        let output = export_keypair(&path, KeypairFormat::Pubkey, Some(&exported)).unwrap();
+        assert_eq!(output.value, None);
+        assert!(!output.has_secrets());
+        assert_eq!(
+            std::fs::read_to_string(&exported).unwrap(),
+            keypair.pubkey().to_string()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_keypair_tightens_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("id.json");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(is_world_readable(&path));
+
+        write_keypair(&Keypair::new(), &path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!is_world_readable(&path));
+    }
+
+    #[test]
+    fn test_keystore_commands() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("id.json");
+        let keypair = Keypair::new();
+        write_keypair(&keypair, &path).unwrap();
+
+        // Encrypted in place, and still readable this session
+        encrypt_keypair(&path, &path, "first", MIN_KDF_ROUNDS).unwrap();
+        assert!(Keystore::read(&path).unwrap().is_some());
+        assert_eq!(
+            read_keypair_from_path(&path).unwrap().pubkey(),
+            keypair.pubkey()
+        );
+
+        assert!(change_password(&path, "wrong", "second", MIN_KDF_ROUNDS).is_err());
+        change_password(&path, "first", "second", MIN_KDF_ROUNDS).unwrap();
+        let keystore = Keystore::read(&path).unwrap().unwrap();
+        assert!(keystore.decrypt("first").is_err());
+        assert!(keystore.decrypt("second").is_ok());
+
+        let plain = dir.path().join("plain.json");
+        decrypt_keystore(&path, &plain).unwrap();
+        assert!(Keystore::read(&plain).unwrap().is_none());
+        assert_eq!(
+            solana_keypair::read_keypair_file(&plain)
+                .unwrap()
+                .to_bytes(),
+            keypair.to_bytes()
+        );
+        assert!(decrypt_keystore(&plain, &path).is_err());
+    }
+}
@@ -1,35 +1,71 @@
 use {
-    crate::{commands::navigation::NavigationTarget, context::ScillaContext},
+    crate::{
+        context::ScillaContext,
+        error::{is_cancelled, is_dry_run, is_sign_only},
+    },
     console::style,
-    std::process::{ExitCode, Termination},
+    std::{
+        fmt,
+        pin::Pin,
+        process::{ExitCode, Termination},
+    },
 };
 
 pub mod account;
+pub mod address;
 pub mod cluster;
 pub mod config;
+pub mod keypair;
 pub mod main_command;
 pub mod navigation;
 pub mod program;
 pub mod stake;
+pub mod token;
 pub mod transaction;
 pub mod vote;
 
 pub enum CommandFlow {
     Processed,
-    NavigateTo(NavigationTarget),
+    /// A prompt was cancelled with Esc/Ctrl-C; the current menu is shown again.
+    Cancelled,
+    /// A command run from the command line failed and its error was printed;
+    /// the process exits with a failure code.
+    Failed,
     Exit,
 }
 
+impl CommandFlow {
+    /// Maps the result of running a command picked from a menu. Cancelling one
+    /// of its prompts is not an error, it just returns to that menu.
+    pub fn from_run(result: anyhow::Result<()>) -> anyhow::Result<CommandFlow> {
+        match result {
+            Ok(()) => Ok(CommandFlow::Processed),
+            Err(e) if is_cancelled(&e) => Ok(CommandFlow::Cancelled),
+            Err(e) if is_dry_run(&e) || is_sign_only(&e) => Ok(CommandFlow::Processed),
+            Err(e) => Err(e),
+        }
+    }
+}
+
 impl Termination for CommandFlow {
     fn report(self) -> std::process::ExitCode {
-        println!("{}", style("Goodbye 👋").dim());
+        match self {
+            CommandFlow::Exit => println!("{}", style("Goodbye 👋").dim()),
+            CommandFlow::Cancelled => println!("{}", style("Operation cancelled.").yellow()),
+            CommandFlow::Failed => return ExitCode::FAILURE,
+            CommandFlow::Processed => {}
+        }
         ExitCode::SUCCESS
     }
 }
 
-pub trait Command {
-    fn process_command(
-        &self,
-        ctx: &mut ScillaContext,
-    ) -> impl Future<Output = anyhow::Result<CommandFlow>>;
+/// Boxed so that commands from every group can sit in the same menu tree.
+pub type CommandFuture<'a> = Pin<Box<dyn Future<Output = anyhow::Result<CommandFlow>> + 'a>>;
+
+/// A leaf of the interactive menu tree. `Display` is the label shown in the
+/// menu.
+pub trait Command: fmt::Display {
+    fn spinner_msg(&self) -> &'static str;
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a>;
 }
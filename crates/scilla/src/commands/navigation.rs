@@ -1,184 +1,322 @@
 use {
     crate::{
-        commands::{Command, CommandFlow, program::ProgramCommand},
+        commands::{Command, CommandFlow},
         context::ScillaContext,
-        prompt::{
-            prompt_account_section, prompt_cluster_section, prompt_config_section,
-            prompt_main_section, prompt_program_section, prompt_stake_section,
-            prompt_transaction_section, prompt_vote_section,
-        },
+        error::is_cancelled,
+        prompt::{MenuChoice, prompt_menu},
     },
+    console::style,
     std::fmt::{self, Display},
 };
 
-pub enum NavigationTarget {
-    MainSection,
-    PreviousSection,
+/// Separator between menu names in the breadcrumb, e.g. `Main › Program › V4`.
+const BREADCRUMB_SEPARATOR: &str = " › ";
+
+/// A node of the interactive menu tree. Each command group builds its own
+/// menu, and [`main_menu`](crate::commands::main_command::main_menu) stitches
+/// them together.
+pub struct Menu {
+    name: &'static str,
+    entries: Vec<MenuEntry>,
 }
 
-/// Maximum nesting depth for navigation (root + subsections).
-/// Adjust this constant if deeper menu nesting is needed.
-const MAX_INTERACTION_DEPTH: usize = 4;
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum NavigationSection {
-    Main,
-    Account,
-    Cluster,
-
-    // Program with subcommands
-    Program,
-    ProgramLegacy,
-    ProgramV4,
-
-    Stake,
-    Vote,
-    Transaction,
-    ScillaConfig,
-    Exit,
+pub enum MenuEntry {
+    Command(Box<dyn Command>),
+    Submenu(Menu),
 }
 
-impl Display for NavigationSection {
+impl Display for MenuEntry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let label = match self {
-            NavigationSection::Main => "Main",
-            NavigationSection::Account => "Account",
-            NavigationSection::Cluster => "Cluster",
-            NavigationSection::Program => "Program",
-            NavigationSection::ProgramLegacy => "ProgramLegacy",
-            NavigationSection::ProgramV4 => "ProgramV4",
-            NavigationSection::Stake => "Stake",
-            NavigationSection::Vote => "Vote",
-            NavigationSection::Transaction => "Transaction",
-            NavigationSection::ScillaConfig => "Scilla Config",
-            NavigationSection::Exit => "Exit",
-        };
-
-        f.write_str(label)
+        match self {
+            MenuEntry::Command(command) => write!(f, "{command}"),
+            MenuEntry::Submenu(menu) => f.write_str(menu.name),
+        }
     }
 }
 
-impl NavigationSection {
-    pub async fn prompt_and_process_command(
-        &self,
-        ctx: &mut ScillaContext,
-    ) -> anyhow::Result<crate::commands::CommandFlow> {
-        match self {
-            NavigationSection::Main => {
-                let cmd = prompt_main_section()?;
-                cmd.process_command(ctx).await
-            }
-
-            NavigationSection::Account => {
-                let cmd = prompt_account_section()?;
-                cmd.process_command(ctx).await
-            }
-
-            NavigationSection::Cluster => {
-                let cmd = prompt_cluster_section()?;
-                cmd.process_command(ctx).await
-            }
-
-            NavigationSection::Program => {
-                let cmd = prompt_program_section()?;
-                cmd.process_command(ctx).await
-            }
+impl Menu {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            entries: Vec::new(),
+        }
+    }
 
-            NavigationSection::ProgramLegacy => {
-                ProgramCommand::ProgramLegacy.process_command(ctx).await
-            }
+    /// Appends `commands` in the order they should be listed.
+    pub fn commands<C>(mut self, commands: impl IntoIterator<Item = C>) -> Self
+    where
+        C: Command + 'static,
+    {
+        self.entries.extend(
+            commands
+                .into_iter()
+                .map(|command| MenuEntry::Command(Box::new(command))),
+        );
+        self
+    }
 
-            NavigationSection::ProgramV4 => ProgramCommand::ProgramV4.process_command(ctx).await,
+    pub fn submenu(mut self, menu: Menu) -> Self {
+        self.entries.push(MenuEntry::Submenu(menu));
+        self
+    }
 
-            NavigationSection::Stake => {
-                let cmd = prompt_stake_section()?;
-                cmd.process_command(ctx).await
-            }
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
 
-            NavigationSection::Vote => {
-                let cmd = prompt_vote_section()?;
-                cmd.process_command(ctx).await
-            }
+    pub fn entries(&self) -> &[MenuEntry] {
+        &self.entries
+    }
 
-            NavigationSection::Transaction => {
-                let cmd = prompt_transaction_section()?;
-                cmd.process_command(ctx).await
-            }
+    /// Every command below this menu, in menu order, for the command palette.
+    pub fn palette(&self) -> Vec<PaletteEntry> {
+        let mut entries = Vec::new();
+        self.collect_palette(&[], &[], &mut entries);
+        entries
+    }
 
-            NavigationSection::ScillaConfig => {
-                let cmd = prompt_config_section()?;
-                cmd.process_command(ctx).await // this is sync 
+    fn collect_palette(&self, path: &[usize], names: &[&str], out: &mut Vec<PaletteEntry>) {
+        for (index, entry) in self.entries.iter().enumerate() {
+            let path = [path, &[index]].concat();
+            match entry {
+                MenuEntry::Command(command) => {
+                    let label = names
+                        .iter()
+                        .map(ToString::to_string)
+                        .chain([command.to_string()])
+                        .collect::<Vec<_>>()
+                        .join(BREADCRUMB_SEPARATOR);
+                    out.push(PaletteEntry { path, label });
+                }
+                MenuEntry::Submenu(menu) => {
+                    menu.collect_palette(&path, &[names, &[menu.name]].concat(), out)
+                }
             }
-
-            NavigationSection::Exit => Ok(CommandFlow::Exit),
         }
     }
 }
 
-/// Tracks the current navigation state as a stack of sections.
-#[derive(Debug, Clone)]
-pub struct NavContext {
-    stack: Vec<NavigationSection>,
+/// A command listed in the command palette.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry {
+    /// Submenu indices from the menu the palette was built for, followed by
+    /// the index of the command itself.
+    pub path: Vec<usize>,
+    /// Where the command lives, e.g. `Vote › Show vote account`.
+    pub label: String,
 }
 
-impl Default for NavContext {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Tracks where the user is in the menu tree as the path of submenu indices
+/// from the main menu. There is no depth limit.
+#[derive(Debug, Clone, Default)]
+pub struct NavContext {
+    path: Vec<usize>,
 }
 
 impl NavContext {
-    /// Creates an empty navigation context.
+    /// Creates a context positioned at the main menu.
     pub fn new() -> Self {
-        let mut stack = Vec::with_capacity(MAX_INTERACTION_DEPTH);
-        stack.push(NavigationSection::Main);
-        Self { stack }
+        Self::default()
     }
 
-    /// Navigates back to the parent section.
-    ///
-    /// Returns `None` if already at MainSection (stack has only one element),
-    /// preserving the root section.
-    pub fn pop_and_get_previous(&mut self) -> Option<NavigationSection> {
-        self.stack.pop();
-        self.current()
+    /// Opens the submenu at `index` of the current menu.
+    pub fn push(&mut self, index: usize) {
+        self.path.push(index);
     }
 
-    /// Returns the current active section, or `None` if uninitialized.
-    pub fn current(&self) -> Option<NavigationSection> {
-        self.stack.last().copied()
+    /// Goes back to the parent menu. Returns `false` if already at the main
+    /// menu.
+    pub fn pop(&mut self) -> bool {
+        self.path.pop().is_some()
     }
 
-    /// Resets the navigation stack and sets the provided section as the current
-    /// root.
-    pub fn reset_navigation_to(&mut self, section: NavigationSection) {
-        self.stack.clear();
-        self.stack.push(section);
+    /// Jumps straight to the menu at `path`, as if each submenu had been
+    /// opened in turn.
+    pub fn set_path(&mut self, path: Vec<usize>) {
+        self.path = path;
     }
 
-    /// Resets the navigation stack and sets the provided section as the current
-    /// root.
-    pub fn reset_navigation_to_main(&mut self) {
-        self.reset_navigation_to(NavigationSection::Main);
+    /// Returns `true` if the current menu is below the main menu.
+    pub fn is_nested(&self) -> bool {
+        !self.path.is_empty()
     }
 
-    /// Pushes a new section onto the navigation stack.
-    ///
-    /// # Panics
-    /// Panics if the maximum navigation depth is exceeded.
-    pub fn checked_push(&mut self, section: NavigationSection) {
-        if self.stack.contains(&section) {
-            return;
+    /// Menus from `root` down to the current one. Indices that no longer point
+    /// at a submenu are ignored rather than trusted.
+    fn menus<'m>(&self, root: &'m Menu) -> Vec<&'m Menu> {
+        let mut menus = vec![root];
+        for &index in &self.path {
+            let parent = menus[menus.len() - 1];
+            match parent.entries.get(index) {
+                Some(MenuEntry::Submenu(menu)) => menus.push(menu),
+                _ => break,
+            }
         }
-        if self.stack.len() == MAX_INTERACTION_DEPTH {
-            panic!("navigation stack exceeded");
+        menus
+    }
+
+    pub fn current<'m>(&self, root: &'m Menu) -> &'m Menu {
+        let menus = self.menus(root);
+        menus[menus.len() - 1]
+    }
+
+    pub fn breadcrumb(&self, root: &Menu) -> String {
+        self.menus(root)
+            .iter()
+            .map(|menu| menu.name)
+            .collect::<Vec<_>>()
+            .join(BREADCRUMB_SEPARATOR)
+    }
+}
+
+/// Shows the current menu until the user exits. Cancelling a menu goes back
+/// one level, or exits from the main menu.
+pub async fn run_menus(ctx: &mut ScillaContext, root: &Menu) -> anyhow::Result<()> {
+    loop {
+        let nav = ctx.get_nav_context();
+        let menu = nav.current(root);
+        let palette = if nav.is_nested() {
+            Vec::new()
+        } else {
+            root.palette()
+        };
+        let choice = prompt_menu(ctx, &nav.breadcrumb(root), menu, nav.is_nested(), &palette);
+
+        let command = match choice {
+            Ok(MenuChoice::Entry(index)) => match &menu.entries[index] {
+                MenuEntry::Submenu(_) => {
+                    ctx.get_nav_context_mut().push(index);
+                    continue;
+                }
+                MenuEntry::Command(command) => command,
+            },
+            Ok(MenuChoice::Palette(entry)) => {
+                let mut path = palette[entry].path.clone();
+                let Some(index) = path.pop() else {
+                    continue;
+                };
+                // Land in the command's own menu, so going back afterwards
+                // works as if it had been picked from there
+                ctx.get_nav_context_mut().set_path(path);
+                match ctx.get_nav_context().current(root).entries.get(index) {
+                    Some(MenuEntry::Command(command)) => command,
+                    _ => continue,
+                }
+            }
+            Ok(MenuChoice::Back) => {
+                ctx.get_nav_context_mut().pop();
+                continue;
+            }
+            Ok(MenuChoice::Exit) => return Ok(()),
+            Err(e) if is_cancelled(&e) => {
+                if !ctx.get_nav_context_mut().pop() {
+                    return Ok(());
+                }
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let flow = command.process_command(ctx).await;
+        ctx.close_tpu_sender().await;
+        match flow? {
+            CommandFlow::Processed | CommandFlow::Failed => {}
+            CommandFlow::Cancelled => {
+                // Show the menu the cancelled command was picked from again
+                println!("{}", style("Operation cancelled.").yellow());
+            }
+            CommandFlow::Exit => return Ok(()),
         }
+    }
+}
 
-        self.stack.push(section);
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::commands::CommandFuture};
+
+    struct Noop;
+
+    impl Display for Noop {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("Noop")
+        }
     }
 
-    /// Returns `true` if the current section is nested within another section.
-    pub fn is_nested(&self) -> bool {
-        self.stack.len() >= 3
+    impl Command for Noop {
+        fn spinner_msg(&self) -> &'static str {
+            "Doing nothing…"
+        }
+
+        fn process_command<'a>(&'a self, _ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+            Box::pin(async { Ok(CommandFlow::Processed) })
+        }
+    }
+
+    fn tree() -> Menu {
+        Menu::new("Main").commands([Noop]).submenu(
+            Menu::new("Program")
+                .submenu(Menu::new("Legacy").commands([Noop]))
+                .submenu(Menu::new("V4").commands([Noop])),
+        )
+    }
+
+    #[test]
+    fn test_breadcrumb_follows_path() {
+        let root = tree();
+        let mut nav = NavContext::new();
+        assert_eq!(nav.breadcrumb(&root), "Main");
+        assert!(!nav.is_nested());
+
+        nav.push(1);
+        nav.push(1);
+        assert_eq!(nav.breadcrumb(&root), "Main › Program › V4");
+        assert_eq!(nav.current(&root).name(), "V4");
+
+        assert!(nav.pop());
+        assert_eq!(nav.breadcrumb(&root), "Main › Program");
+    }
+
+    #[test]
+    fn test_pop_at_main_and_stale_path_do_not_panic() {
+        let root = tree();
+        let mut nav = NavContext::new();
+        assert!(!nav.pop());
+
+        // Index 0 of the main menu is a command, not a submenu
+        nav.push(0);
+        for _ in 0..16 {
+            nav.push(7);
+        }
+        assert_eq!(nav.current(&root).name(), "Main");
+        assert_eq!(nav.breadcrumb(&root), "Main");
+    }
+
+    #[test]
+    fn test_palette_lists_every_command_with_its_path() {
+        let root = tree();
+        let palette = root.palette();
+        assert_eq!(
+            palette,
+            vec![
+                PaletteEntry {
+                    path: vec![0],
+                    label: "Noop".to_string(),
+                },
+                PaletteEntry {
+                    path: vec![1, 0, 0],
+                    label: "Program › Legacy › Noop".to_string(),
+                },
+                PaletteEntry {
+                    path: vec![1, 1, 0],
+                    label: "Program › V4 › Noop".to_string(),
+                },
+            ]
+        );
+
+        let mut nav = NavContext::new();
+        let mut path = palette[2].path.clone();
+        path.pop();
+        nav.set_path(path);
+        assert_eq!(nav.breadcrumb(&root), "Main › Program › V4");
     }
 }
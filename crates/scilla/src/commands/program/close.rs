@@ -1 +1,65 @@
+use {
+    super::{ProgramTxOutput, load_program, loader_state, read_authority},
+    crate::{context::ScillaContext, misc::helpers::build_and_send_tx},
+    anyhow::bail,
+    solana_loader_v3_interface::{instruction::close_any, state::UpgradeableLoaderState},
+    solana_pubkey::Pubkey,
+    std::path::PathBuf,
+};
 
+/// Closes a program, a buffer or an uninitialized account of the upgradeable
+/// loader, sending its lamports to `recipient`.
+pub(super) async fn close_account(
+    ctx: &ScillaContext,
+    address: &Pubkey,
+    recipient: &Pubkey,
+    authority: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    let authority_signer = read_authority(ctx, authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let account = ctx.rpc().get_account(address).await?;
+    let (instruction, reclaimed, success_msg) = match loader_state(address, &account)? {
+        UpgradeableLoaderState::Uninitialized => (
+            close_any(address, recipient, None, None),
+            account.lamports,
+            "Account closed successfully!",
+        ),
+        UpgradeableLoaderState::Buffer { authority_address } => {
+            match authority_address {
+                None => bail!("Buffer {address} has no authority and cannot be closed"),
+                Some(buffer_authority) if buffer_authority != authority.pubkey() => bail!(
+                    "{} cannot close buffer {address}, only {buffer_authority} can",
+                    authority.pubkey()
+                ),
+                Some(_) => {}
+            }
+            (
+                close_any(address, recipient, Some(&authority.pubkey()), None),
+                account.lamports,
+                "Buffer closed successfully!",
+            )
+        }
+        UpgradeableLoaderState::Program { .. } => {
+            let program = load_program(ctx, address).await?;
+            program.check_authority(address, &authority.pubkey())?;
+            (
+                close_any(
+                    &program.program_data,
+                    recipient,
+                    Some(&authority.pubkey()),
+                    Some(address),
+                ),
+                program.program_data_account.lamports,
+                "Program closed successfully!",
+            )
+        }
+        UpgradeableLoaderState::ProgramData { .. } => {
+            bail!("{address} holds the code of a program. Close the program account itself instead")
+        }
+    };
+
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    Ok(ProgramTxOutput::new(success_msg, signature, address).with_reclaimed(reclaimed, recipient))
+}
@@ -1 +1,386 @@
+use {
+    super::{
+        loader_state, read_authority, read_program, sibling_file,
+        writer::{check_can_write, write_program_data},
+    },
+    crate::{
+        commands::keypair::write_keypair,
+        context::ScillaContext,
+        misc::helpers::{
+            build_and_send_tx, check_minimum_balance, read_keypair_from_path, read_signer,
+        },
+        output::{CommandOutput, TableView},
+    },
+    anyhow::bail,
+    console::style,
+    serde::Serialize,
+    solana_keypair::{Keypair, Signer},
+    solana_loader_v3_interface::{
+        instruction::{create_buffer, set_upgrade_authority, write},
+        state::UpgradeableLoaderState,
+    },
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
 
+/// Buffer keypairs of upgradeable programs are saved as
+/// `<name>-buffer-keypair.json`.
+const BUFFER_KEYPAIR_SUFFIX: &str = "buffer-keypair.json";
+
+/// The keypair of the buffer a program is written to.
+pub(super) struct BufferKeypair {
+    pub(super) signer: Box<dyn Signer>,
+    /// Where a keypair Scilla generated is kept until the buffer is used up,
+    /// so an interrupted write can be resumed
+    saved_to: Option<PathBuf>,
+}
+
+impl BufferKeypair {
+    /// The `--buffer` keypair if given. Otherwise the one left next to the
+    /// program as `<name>-<suffix>` by an interrupted write, or a new one
+    /// saved there.
+    pub(super) fn resolve(
+        ctx: &ScillaContext,
+        program_path: &Path,
+        buffer: Option<PathBuf>,
+        suffix: &str,
+    ) -> anyhow::Result<Self> {
+        if let Some(path) = buffer {
+            return Ok(Self {
+                signer: read_signer(ctx, path)?,
+                saved_to: None,
+            });
+        }
+
+        let (keypair, path) = saved_buffer_keypair(program_path, suffix)?;
+        Ok(Self {
+            signer: Box::new(keypair),
+            saved_to: Some(path),
+        })
+    }
+
+    /// Forgets a generated keypair once its buffer has been used up.
+    pub(super) fn discard(self) {
+        if let Some(path) = self.saved_to {
+            // A leftover file only means the next write starts a new buffer
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// The buffer keypair kept next to the program at `program_path`, generated
+/// and saved there if there is none yet.
+fn saved_buffer_keypair(program_path: &Path, suffix: &str) -> anyhow::Result<(Keypair, PathBuf)> {
+    let path = sibling_file(program_path, suffix);
+    let keypair = if path.exists() {
+        read_keypair_from_path(&path)?
+    } else {
+        let keypair = Keypair::new();
+        write_keypair(&keypair, &path)?;
+        keypair
+    };
+
+    Ok((keypair, path))
+}
+
+/// Creates the buffer for `program`, or checks that an existing one can be
+/// resumed, then writes whatever it is still missing.
+async fn fill_buffer(
+    ctx: &ScillaContext,
+    program: &[u8],
+    buffer: &dyn Signer,
+    authority: &dyn Signer,
+) -> anyhow::Result<()> {
+    let buffer_pubkey = buffer.pubkey();
+    let buffer_len = UpgradeableLoaderState::size_of_buffer(program.len());
+
+    let existing = ctx
+        .rpc()
+        .get_account_with_commitment(&buffer_pubkey, ctx.rpc().commitment())
+        .await?
+        .value;
+    match existing {
+        Some(account) => {
+            let UpgradeableLoaderState::Buffer { authority_address } =
+                loader_state(&buffer_pubkey, &account)?
+            else {
+                bail!("{buffer_pubkey} is not a buffer account");
+            };
+            if authority_address != Some(authority.pubkey()) {
+                bail!(
+                    "Buffer {buffer_pubkey} cannot be written by {}, its authority is {}",
+                    authority.pubkey(),
+                    authority_address.map_or_else(|| "unset".to_string(), |a| a.to_string())
+                );
+            }
+            if account.data.len() != buffer_len {
+                bail!(
+                    "Buffer {buffer_pubkey} was created for a program of {} bytes, not {}. Close \
+                     it or write to another buffer",
+                    account
+                        .data
+                        .len()
+                        .saturating_sub(UpgradeableLoaderState::size_of_buffer_metadata()),
+                    program.len()
+                );
+            }
+        }
+        None => {
+            let lamports = ctx
+                .rpc()
+                .get_minimum_balance_for_rent_exemption(buffer_len)
+                .await?;
+            check_minimum_balance(ctx, ctx.pubkey(), lamports).await?;
+
+            let instructions = create_buffer(
+                ctx.pubkey(),
+                &buffer_pubkey,
+                &authority.pubkey(),
+                lamports,
+                program.len(),
+            )?;
+            build_and_send_tx(ctx, &instructions, &[ctx.keypair(), buffer]).await?;
+        }
+    }
+
+    write_program_data(
+        ctx,
+        &buffer_pubkey,
+        UpgradeableLoaderState::size_of_buffer_metadata(),
+        program,
+        &[ctx.keypair(), authority],
+        |offset, bytes| write(&buffer_pubkey, &authority.pubkey(), offset, bytes),
+    )
+    .await
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferOutput {
+    pub buffer: String,
+    pub authority: String,
+    pub program_length: usize,
+}
+
+impl CommandOutput for BufferOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("BUFFER");
+        table
+            .field("Buffer", &self.buffer)
+            .field("Authority", &self.authority)
+            .field("Program Length", format!("{} bytes", self.program_length));
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        println!(
+            "{}",
+            style("Program written to buffer successfully!")
+                .yellow()
+                .bold()
+        );
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+pub(super) async fn write_buffer(
+    ctx: &ScillaContext,
+    program_path: &Path,
+    buffer: Option<PathBuf>,
+    buffer_authority: Option<PathBuf>,
+) -> anyhow::Result<BufferOutput> {
+    check_can_write(ctx)?;
+    let program = read_program(program_path)?;
+    let authority_signer = read_authority(ctx, buffer_authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let buffer = BufferKeypair::resolve(ctx, program_path, buffer, BUFFER_KEYPAIR_SUFFIX)?;
+    let buffer_pubkey = buffer.signer.pubkey();
+    fill_buffer(ctx, &program, buffer.signer.as_ref(), authority).await?;
+    // Upgrading only needs the buffer's address and authority
+    buffer.discard();
+
+    Ok(BufferOutput {
+        buffer: buffer_pubkey.to_string(),
+        authority: authority.pubkey().to_string(),
+        program_length: program.len(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployOutput {
+    pub signature: String,
+    pub program_id: String,
+    pub program_data: String,
+    /// `None` for programs deployed as final
+    pub upgrade_authority: Option<String>,
+    pub program_length: usize,
+    pub max_program_length: usize,
+}
+
+impl CommandOutput for DeployOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("PROGRAM");
+        table
+            .field("Program ID", &self.program_id)
+            .field("Program Data", &self.program_data)
+            .field(
+                "Upgrade Authority",
+                self.upgrade_authority
+                    .as_deref()
+                    .unwrap_or("None (immutable)"),
+            )
+            .field("Program Length", format!("{} bytes", self.program_length))
+            .field(
+                "Max Program Length",
+                format!("{} bytes", self.max_program_length),
+            );
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        println!(
+            "{}\n{}",
+            style("Program deployed successfully!").yellow().bold(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+pub(super) async fn deploy_program(
+    ctx: &ScillaContext,
+    program_path: &Path,
+    program_keypair: Option<PathBuf>,
+    upgrade_authority: Option<PathBuf>,
+    max_len: Option<usize>,
+    buffer: Option<PathBuf>,
+    is_final: bool,
+) -> anyhow::Result<DeployOutput> {
+    check_can_write(ctx)?;
+    let program = read_program(program_path)?;
+    let max_len = max_len.unwrap_or(program.len());
+    if max_len < program.len() {
+        bail!(
+            "--max-len of {max_len} bytes is smaller than the program's {} bytes",
+            program.len()
+        );
+    }
+
+    let program_signer: Box<dyn Signer> = match program_keypair {
+        Some(path) => read_signer(ctx, path)?,
+        None => {
+            let path = sibling_file(program_path, "keypair.json");
+            if path.exists() {
+                read_signer(ctx, path)?
+            } else {
+                Box::new(Keypair::new())
+            }
+        }
+    };
+    let program_id = program_signer.pubkey();
+    if let Some(account) = ctx
+        .rpc()
+        .get_account_with_commitment(&program_id, ctx.rpc().commitment())
+        .await?
+        .value
+    {
+        if let Ok(UpgradeableLoaderState::Program { .. }) = loader_state(&program_id, &account) {
+            bail!("Program {program_id} is already deployed. Upgrade it instead");
+        }
+        bail!("Account {program_id} already exists and cannot hold a program");
+    }
+
+    let authority_signer = read_authority(ctx, upgrade_authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let program_lamports = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_program())
+        .await?;
+    let program_data_lamports = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_programdata(
+            max_len,
+        ))
+        .await?;
+    check_minimum_balance(ctx, ctx.pubkey(), program_lamports + program_data_lamports).await?;
+
+    let buffer = BufferKeypair::resolve(ctx, program_path, buffer, BUFFER_KEYPAIR_SUFFIX)?;
+    let buffer_pubkey = buffer.signer.pubkey();
+    fill_buffer(ctx, &program, buffer.signer.as_ref(), authority).await?;
+
+    #[allow(deprecated)]
+    let mut instructions = solana_loader_v3_interface::instruction::deploy_with_max_program_len(
+        ctx.pubkey(),
+        &program_id,
+        &buffer_pubkey,
+        &authority.pubkey(),
+        program_lamports,
+        max_len,
+    )?;
+    if is_final {
+        instructions.push(set_upgrade_authority(
+            &program_id,
+            &authority.pubkey(),
+            None,
+        ));
+    }
+
+    let signature = build_and_send_tx(
+        ctx,
+        &instructions,
+        &[ctx.keypair(), program_signer.as_ref(), authority],
+    )
+    .await?;
+    // The deploy drains the buffer into the program data account
+    buffer.discard();
+
+    Ok(DeployOutput {
+        signature: signature.to_string(),
+        program_id: program_id.to_string(),
+        program_data: solana_loader_v3_interface::get_program_data_address(&program_id).to_string(),
+        upgrade_authority: (!is_final).then(|| authority.pubkey().to_string()),
+        program_length: program.len(),
+        max_program_length: max_len,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_buffer_keypair_is_reused_until_discarded() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let program_path = dir.path().join("counter.so");
+
+        let (keypair, saved_to) = saved_buffer_keypair(&program_path, BUFFER_KEYPAIR_SUFFIX)?;
+        assert_eq!(saved_to, dir.path().join("counter-buffer-keypair.json"));
+        assert!(saved_to.exists());
+
+        // An interrupted write resumes into the same buffer
+        let (resumed, _) = saved_buffer_keypair(&program_path, BUFFER_KEYPAIR_SUFFIX)?;
+        assert_eq!(resumed.pubkey(), keypair.pubkey());
+
+        BufferKeypair {
+            signer: Box::new(resumed),
+            saved_to: Some(saved_to.clone()),
+        }
+        .discard();
+        assert!(!saved_to.exists());
+
+        Ok(())
+    }
+}
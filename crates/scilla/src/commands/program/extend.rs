@@ -1 +1,64 @@
+use {
+    super::{ProgramTxOutput, load_program, read_authority},
+    crate::{
+        context::ScillaContext,
+        misc::helpers::{build_and_send_tx, check_minimum_balance},
+    },
+    anyhow::bail,
+    solana_loader_v3_interface::{
+        instruction::extend_program_checked, state::UpgradeableLoaderState,
+    },
+    solana_pubkey::Pubkey,
+    solana_system_interface::MAX_PERMITTED_DATA_LENGTH,
+    std::path::PathBuf,
+};
 
+pub(super) async fn extend_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    additional_bytes: u32,
+    upgrade_authority: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    if additional_bytes == 0 {
+        bail!("Extend by at least one byte");
+    }
+
+    let authority_signer = read_authority(ctx, upgrade_authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let program = load_program(ctx, program_id).await?;
+    program.check_authority(program_id, &authority.pubkey())?;
+
+    let max_len = program.max_len() + additional_bytes as usize;
+    let new_len = UpgradeableLoaderState::size_of_programdata(max_len);
+    if new_len as u64 > MAX_PERMITTED_DATA_LENGTH {
+        bail!(
+            "Program data accounts hold at most {MAX_PERMITTED_DATA_LENGTH} bytes, extending by \
+             {additional_bytes} would make it {new_len}"
+        );
+    }
+
+    let rent = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(new_len)
+        .await?;
+    check_minimum_balance(
+        ctx,
+        ctx.pubkey(),
+        rent.saturating_sub(program.program_data_account.lamports),
+    )
+    .await?;
+
+    let instruction = extend_program_checked(
+        program_id,
+        &authority.pubkey(),
+        Some(ctx.pubkey()),
+        additional_bytes,
+    );
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    Ok(
+        ProgramTxOutput::new("Program data extended successfully!", signature, program_id)
+            .with_max_program_length(max_len),
+    )
+}
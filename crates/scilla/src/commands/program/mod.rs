@@ -1,14 +1,26 @@
 use {
     crate::{
-        commands::{
-            Command, CommandFlow,
-            navigation::{NavigationSection, NavigationTarget},
-        },
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
         context::ScillaContext,
-        prompt::{prompt_go_back, prompt_program_section_shared},
+        misc::helpers::{bincode_deserialize, lamports_to_sol, read_signer},
+        output::{CommandOutput, TableView},
+        prompt::{prompt_confirmation, prompt_input_data, prompt_pubkey},
+        ui::{print_error, render_with_spinner},
     },
-    anyhow::Ok,
+    anyhow::{Context, bail},
+    clap::Subcommand,
+    console::style,
     core::fmt,
+    serde::Serialize,
+    solana_account::Account,
+    solana_keypair::Signer,
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::bpf_loader_upgradeable,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
 };
 
 mod build;
@@ -16,108 +28,523 @@ mod close;
 mod deploy;
 mod extend;
 mod upgrade;
+mod v4;
+mod writer;
 
-#[derive(Debug, Clone, Copy)]
-pub enum ProgramCommand {
-    ProgramLegacy,
-    ProgramV4,
-    GoBack,
-}
+pub use v4::{ProgramV4Args, ProgramV4Command};
 
+/// Programs of the upgradeable loader (v3), the `Legacy` submenu
 #[derive(Debug, Clone, Copy)]
-pub enum ProgramShared {
+pub enum ProgramCommand {
     Deploy,
+    WriteBuffer,
     Upgrade,
     Build,
     Close,
     Extend,
-    GoBack,
 }
 
-impl ProgramShared {
-    pub fn spinner_msg(&self) -> &'static str {
-        match self {
-            ProgramShared::Deploy => "Deploying program",
-            ProgramShared::Upgrade => "Upgrading program",
-            ProgramShared::Build => "Building program",
-            ProgramShared::Close => "Closing program",
-            ProgramShared::Extend => "Extending program data",
-            ProgramShared::GoBack => "Go Back",
-        }
-    }
-}
-
-impl fmt::Display for ProgramShared {
+impl fmt::Display for ProgramCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let label = match self {
-            ProgramShared::Deploy => "Deploy",
-            ProgramShared::Upgrade => "Upgrade",
-            ProgramShared::Build => "Build",
-            ProgramShared::Close => "Close",
-            ProgramShared::Extend => "Extend",
-            ProgramShared::GoBack => "Go Back",
+            ProgramCommand::Deploy => "Deploy",
+            ProgramCommand::WriteBuffer => "Write buffer",
+            ProgramCommand::Upgrade => "Upgrade",
+            ProgramCommand::Build => "Build",
+            ProgramCommand::Close => "Close",
+            ProgramCommand::Extend => "Extend",
         };
 
         f.write_str(label)
     }
 }
 
-impl ProgramShared {
-    fn process_command(&self, _ctx: &ScillaContext) -> CommandFlow {
+impl Command for ProgramCommand {
+    fn spinner_msg(&self) -> &'static str {
         match self {
-            ProgramShared::Deploy => todo!(),
-            ProgramShared::Upgrade => todo!(),
-            ProgramShared::Build => todo!(),
-            ProgramShared::Close => todo!(),
-            ProgramShared::Extend => todo!(),
-            ProgramShared::GoBack => CommandFlow::NavigateTo(prompt_go_back()),
+            ProgramCommand::Deploy => "Deploying program…",
+            ProgramCommand::WriteBuffer => "Writing program buffer…",
+            ProgramCommand::Upgrade => "Upgrading program…",
+            ProgramCommand::Build => "Building program…",
+            ProgramCommand::Close => "Closing program account…",
+            ProgramCommand::Extend => "Extending program data…",
         }
     }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            ProgramCommand::Deploy => ProgramArgs::Deploy {
+                program_path: None,
+                program_keypair: None,
+                upgrade_authority: None,
+                max_len: None,
+                buffer: None,
+                is_final: false,
+            },
+            ProgramCommand::WriteBuffer => ProgramArgs::WriteBuffer {
+                program_path: None,
+                buffer: None,
+                buffer_authority: None,
+            },
+            ProgramCommand::Upgrade => ProgramArgs::Upgrade {
+                program_id: None,
+                buffer: None,
+                upgrade_authority: None,
+            },
+            ProgramCommand::Build => {
+                return Box::pin(async move {
+                    print_error(format!("Program {self} is not implemented yet"));
+                    Ok(CommandFlow::Processed)
+                });
+            }
+            ProgramCommand::Close => ProgramArgs::Close {
+                account: None,
+                recipient: None,
+                authority: None,
+                yes: false,
+            },
+            ProgramCommand::Extend => ProgramArgs::Extend {
+                program_id: None,
+                additional_bytes: None,
+                upgrade_authority: None,
+            },
+        };
+
+        Box::pin(async move { CommandFlow::from_run(args.run(ctx).await) })
+    }
+}
+
+/// The Program menu, with one submenu per loader.
+pub fn menu() -> Menu {
+    Menu::new("Program")
+        .submenu(Menu::new("Legacy").commands([
+            ProgramCommand::Deploy,
+            ProgramCommand::WriteBuffer,
+            ProgramCommand::Upgrade,
+            ProgramCommand::Build,
+            ProgramCommand::Close,
+            ProgramCommand::Extend,
+        ]))
+        .submenu(Menu::new("V4").commands(ProgramV4Command::all()))
+}
+
+/// Non-interactive arguments for [`ProgramCommand`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ProgramArgs {
+    /// Deploy a program from its `.so` file
+    Deploy {
+        /// Path to the program's `.so` file
+        program_path: Option<PathBuf>,
+        /// Keypair of the program address. Defaults to `<name>-keypair.json`
+        /// next to the `.so` file, or a new keypair
+        #[arg(long)]
+        program_keypair: Option<PathBuf>,
+        /// Upgrade authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        upgrade_authority: Option<PathBuf>,
+        /// Largest program the program data account can hold, in bytes.
+        /// Defaults to the size of the program
+        #[arg(long)]
+        max_len: Option<usize>,
+        /// Buffer keypair to write to, or to resume. Defaults to
+        /// `<name>-buffer-keypair.json` next to the `.so` file
+        #[arg(long)]
+        buffer: Option<PathBuf>,
+        /// Deploy without an upgrade authority, making the program immutable
+        #[arg(long = "final")]
+        is_final: bool,
+    },
+    /// Write a program to a buffer account to upgrade from later
+    WriteBuffer {
+        /// Path to the program's `.so` file
+        program_path: Option<PathBuf>,
+        /// Buffer keypair to write to, or to resume. Defaults to
+        /// `<name>-buffer-keypair.json` next to the `.so` file
+        #[arg(long)]
+        buffer: Option<PathBuf>,
+        /// Buffer authority keypair, defaults to the configured keypair. It has
+        /// to be the upgrade authority of the program to upgrade
+        #[arg(long)]
+        buffer_authority: Option<PathBuf>,
+    },
+    /// Upgrade a program with the contents of a buffer account
+    Upgrade {
+        program_id: Option<Pubkey>,
+        /// Buffer holding the new program
+        #[arg(long)]
+        buffer: Option<Pubkey>,
+        /// Upgrade authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        upgrade_authority: Option<PathBuf>,
+    },
+    /// Close a program or buffer account and reclaim its rent
+    Close {
+        /// Program or buffer address
+        account: Option<Pubkey>,
+        /// Account receiving the reclaimed SOL, defaults to the configured
+        /// keypair
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        /// Upgrade or buffer authority keypair, defaults to the configured
+        /// keypair
+        #[arg(long)]
+        authority: Option<PathBuf>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Make room for a larger program in a program's data account
+    Extend {
+        program_id: Option<Pubkey>,
+        /// Bytes to add to the program data account
+        #[arg(long)]
+        additional_bytes: Option<u32>,
+        /// Upgrade authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        upgrade_authority: Option<PathBuf>,
+    },
 }
 
-impl ProgramCommand {
-    pub fn spinner_msg(&self) -> &'static str {
+impl ProgramArgs {
+    pub fn command(&self) -> ProgramCommand {
         match self {
-            ProgramCommand::ProgramLegacy => "Managing legacy program",
-            ProgramCommand::ProgramV4 => "Managing v4 program",
-            ProgramCommand::GoBack => "Going back...",
+            ProgramArgs::Deploy { .. } => ProgramCommand::Deploy,
+            ProgramArgs::WriteBuffer { .. } => ProgramCommand::WriteBuffer,
+            ProgramArgs::Upgrade { .. } => ProgramCommand::Upgrade,
+            ProgramArgs::Close { .. } => ProgramCommand::Close,
+            ProgramArgs::Extend { .. } => ProgramCommand::Extend,
         }
     }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
+        match self {
+            ProgramArgs::Deploy {
+                program_path,
+                program_keypair,
+                upgrade_authority,
+                max_len,
+                buffer,
+                is_final,
+            } => {
+                let program_path = program_path.map_or_else(prompt_program_path, Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    deploy::deploy_program(
+                        ctx,
+                        &program_path,
+                        program_keypair,
+                        upgrade_authority,
+                        max_len,
+                        buffer,
+                        is_final,
+                    ),
+                )
+                .await?;
+            }
+            ProgramArgs::WriteBuffer {
+                program_path,
+                buffer,
+                buffer_authority,
+            } => {
+                let program_path = program_path.map_or_else(prompt_program_path, Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    deploy::write_buffer(ctx, &program_path, buffer, buffer_authority),
+                )
+                .await?;
+            }
+            ProgramArgs::Upgrade {
+                program_id,
+                buffer,
+                upgrade_authority,
+            } => {
+                let program_id =
+                    program_id.map_or_else(|| prompt_pubkey("Enter program ID:", ctx), Ok)?;
+                let buffer =
+                    buffer.map_or_else(|| prompt_pubkey("Enter buffer pubkey:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    upgrade::upgrade_program(ctx, &program_id, &buffer, upgrade_authority),
+                )
+                .await?;
+            }
+            ProgramArgs::Close {
+                account,
+                recipient,
+                authority,
+                yes,
+            } => {
+                let account = account
+                    .map_or_else(|| prompt_pubkey("Enter program or buffer pubkey:", ctx), Ok)?;
+                let recipient = recipient.unwrap_or(*ctx.pubkey());
+
+                if !yes
+                    && !prompt_confirmation(&format!(
+                        "Are you sure you want to close {account}? A closed program can never be \
+                         deployed to the same address again"
+                    ))?
+                {
+                    println!("{}", style("Close cancelled.").yellow());
+                    return Ok(());
+                }
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    close::close_account(ctx, &account, &recipient, authority),
+                )
+                .await?;
+            }
+            ProgramArgs::Extend {
+                program_id,
+                additional_bytes,
+                upgrade_authority,
+            } => {
+                let program_id =
+                    program_id.map_or_else(|| prompt_pubkey("Enter program ID:", ctx), Ok)?;
+                let additional_bytes = additional_bytes
+                    .map_or_else(|| prompt_input_data("Enter bytes to add:"), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    extend::extend_program(ctx, &program_id, additional_bytes, upgrade_authority),
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
-impl fmt::Display for ProgramCommand {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let label = match self {
-            ProgramCommand::ProgramLegacy => "Legacy Program",
-            ProgramCommand::ProgramV4 => "V4 Program",
-            ProgramCommand::GoBack => "Go Back",
-        };
+fn prompt_program_path() -> anyhow::Result<PathBuf> {
+    prompt_input_data("Enter path to the program .so file:")
+}
 
-        f.write_str(label)
+fn read_authority(
+    ctx: &ScillaContext,
+    path: Option<PathBuf>,
+) -> anyhow::Result<Option<Box<dyn Signer>>> {
+    path.map(|path| read_signer(ctx, path)).transpose()
+}
+
+/// Reads a compiled program, checking that it at least looks like an ELF
+/// file.
+fn read_program(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let program =
+        fs::read(path).with_context(|| format!("Failed to read program {}", path.display()))?;
+    if !program.starts_with(b"\x7fELF") {
+        bail!("{} is not a compiled program (.so file)", path.display());
     }
+
+    Ok(program)
 }
 
-impl Command for ProgramCommand {
-    async fn process_command(&self, ctx: &mut ScillaContext) -> anyhow::Result<CommandFlow> {
-        ctx.get_nav_context_mut()
-            .checked_push(NavigationSection::Program);
-        let res = match self {
-            ProgramCommand::ProgramLegacy => {
-                ctx.get_nav_context_mut()
-                    .checked_push(NavigationSection::ProgramLegacy);
-                let command = prompt_program_section_shared()?;
-                command.process_command(ctx)
-            }
-            ProgramCommand::ProgramV4 => {
-                ctx.get_nav_context_mut()
-                    .checked_push(NavigationSection::ProgramV4);
-                let command = prompt_program_section_shared()?;
-                command.process_command(ctx)
-            }
-            ProgramCommand::GoBack => {
-                return Ok(CommandFlow::NavigateTo(NavigationTarget::PreviousSection));
-            }
-        };
-        Ok(res)
+/// `<name>-<suffix>` next to the program at `path`, the way `cargo build-sbf`
+/// places `<name>-keypair.json` beside `<name>.so`.
+fn sibling_file(path: &Path, suffix: &str) -> PathBuf {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    path.with_file_name(format!("{name}-{suffix}"))
+}
+
+/// Decodes an account of the upgradeable loader.
+fn loader_state(address: &Pubkey, account: &Account) -> anyhow::Result<UpgradeableLoaderState> {
+    if account.owner != bpf_loader_upgradeable::id() {
+        bail!(
+            "{address} is not owned by the upgradeable loader, {} owns it",
+            account.owner
+        );
+    }
+
+    bincode_deserialize(&account.data, "upgradeable loader account")
+}
+
+/// A deployed program and the account holding its code.
+struct UpgradeableProgram {
+    program_data: Pubkey,
+    program_data_account: Account,
+    /// `None` once the program is immutable
+    authority: Option<Pubkey>,
+}
+
+impl UpgradeableProgram {
+    /// Largest program the program data account can hold.
+    fn max_len(&self) -> usize {
+        self.program_data_account
+            .data
+            .len()
+            .saturating_sub(UpgradeableLoaderState::size_of_programdata_metadata())
+    }
+
+    /// Checks that `authority` may change the program.
+    fn check_authority(&self, program_id: &Pubkey, authority: &Pubkey) -> anyhow::Result<()> {
+        match self.authority {
+            None => bail!("Program {program_id} is immutable and cannot be changed"),
+            Some(upgrade_authority) if upgrade_authority != *authority => bail!(
+                "{authority} is not the upgrade authority of program {program_id}, \
+                 {upgrade_authority} is"
+            ),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// Fetches an upgradeable program along with its program data account.
+async fn load_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+) -> anyhow::Result<UpgradeableProgram> {
+    let account = ctx.rpc().get_account(program_id).await?;
+    let UpgradeableLoaderState::Program {
+        programdata_address,
+    } = loader_state(program_id, &account)?
+    else {
+        bail!("{program_id} is not a program account");
+    };
+
+    let program_data_account = ctx
+        .rpc()
+        .get_account(&programdata_address)
+        .await
+        .with_context(|| format!("Program {program_id} has been closed"))?;
+    let UpgradeableLoaderState::ProgramData {
+        upgrade_authority_address,
+        ..
+    } = loader_state(&programdata_address, &program_data_account)?
+    else {
+        bail!("{programdata_address} is not a program data account");
+    };
+
+    Ok(UpgradeableProgram {
+        program_data: programdata_address,
+        program_data_account,
+        authority: upgrade_authority_address,
+    })
+}
+
+/// The result of a transaction sent for a program or buffer account.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramTxOutput {
+    #[serde(skip)]
+    success_msg: &'static str,
+    pub signature: String,
+    pub account: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_program_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reclaimed_lamports: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+}
+
+impl ProgramTxOutput {
+    fn new(success_msg: &'static str, signature: impl ToString, account: &Pubkey) -> Self {
+        Self {
+            success_msg,
+            signature: signature.to_string(),
+            account: account.to_string(),
+            program_length: None,
+            max_program_length: None,
+            authority: None,
+            reclaimed_lamports: None,
+            recipient: None,
+        }
+    }
+
+    fn with_program_length(mut self, program_length: usize) -> Self {
+        self.program_length = Some(program_length);
+        self
+    }
+
+    fn with_max_program_length(mut self, max_program_length: usize) -> Self {
+        self.max_program_length = Some(max_program_length);
+        self
+    }
+
+    fn with_authority(mut self, authority: &Pubkey) -> Self {
+        self.authority = Some(authority.to_string());
+        self
+    }
+
+    fn with_reclaimed(mut self, lamports: u64, recipient: &Pubkey) -> Self {
+        self.reclaimed_lamports = Some(lamports);
+        self.recipient = Some(recipient.to_string());
+        self
+    }
+}
+
+impl CommandOutput for ProgramTxOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("PROGRAM");
+        table.field("Account", &self.account);
+        if let Some(program_length) = self.program_length {
+            table.field("Program Length", format!("{program_length} bytes"));
+        }
+        if let Some(max_program_length) = self.max_program_length {
+            table.field("Max Program Length", format!("{max_program_length} bytes"));
+        }
+        if let Some(authority) = &self.authority {
+            table.field("Authority", authority);
+        }
+        if let Some(lamports) = self.reclaimed_lamports {
+            table.field("Reclaimed (SOL)", lamports_to_sol(lamports));
+        }
+        if let Some(recipient) = &self.recipient {
+            table.field("Recipient", recipient);
+        }
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        println!(
+            "{}\n{}",
+            style(self.success_msg).yellow().bold(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sibling_file_follows_cargo_build_sbf_layout() {
+        assert_eq!(
+            sibling_file(Path::new("target/deploy/counter.so"), "keypair.json"),
+            Path::new("target/deploy/counter-keypair.json")
+        );
+    }
+
+    #[test]
+    fn test_read_program_rejects_non_elf_files() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("counter.so");
+        fs::write(&path, b"not a program")?;
+
+        assert!(read_program(&path).is_err());
+
+        fs::write(&path, b"\x7fELF\x02\x01")?;
+        assert_eq!(read_program(&path)?.len(), 6);
+
+        Ok(())
     }
 }
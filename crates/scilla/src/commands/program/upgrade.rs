@@ -1 +1,66 @@
+use {
+    super::{ProgramTxOutput, load_program, loader_state, read_authority},
+    crate::{context::ScillaContext, misc::helpers::build_and_send_tx},
+    anyhow::bail,
+    solana_loader_v3_interface::{
+        instruction::{extend_program_checked, upgrade},
+        state::UpgradeableLoaderState,
+    },
+    solana_pubkey::Pubkey,
+    std::path::PathBuf,
+};
 
+pub(super) async fn upgrade_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    upgrade_authority: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    let authority_signer = read_authority(ctx, upgrade_authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let program = load_program(ctx, program_id).await?;
+    program.check_authority(program_id, &authority.pubkey())?;
+
+    let buffer_account = ctx.rpc().get_account(buffer).await?;
+    let UpgradeableLoaderState::Buffer { authority_address } =
+        loader_state(buffer, &buffer_account)?
+    else {
+        bail!("{buffer} is not a buffer account");
+    };
+    // The loader only upgrades from buffers held by the upgrade authority
+    if authority_address != Some(authority.pubkey()) {
+        bail!(
+            "Buffer {buffer} must have {} as its authority to upgrade program {program_id}",
+            authority.pubkey()
+        );
+    }
+
+    let program_len = buffer_account
+        .data
+        .len()
+        .saturating_sub(UpgradeableLoaderState::size_of_buffer_metadata());
+    let mut max_len = program.max_len();
+    if program_len > max_len {
+        // Extended on its own first, the upgrade has to see the larger
+        // account
+        let additional_bytes = u32::try_from(program_len - max_len)?;
+        let instruction = extend_program_checked(
+            program_id,
+            &authority.pubkey(),
+            Some(ctx.pubkey()),
+            additional_bytes,
+        );
+        build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+        max_len = program_len;
+    }
+
+    let instruction = upgrade(program_id, buffer, &authority.pubkey(), ctx.pubkey());
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    Ok(
+        ProgramTxOutput::new("Program upgraded successfully!", signature, program_id)
+            .with_program_length(program_len)
+            .with_max_program_length(max_len),
+    )
+}
@@ -0,0 +1,257 @@
+use {
+    super::{check_authority, load_program, loader_v4_state, program_len},
+    crate::{
+        commands::{
+            keypair::write_keypair,
+            program::{
+                ProgramTxOutput,
+                deploy::BufferKeypair,
+                read_authority, read_program, sibling_file,
+                writer::{check_can_write, write_program_data},
+            },
+        },
+        context::ScillaContext,
+        misc::helpers::{build_and_send_tx, check_minimum_balance, read_signer},
+    },
+    anyhow::bail,
+    solana_account::Account,
+    solana_instruction::Instruction,
+    solana_keypair::{Keypair, Signer},
+    solana_loader_v4_interface::{
+        DEPLOYMENT_COOLDOWN_IN_SLOTS,
+        instruction::{copy, create_buffer, deploy, retract, set_program_length, write},
+        state::{LoaderV4State, LoaderV4Status},
+    },
+    solana_pubkey::Pubkey,
+    solana_system_interface::instruction::transfer,
+    std::path::{Path, PathBuf},
+};
+
+/// Buffer keypairs of loader v4 programs are saved as
+/// `<name>-v4-buffer-keypair.json`, apart from upgradeable loader buffers.
+const BUFFER_KEYPAIR_SUFFIX: &str = "v4-buffer-keypair.json";
+
+/// The `--program-keypair` if given, otherwise `<name>-keypair.json` next to
+/// the program. A keypair generated there is kept so that an interrupted
+/// write can be resumed.
+fn program_signer(
+    ctx: &ScillaContext,
+    program_path: &Path,
+    program_keypair: Option<PathBuf>,
+) -> anyhow::Result<Box<dyn Signer>> {
+    if let Some(path) = program_keypair {
+        return read_signer(ctx, path);
+    }
+
+    let path = sibling_file(program_path, "keypair.json");
+    if path.exists() {
+        return read_signer(ctx, path);
+    }
+    let keypair = Keypair::new();
+    write_keypair(&keypair, &path)?;
+    Ok(Box::new(keypair))
+}
+
+/// Instructions resizing the retracted program `account` to hold
+/// `program_len` bytes, topping up its rent first when it grows. Lamports it
+/// no longer needs go back to the payer.
+async fn resize_instructions(
+    ctx: &ScillaContext,
+    address: &Pubkey,
+    account: &Account,
+    authority: &Pubkey,
+    program_len: usize,
+) -> anyhow::Result<Vec<Instruction>> {
+    let rent = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(LoaderV4State::program_data_offset() + program_len)
+        .await?;
+
+    let mut instructions = Vec::new();
+    if rent > account.lamports {
+        let top_up = rent - account.lamports;
+        check_minimum_balance(ctx, ctx.pubkey(), top_up).await?;
+        instructions.push(transfer(ctx.pubkey(), address, top_up));
+    }
+    instructions.push(set_program_length(
+        address,
+        authority,
+        u32::try_from(program_len)?,
+        ctx.pubkey(),
+    ));
+
+    Ok(instructions)
+}
+
+/// Creates a retracted loader v4 account of `program`'s length, or checks
+/// that an existing one can be resumed and resizes it, then writes whatever
+/// it is still missing.
+async fn fill_account(
+    ctx: &ScillaContext,
+    program: &[u8],
+    account_signer: &dyn Signer,
+    authority: &dyn Signer,
+) -> anyhow::Result<()> {
+    let address = account_signer.pubkey();
+
+    let existing = ctx
+        .rpc()
+        .get_account_with_commitment(&address, ctx.rpc().commitment())
+        .await?
+        .value;
+    match existing {
+        Some(account) => {
+            let state = loader_v4_state(&address, &account)?;
+            check_authority(&address, &state, &authority.pubkey())?;
+            if state.status != LoaderV4Status::Retracted {
+                bail!("{address} is already deployed. Redeploy it instead");
+            }
+            if program_len(&account) != program.len() {
+                let instructions = resize_instructions(
+                    ctx,
+                    &address,
+                    &account,
+                    &authority.pubkey(),
+                    program.len(),
+                )
+                .await?;
+                build_and_send_tx(ctx, &instructions, &[ctx.keypair(), authority]).await?;
+            }
+        }
+        None => {
+            let lamports = ctx
+                .rpc()
+                .get_minimum_balance_for_rent_exemption(
+                    LoaderV4State::program_data_offset() + program.len(),
+                )
+                .await?;
+            check_minimum_balance(ctx, ctx.pubkey(), lamports).await?;
+
+            // Sizing a new account sets its authority, which needs the
+            // account's own signature
+            let instructions = create_buffer(
+                ctx.pubkey(),
+                &address,
+                lamports,
+                &authority.pubkey(),
+                u32::try_from(program.len())?,
+                ctx.pubkey(),
+            );
+            build_and_send_tx(
+                ctx,
+                &instructions,
+                &[ctx.keypair(), account_signer, authority],
+            )
+            .await?;
+        }
+    }
+
+    write_program_data(
+        ctx,
+        &address,
+        LoaderV4State::program_data_offset(),
+        program,
+        &[ctx.keypair(), authority],
+        |offset, bytes| write(&address, &authority.pubkey(), offset, bytes),
+    )
+    .await
+}
+
+/// Writes a program straight into its program account, new or retracted, and
+/// deploys it.
+pub(super) async fn deploy_program(
+    ctx: &ScillaContext,
+    program_path: &Path,
+    program_keypair: Option<PathBuf>,
+    authority: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    check_can_write(ctx)?;
+    let program = read_program(program_path)?;
+    let authority_signer = read_authority(ctx, authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let program_signer = program_signer(ctx, program_path, program_keypair)?;
+    let program_id = program_signer.pubkey();
+    fill_account(ctx, &program, program_signer.as_ref(), authority).await?;
+
+    let instruction = deploy(&program_id, &authority.pubkey());
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    Ok(
+        ProgramTxOutput::new("Program deployed successfully!", signature, &program_id)
+            .with_program_length(program.len())
+            .with_authority(&authority.pubkey()),
+    )
+}
+
+/// Writes a new build of a program to a buffer, then swaps it in within a
+/// single transaction so the program is never left half written.
+pub(super) async fn redeploy_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    program_path: &Path,
+    authority: Option<PathBuf>,
+    buffer: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    check_can_write(ctx)?;
+    let program = read_program(program_path)?;
+    let authority_signer = read_authority(ctx, authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let (account, state) = load_program(ctx, program_id, &authority.pubkey()).await?;
+
+    let buffer = BufferKeypair::resolve(ctx, program_path, buffer, BUFFER_KEYPAIR_SUFFIX)?;
+    let buffer_pubkey = buffer.signer.pubkey();
+    if buffer_pubkey == *program_id {
+        bail!("The buffer has to be another account than program {program_id}");
+    }
+    fill_account(ctx, &program, buffer.signer.as_ref(), authority).await?;
+
+    let mut instructions = Vec::new();
+    if state.status == LoaderV4Status::Deployed {
+        // The loader refuses to retract a program in the slot it was
+        // deployed in
+        let slot = ctx.rpc().get_slot().await?;
+        if state.slot.saturating_add(DEPLOYMENT_COOLDOWN_IN_SLOTS) > slot {
+            bail!(
+                "Program {program_id} was deployed in slot {}, retry in a moment",
+                state.slot
+            );
+        }
+        instructions.push(retract(program_id, &authority.pubkey()));
+    }
+    if program_len(&account) != program.len() {
+        instructions.extend(
+            resize_instructions(
+                ctx,
+                program_id,
+                &account,
+                &authority.pubkey(),
+                program.len(),
+            )
+            .await?,
+        );
+    }
+    instructions.extend([
+        copy(
+            program_id,
+            &authority.pubkey(),
+            &buffer_pubkey,
+            0,
+            0,
+            u32::try_from(program.len())?,
+        ),
+        deploy(program_id, &authority.pubkey()),
+        // Closes the buffer, its rent goes back to the payer
+        set_program_length(&buffer_pubkey, &authority.pubkey(), 0, ctx.pubkey()),
+    ]);
+
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.keypair(), authority]).await?;
+    buffer.discard();
+
+    Ok(
+        ProgramTxOutput::new("Program redeployed successfully!", signature, program_id)
+            .with_program_length(program.len())
+            .with_authority(&authority.pubkey()),
+    )
+}
@@ -0,0 +1,119 @@
+use {
+    super::{load_program, loader_v4_state},
+    crate::{
+        commands::program::{ProgramTxOutput, read_authority},
+        context::ScillaContext,
+        misc::helpers::build_and_send_tx,
+    },
+    anyhow::bail,
+    solana_keypair::Signer,
+    solana_loader_v4_interface::{
+        DEPLOYMENT_COOLDOWN_IN_SLOTS,
+        instruction::{finalize, retract, transfer_authority as transfer_authority_ix},
+        state::LoaderV4Status,
+    },
+    solana_pubkey::Pubkey,
+    std::path::PathBuf,
+};
+
+pub(super) async fn retract_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    authority: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    let authority_signer = read_authority(ctx, authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let (_, state) = load_program(ctx, program_id, &authority.pubkey()).await?;
+    if state.status != LoaderV4Status::Deployed {
+        bail!("Program {program_id} is not deployed");
+    }
+    // The loader refuses to retract a program in the slot it was deployed in
+    let slot = ctx.rpc().get_slot().await?;
+    if state.slot.saturating_add(DEPLOYMENT_COOLDOWN_IN_SLOTS) > slot {
+        bail!(
+            "Program {program_id} was deployed in slot {}, retry in a moment",
+            state.slot
+        );
+    }
+
+    let instruction = retract(program_id, &authority.pubkey());
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    Ok(ProgramTxOutput::new(
+        "Program retracted successfully!",
+        signature,
+        program_id,
+    ))
+}
+
+pub(super) async fn transfer_authority(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    new_authority: &dyn Signer,
+    authority: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    let authority_signer = read_authority(ctx, authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    load_program(ctx, program_id, &authority.pubkey()).await?;
+    if new_authority.pubkey() == authority.pubkey() {
+        bail!(
+            "{} is already the authority of program {program_id}",
+            authority.pubkey()
+        );
+    }
+
+    let instruction =
+        transfer_authority_ix(program_id, &authority.pubkey(), &new_authority.pubkey());
+    let signature = build_and_send_tx(
+        ctx,
+        &[instruction],
+        &[ctx.keypair(), authority, new_authority],
+    )
+    .await?;
+
+    Ok(ProgramTxOutput::new(
+        "Program authority transferred successfully!",
+        signature,
+        program_id,
+    )
+    .with_authority(&new_authority.pubkey()))
+}
+
+pub(super) async fn finalize_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    next_version: &Pubkey,
+    authority: Option<PathBuf>,
+) -> anyhow::Result<ProgramTxOutput> {
+    let authority_signer = read_authority(ctx, authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let (_, state) = load_program(ctx, program_id, &authority.pubkey()).await?;
+    if state.status != LoaderV4Status::Deployed {
+        bail!("Program {program_id} has to be deployed to be finalized");
+    }
+    if next_version != program_id {
+        // The loader only links programs of the same authority
+        let account = ctx.rpc().get_account(next_version).await?;
+        let next = loader_v4_state(next_version, &account)?;
+        if next.status == LoaderV4Status::Finalized
+            || next.authority_address_or_next_version != authority.pubkey()
+        {
+            bail!(
+                "Next version {next_version} has to be a program of {} that is not finalized",
+                authority.pubkey()
+            );
+        }
+    }
+
+    let instruction = finalize(program_id, &authority.pubkey(), next_version);
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    Ok(ProgramTxOutput::new(
+        "Program finalized successfully!",
+        signature,
+        program_id,
+    ))
+}
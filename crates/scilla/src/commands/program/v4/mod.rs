@@ -0,0 +1,491 @@
+use {
+    super::prompt_program_path,
+    crate::{
+        commands::{Command, CommandFlow, CommandFuture},
+        context::ScillaContext,
+        misc::helpers::{lamports_to_sol, read_signer},
+        output::{CommandOutput, TableView},
+        prompt::{prompt_confirmation, prompt_keypair_path, prompt_pubkey},
+        ui::render_with_spinner,
+    },
+    anyhow::{Context, bail},
+    clap::Subcommand,
+    console::style,
+    core::fmt,
+    serde::Serialize,
+    solana_account::Account,
+    solana_loader_v4_interface::state::{LoaderV4State, LoaderV4Status},
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::loader_v4,
+    std::path::PathBuf,
+};
+
+mod deploy;
+mod manage;
+
+/// Programs of loader v4, the `V4` submenu
+#[derive(Debug, Clone, Copy)]
+pub enum ProgramV4Command {
+    Deploy,
+    Redeploy,
+    Retract,
+    TransferAuthority,
+    Finalize,
+    Status,
+}
+
+impl ProgramV4Command {
+    pub(super) fn all() -> [ProgramV4Command; 6] {
+        [
+            ProgramV4Command::Deploy,
+            ProgramV4Command::Redeploy,
+            ProgramV4Command::Retract,
+            ProgramV4Command::TransferAuthority,
+            ProgramV4Command::Finalize,
+            ProgramV4Command::Status,
+        ]
+    }
+}
+
+impl fmt::Display for ProgramV4Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ProgramV4Command::Deploy => "Deploy",
+            ProgramV4Command::Redeploy => "Redeploy",
+            ProgramV4Command::Retract => "Retract",
+            ProgramV4Command::TransferAuthority => "Transfer authority",
+            ProgramV4Command::Finalize => "Finalize",
+            ProgramV4Command::Status => "Show status",
+        };
+
+        f.write_str(label)
+    }
+}
+
+impl Command for ProgramV4Command {
+    fn spinner_msg(&self) -> &'static str {
+        match self {
+            ProgramV4Command::Deploy => "Deploying program…",
+            ProgramV4Command::Redeploy => "Redeploying program…",
+            ProgramV4Command::Retract => "Retracting program…",
+            ProgramV4Command::TransferAuthority => "Transferring program authority…",
+            ProgramV4Command::Finalize => "Finalizing program…",
+            ProgramV4Command::Status => "Fetching program status…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            ProgramV4Command::Deploy => ProgramV4Args::Deploy {
+                program_path: None,
+                program_keypair: None,
+                authority: None,
+            },
+            ProgramV4Command::Redeploy => ProgramV4Args::Redeploy {
+                program_path: None,
+                program_id: None,
+                authority: None,
+                buffer: None,
+            },
+            ProgramV4Command::Retract => ProgramV4Args::Retract {
+                program_id: None,
+                authority: None,
+            },
+            ProgramV4Command::TransferAuthority => ProgramV4Args::TransferAuthority {
+                program_id: None,
+                new_authority: None,
+                authority: None,
+            },
+            ProgramV4Command::Finalize => ProgramV4Args::Finalize {
+                program_id: None,
+                next_version: None,
+                authority: None,
+                yes: false,
+            },
+            ProgramV4Command::Status => ProgramV4Args::Status { program_id: None },
+        };
+
+        Box::pin(async move { CommandFlow::from_run(args.run(ctx).await) })
+    }
+}
+
+/// Non-interactive arguments for [`ProgramV4Command`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ProgramV4Args {
+    /// Write a program from its `.so` file to a new or retracted program
+    /// account and deploy it
+    Deploy {
+        /// Path to the program's `.so` file
+        program_path: Option<PathBuf>,
+        /// Keypair of the program address. Defaults to `<name>-keypair.json`
+        /// next to the `.so` file, generated there if missing
+        #[arg(long)]
+        program_keypair: Option<PathBuf>,
+        /// Program authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        authority: Option<PathBuf>,
+    },
+    /// Replace a deployed program with a new build, written to a buffer first
+    /// so the program keeps running meanwhile
+    Redeploy {
+        /// Path to the program's `.so` file
+        program_path: Option<PathBuf>,
+        /// Program to redeploy
+        #[arg(long)]
+        program_id: Option<Pubkey>,
+        /// Program authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        authority: Option<PathBuf>,
+        /// Buffer keypair to write to, or to resume. Defaults to
+        /// `<name>-v4-buffer-keypair.json` next to the `.so` file
+        #[arg(long)]
+        buffer: Option<PathBuf>,
+    },
+    /// Take a program out of service so it can be written to
+    Retract {
+        program_id: Option<Pubkey>,
+        /// Program authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        authority: Option<PathBuf>,
+    },
+    /// Hand a program over to a new authority, which has to sign as well
+    TransferAuthority {
+        program_id: Option<Pubkey>,
+        /// Keypair of the new authority
+        #[arg(long)]
+        new_authority: Option<PathBuf>,
+        /// Current authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        authority: Option<PathBuf>,
+    },
+    /// Make a deployed program immutable
+    Finalize {
+        program_id: Option<Pubkey>,
+        /// Program recorded as the next version of this one, defaults to the
+        /// program itself
+        #[arg(long)]
+        next_version: Option<Pubkey>,
+        /// Program authority keypair, defaults to the configured keypair
+        #[arg(long)]
+        authority: Option<PathBuf>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Show a program's authority, status and length
+    Status { program_id: Option<Pubkey> },
+}
+
+impl ProgramV4Args {
+    pub fn command(&self) -> ProgramV4Command {
+        match self {
+            ProgramV4Args::Deploy { .. } => ProgramV4Command::Deploy,
+            ProgramV4Args::Redeploy { .. } => ProgramV4Command::Redeploy,
+            ProgramV4Args::Retract { .. } => ProgramV4Command::Retract,
+            ProgramV4Args::TransferAuthority { .. } => ProgramV4Command::TransferAuthority,
+            ProgramV4Args::Finalize { .. } => ProgramV4Command::Finalize,
+            ProgramV4Args::Status { .. } => ProgramV4Command::Status,
+        }
+    }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
+        match self {
+            ProgramV4Args::Deploy {
+                program_path,
+                program_keypair,
+                authority,
+            } => {
+                let program_path = program_path.map_or_else(prompt_program_path, Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    deploy::deploy_program(ctx, &program_path, program_keypair, authority),
+                )
+                .await?;
+            }
+            ProgramV4Args::Redeploy {
+                program_path,
+                program_id,
+                authority,
+                buffer,
+            } => {
+                let program_id =
+                    program_id.map_or_else(|| prompt_pubkey("Enter program ID:", ctx), Ok)?;
+                let program_path = program_path.map_or_else(prompt_program_path, Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    deploy::redeploy_program(ctx, &program_id, &program_path, authority, buffer),
+                )
+                .await?;
+            }
+            ProgramV4Args::Retract {
+                program_id,
+                authority,
+            } => {
+                let program_id =
+                    program_id.map_or_else(|| prompt_pubkey("Enter program ID:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    manage::retract_program(ctx, &program_id, authority),
+                )
+                .await?;
+            }
+            ProgramV4Args::TransferAuthority {
+                program_id,
+                new_authority,
+                authority,
+            } => {
+                let program_id =
+                    program_id.map_or_else(|| prompt_pubkey("Enter program ID:", ctx), Ok)?;
+                let new_authority = new_authority.map_or_else(
+                    || prompt_keypair_path("Enter New Authority Keypair Path:", ctx),
+                    Ok,
+                )?;
+                let new_authority = read_signer(ctx, new_authority)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    manage::transfer_authority(ctx, &program_id, new_authority.as_ref(), authority),
+                )
+                .await?;
+            }
+            ProgramV4Args::Finalize {
+                program_id,
+                next_version,
+                authority,
+                yes,
+            } => {
+                let program_id =
+                    program_id.map_or_else(|| prompt_pubkey("Enter program ID:", ctx), Ok)?;
+                let next_version = next_version.unwrap_or(program_id);
+
+                if !yes
+                    && !prompt_confirmation(&format!(
+                        "Are you sure you want to finalize {program_id}? A finalized program can \
+                         never be changed again"
+                    ))?
+                {
+                    println!("{}", style("Finalize cancelled.").yellow());
+                    return Ok(());
+                }
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    manage::finalize_program(ctx, &program_id, &next_version, authority),
+                )
+                .await?;
+            }
+            ProgramV4Args::Status { program_id } => {
+                let program_id =
+                    program_id.map_or_else(|| prompt_pubkey("Enter program ID:", ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, program_status(ctx, &program_id)).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes the header loader v4 keeps in front of a program's bytes.
+fn loader_v4_state(address: &Pubkey, account: &Account) -> anyhow::Result<LoaderV4State> {
+    if account.owner != loader_v4::id() {
+        bail!(
+            "{address} is not owned by loader v4, {} owns it",
+            account.owner
+        );
+    }
+
+    // The header is the `repr(C)` layout of `LoaderV4State`, not bincode
+    let header = account
+        .data
+        .get(..LoaderV4State::program_data_offset())
+        .with_context(|| format!("{address} has not been initialized by loader v4"))?;
+    let slot = u64::from_le_bytes(header[0..8].try_into()?);
+    let authority_address_or_next_version = Pubkey::try_from(&header[8..40])?;
+    let status = match u64::from_le_bytes(header[40..48].try_into()?) {
+        0 => LoaderV4Status::Retracted,
+        1 => LoaderV4Status::Deployed,
+        2 => LoaderV4Status::Finalized,
+        status => bail!("{address} has an unknown loader v4 status {status}"),
+    };
+
+    Ok(LoaderV4State {
+        slot,
+        authority_address_or_next_version,
+        status,
+    })
+}
+
+/// Bytes of program stored in a loader v4 account.
+fn program_len(account: &Account) -> usize {
+    account
+        .data
+        .len()
+        .saturating_sub(LoaderV4State::program_data_offset())
+}
+
+/// Checks that `authority` may change a program in `state`.
+fn check_authority(
+    program_id: &Pubkey,
+    state: &LoaderV4State,
+    authority: &Pubkey,
+) -> anyhow::Result<()> {
+    if state.status == LoaderV4Status::Finalized {
+        bail!("Program {program_id} is finalized and cannot be changed");
+    }
+    if state.authority_address_or_next_version != *authority {
+        bail!(
+            "{authority} is not the authority of program {program_id}, {} is",
+            state.authority_address_or_next_version
+        );
+    }
+
+    Ok(())
+}
+
+/// Fetches a loader v4 program and checks that `authority` may change it.
+async fn load_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) -> anyhow::Result<(Account, LoaderV4State)> {
+    let account = ctx.rpc().get_account(program_id).await?;
+    let state = loader_v4_state(program_id, &account)?;
+    check_authority(program_id, &state, authority)?;
+
+    Ok((account, state))
+}
+
+fn status_label(status: LoaderV4Status) -> &'static str {
+    match status {
+        LoaderV4Status::Retracted => "Retracted",
+        LoaderV4Status::Deployed => "Deployed",
+        LoaderV4Status::Finalized => "Finalized",
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramV4StatusOutput {
+    pub program_id: String,
+    pub status: &'static str,
+    /// `None` once the program is finalized
+    pub authority: Option<String>,
+    /// Set once the program is finalized
+    pub next_version: Option<String>,
+    pub last_deployed_slot: u64,
+    pub program_length: usize,
+    pub balance_sol: f64,
+}
+
+impl CommandOutput for ProgramV4StatusOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("PROGRAM");
+        table
+            .field("Program ID", &self.program_id)
+            .field("Status", self.status)
+            .field(
+                "Authority",
+                self.authority.as_deref().unwrap_or("None (finalized)"),
+            );
+        if let Some(next_version) = &self.next_version {
+            table.field("Next Version", next_version);
+        }
+        table
+            .field("Last Deployed Slot", self.last_deployed_slot)
+            .field("Program Length", format!("{} bytes", self.program_length))
+            .field("Balance (SOL)", self.balance_sol);
+
+        vec![table]
+    }
+}
+
+async fn program_status(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+) -> anyhow::Result<ProgramV4StatusOutput> {
+    let account = ctx.rpc().get_account(program_id).await?;
+    let state = loader_v4_state(program_id, &account)?;
+
+    // A finalized program's authority field points at its next version
+    let (authority, next_version) = match state.status {
+        LoaderV4Status::Finalized => (
+            None,
+            Some(state.authority_address_or_next_version.to_string()),
+        ),
+        _ => (
+            Some(state.authority_address_or_next_version.to_string()),
+            None,
+        ),
+    };
+
+    Ok(ProgramV4StatusOutput {
+        program_id: program_id.to_string(),
+        status: status_label(state.status),
+        authority,
+        next_version,
+        last_deployed_slot: state.slot,
+        program_length: program_len(&account),
+        balance_sol: lamports_to_sol(account.lamports),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program_account(status: u64, authority: &Pubkey, program: &[u8]) -> Account {
+        let mut data = 42u64.to_le_bytes().to_vec();
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&status.to_le_bytes());
+        data.extend_from_slice(program);
+
+        Account {
+            lamports: 1,
+            data,
+            owner: loader_v4::id(),
+            executable: true,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_loader_v4_state_reads_the_program_header() -> anyhow::Result<()> {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let account = program_account(1, &authority, b"\x7fELF");
+
+        let state = loader_v4_state(&program_id, &account)?;
+        assert_eq!(state.slot, 42);
+        assert_eq!(state.authority_address_or_next_version, authority);
+        assert_eq!(state.status, LoaderV4Status::Deployed);
+        assert_eq!(program_len(&account), 4);
+
+        assert!(check_authority(&program_id, &state, &authority).is_ok());
+        assert!(check_authority(&program_id, &state, &Pubkey::new_unique()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_loader_v4_state_rejects_other_accounts() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+
+        let mut account = program_account(1, &authority, &[]);
+        account.owner = Pubkey::new_unique();
+        assert!(loader_v4_state(&program_id, &account).is_err());
+
+        let account = program_account(3, &authority, &[]);
+        assert!(loader_v4_state(&program_id, &account).is_err());
+
+        let finalized = loader_v4_state(&program_id, &program_account(2, &authority, &[]))
+            .expect("finalized programs decode");
+        assert!(check_authority(&program_id, &finalized, &authority).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use {
+    crate::{
+        context::ScillaContext,
+        misc::{compute_budget::compute_budget_instructions, helpers::send_and_confirm},
+        ui::progress_bar,
+    },
+    anyhow::{anyhow, bail},
+    futures::{StreamExt, stream},
+    solana_hash::Hash,
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+    std::{
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+};
+
+/// Largest transaction that fits in a packet: the IPv6 minimum MTU minus the
+/// IP and UDP headers.
+const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
+
+/// Write transactions in flight at once.
+const MAX_PARALLEL_WRITES: usize = 16;
+
+/// Rounds of writes sent before giving up on chunks that keep failing to land.
+const MAX_WRITE_ROUNDS: usize = 3;
+
+/// How long a blockhash signs writes before a newer one is fetched, well
+/// within the ~60s it stays valid.
+const BLOCKHASH_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// Writing a program takes one transaction per chunk, which cannot be signed
+/// offline, simulated ahead of time or chained on a single durable nonce.
+pub(super) fn check_can_write(ctx: &ScillaContext) -> anyhow::Result<()> {
+    if ctx.is_sign_only() || ctx.is_dry_run() || ctx.durable_nonce().is_some() {
+        bail!(
+            "Writing a program sends one transaction per chunk and cannot be combined with \
+             --sign-only, --dry-run or --nonce"
+        );
+    }
+
+    Ok(())
+}
+
+/// Writes `program` into `account`, whose program bytes start at
+/// `data_offset`, with one `write` instruction per chunk that fits in a
+/// transaction. Chunks the account already holds are skipped, so an
+/// interrupted write picks up where it stopped. Writes are sent in parallel
+/// behind a progress bar, and the ones that did not land are sent again.
+pub(super) async fn write_program_data(
+    ctx: &ScillaContext,
+    account: &Pubkey,
+    data_offset: usize,
+    program: &[u8],
+    signers: &[&dyn Signer],
+    write: impl Fn(u32, Vec<u8>) -> Instruction,
+) -> anyhow::Result<()> {
+    // Loader writes cost the same compute units whatever their size
+    let budget = compute_budget_instructions(ctx, &[write(0, Vec::new())]).await?;
+    let chunk_size = max_chunk_size(ctx.pubkey(), &budget, write(0, Vec::new()))?;
+    let chunks: Vec<(usize, &[u8])> = program
+        .chunks(chunk_size)
+        .enumerate()
+        .map(|(idx, chunk)| (idx * chunk_size, chunk))
+        .collect();
+
+    let progress = progress_bar(chunks.len() as u64, "Writing program");
+    let blockhash = RecentBlockhash::default();
+    let mut last_error = None;
+    let mut round = 0;
+
+    loop {
+        let data = ctx.rpc().get_account_data(account).await?;
+        let written = data.get(data_offset..).unwrap_or_default();
+        let pending: Vec<_> = chunks
+            .iter()
+            .filter(|(offset, chunk)| written.get(*offset..offset + chunk.len()) != Some(*chunk))
+            .collect();
+
+        progress.set_position((chunks.len() - pending.len()) as u64);
+        if pending.is_empty() {
+            progress.finish_and_clear();
+            return Ok(());
+        }
+        if round == MAX_WRITE_ROUNDS {
+            progress.finish_and_clear();
+            let error = last_error.map_or_else(String::new, |e: anyhow::Error| format!(": {e}"));
+            bail!(
+                "{} of {} chunks could not be written{error}. Run the command again to resume",
+                pending.len(),
+                chunks.len()
+            );
+        }
+
+        let results: Vec<anyhow::Result<Signature>> = stream::iter(pending)
+            .map(|(offset, chunk)| {
+                let instruction = write(*offset as u32, chunk.to_vec());
+                let (budget, blockhash, progress) = (&budget, &blockhash, &progress);
+                async move {
+                    let signature =
+                        send_write(ctx, budget, instruction, signers, blockhash).await?;
+                    progress.inc(1);
+                    Ok(signature)
+                }
+            })
+            .buffer_unordered(MAX_PARALLEL_WRITES)
+            .collect()
+            .await;
+        last_error = results.into_iter().find_map(Result::err).or(last_error);
+        round += 1;
+    }
+}
+
+/// Program bytes a single write transaction can carry, given its compute
+/// budget instructions and signers.
+fn max_chunk_size(
+    payer: &Pubkey,
+    budget: &[Instruction],
+    empty_write: Instruction,
+) -> anyhow::Result<usize> {
+    let instructions = [budget, &[empty_write]].concat();
+    let tx = Transaction::new_unsigned(Message::new(&instructions, Some(payer)));
+    // The length prefix of the instruction data grows by a byte past 127
+    // bytes
+    let overhead = bincode::serialized_size(&tx)? as usize + 1;
+
+    PACKET_DATA_SIZE
+        .checked_sub(overhead)
+        .filter(|size| *size > 0)
+        .ok_or_else(|| anyhow!("A write transaction leaves no room for program data"))
+}
+
+async fn send_write(
+    ctx: &ScillaContext,
+    budget: &[Instruction],
+    write: Instruction,
+    signers: &[&dyn Signer],
+    blockhash: &RecentBlockhash,
+) -> anyhow::Result<Signature> {
+    let instructions = [budget, &[write]].concat();
+    let mut tx = Transaction::new_unsigned(Message::new(&instructions, Some(ctx.pubkey())));
+    tx.try_sign(signers, blockhash.get(ctx).await?)?;
+
+    send_and_confirm(ctx, &tx).await
+}
+
+/// The blockhash writes are signed with, shared by the writes in flight and
+/// renewed once it gets old so long uploads do not outlive it.
+#[derive(Default)]
+struct RecentBlockhash(Mutex<Option<(Hash, Instant)>>);
+
+impl RecentBlockhash {
+    async fn get(&self, ctx: &ScillaContext) -> anyhow::Result<Hash> {
+        let cached = *self
+            .0
+            .lock()
+            .map_err(|_| anyhow!("Blockhash cache poisoned"))?;
+        if let Some((hash, fetched)) = cached
+            && fetched.elapsed() < BLOCKHASH_MAX_AGE
+        {
+            return Ok(hash);
+        }
+
+        // Writes racing here may each fetch one, any of them will do
+        let hash = ctx.rpc().get_latest_blockhash().await?;
+        if let Ok(mut cached) = self.0.lock() {
+            *cached = Some((hash, Instant::now()));
+        }
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_keypair::Keypair, solana_loader_v3_interface::instruction::write};
+
+    #[test]
+    fn test_max_chunk_size_fills_a_packet() -> anyhow::Result<()> {
+        let payer = Keypair::new();
+        let authority = Keypair::new();
+        let buffer = Pubkey::new_unique();
+        let empty_write = write(&buffer, &authority.pubkey(), 0, Vec::new());
+
+        let chunk_size = max_chunk_size(&payer.pubkey(), &[], empty_write)?;
+
+        let full_write = write(&buffer, &authority.pubkey(), 0, vec![0xff; chunk_size]);
+        let mut tx = Transaction::new_unsigned(Message::new(&[full_write], Some(&payer.pubkey())));
+        tx.try_sign(&[&payer, &authority], Hash::new_unique())?;
+        assert_eq!(bincode::serialized_size(&tx)? as usize, PACKET_DATA_SIZE);
+
+        Ok(())
+    }
+}
@@ -1,25 +1,21 @@
 use {
     crate::{
-        commands::{
-            Command, CommandFlow,
-            navigation::{NavigationSection, NavigationTarget},
-        },
-        constants::{
-            ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_EPOCH_LIMIT, LAMPORTS_PER_SOL,
-            STAKE_HISTORY_SYSVAR_ADDR,
-        },
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
+        constants::{ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_EPOCH_LIMIT, STAKE_HISTORY_SYSVAR_ADDR},
         context::ScillaContext,
         misc::helpers::{
             SolAmount, bincode_deserialize, bincode_deserialize_with_limit, build_and_send_tx,
-            check_minimum_balance, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path, sol_to_lamports,
+            check_minimum_balance, fetch_account_with_epoch, lamports_to_sol, read_signer,
+            sol_to_lamports,
         },
-        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path},
-        ui::show_spinner,
+        output::{CommandOutput, TableCell, TableView},
+        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path, prompt_pubkey},
+        ui::render_with_spinner,
     },
     anyhow::{anyhow, bail},
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    clap::{Args, Subcommand},
     console::style,
+    serde::Serialize,
     solana_clock::Clock,
     solana_keypair::Signer,
     solana_pubkey::Pubkey,
@@ -35,7 +31,7 @@ use {
         state::{Authorized, Lockup, Meta, StakeActivationStatus, StakeStateV2},
     },
     solana_sysvar::clock,
-    std::{fmt, ops::Div, path::PathBuf},
+    std::{fmt, path::PathBuf},
 };
 
 /// Commands related to staking operations
@@ -49,23 +45,6 @@ pub enum StakeCommand {
     Split,
     Show,
     History,
-    GoBack,
-}
-
-impl StakeCommand {
-    pub fn spinner_msg(&self) -> &'static str {
-        match self {
-            StakeCommand::Create => "Creating new stake account…",
-            StakeCommand::Delegate => "Delegating stake to validator…",
-            StakeCommand::Deactivate => "Deactivating stake (cooldown starting)…",
-            StakeCommand::Withdraw => "Withdrawing SOL from deactivated stake…",
-            StakeCommand::Merge => "Merging stake accounts…",
-            StakeCommand::Split => "Splitting stake into multiple accounts…",
-            StakeCommand::Show => "Fetching stake account details…",
-            StakeCommand::History => "Fetching stake account history…",
-            StakeCommand::GoBack => "Going back…",
-        }
-    }
 }
 
 impl fmt::Display for StakeCommand {
@@ -79,43 +58,242 @@ impl fmt::Display for StakeCommand {
             StakeCommand::Split => "Split stake account",
             StakeCommand::Show => "Show stake",
             StakeCommand::History => "View stake history",
-            StakeCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
     }
 }
 
 impl Command for StakeCommand {
-    async fn process_command(&self, ctx: &mut ScillaContext) -> anyhow::Result<CommandFlow> {
-        ctx.get_nav_context_mut()
-            .checked_push(NavigationSection::Stake);
+    fn spinner_msg(&self) -> &'static str {
+        match self {
+            StakeCommand::Create => "Creating new stake account…",
+            StakeCommand::Delegate => "Delegating stake to validator…",
+            StakeCommand::Deactivate => "Deactivating stake (cooldown starting)…",
+            StakeCommand::Withdraw => "Withdrawing SOL from deactivated stake…",
+            StakeCommand::Merge => "Merging stake accounts…",
+            StakeCommand::Split => "Splitting stake into multiple accounts…",
+            StakeCommand::Show => "Fetching stake account details…",
+            StakeCommand::History => "Fetching stake account history…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            StakeCommand::Create => StakeArgs::Create {
+                stake_account_keypair: None,
+                amount: None,
+                withdraw_authority_keypair: None,
+                lockup: LockupArgs::default(),
+            },
+            StakeCommand::Delegate => StakeArgs::Delegate {
+                stake: None,
+                vote: None,
+                stake_authority_keypair: None,
+            },
+            StakeCommand::Deactivate => StakeArgs::Deactivate {
+                stake: None,
+                yes: false,
+            },
+            StakeCommand::Withdraw => StakeArgs::Withdraw {
+                stake: None,
+                recipient: None,
+                amount: None,
+                yes: false,
+            },
+            StakeCommand::Merge => StakeArgs::Merge {
+                destination: None,
+                source: None,
+                stake_authority_keypair: None,
+            },
+            StakeCommand::Split => StakeArgs::Split {
+                stake: None,
+                split_stake: None,
+                stake_authority_keypair: None,
+                amount: None,
+            },
+            StakeCommand::Show => StakeArgs::Show { stake: None },
+            StakeCommand::History => StakeArgs::History,
+        };
+
+        Box::pin(async move { CommandFlow::from_run(args.run(ctx).await) })
+    }
+}
+
+/// The Stake menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Stake").commands([
+        StakeCommand::Create,
+        StakeCommand::Delegate,
+        StakeCommand::Deactivate,
+        StakeCommand::Withdraw,
+        StakeCommand::Merge,
+        StakeCommand::Split,
+        StakeCommand::Show,
+        StakeCommand::History,
+    ])
+}
+
+/// Non-interactive arguments for [`StakeCommand`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum StakeArgs {
+    /// Create a new stake account
+    Create {
+        #[arg(long)]
+        stake_account_keypair: Option<PathBuf>,
+        /// Amount to stake in SOL
+        #[arg(long)]
+        amount: Option<SolAmount>,
+        #[arg(long)]
+        withdraw_authority_keypair: Option<PathBuf>,
+        #[command(flatten)]
+        lockup: LockupArgs,
+    },
+    /// Delegate stake to a validator
+    Delegate {
+        /// Stake account pubkey
+        #[arg(long)]
+        stake: Option<Pubkey>,
+        /// Vote account pubkey
+        #[arg(long)]
+        vote: Option<Pubkey>,
+        #[arg(long)]
+        stake_authority_keypair: Option<PathBuf>,
+    },
+    /// Deactivate a delegated stake account
+    Deactivate {
+        /// Stake account pubkey
+        #[arg(long)]
+        stake: Option<Pubkey>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Withdraw SOL from a deactivated stake account
+    Withdraw {
+        /// Stake account pubkey
+        #[arg(long)]
+        stake: Option<Pubkey>,
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        /// Amount to withdraw in SOL
+        #[arg(long)]
+        amount: Option<SolAmount>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Merge a source stake account into a destination stake account
+    Merge {
+        #[arg(long)]
+        destination: Option<Pubkey>,
+        #[arg(long)]
+        source: Option<Pubkey>,
+        #[arg(long)]
+        stake_authority_keypair: Option<PathBuf>,
+    },
+    /// Split part of a stake account into another account
+    Split {
+        /// Stake account pubkey
+        #[arg(long)]
+        stake: Option<Pubkey>,
+        /// Split stake account pubkey
+        #[arg(long)]
+        split_stake: Option<Pubkey>,
+        #[arg(long)]
+        stake_authority_keypair: Option<PathBuf>,
+        /// Amount to split in SOL
+        #[arg(long)]
+        amount: Option<f64>,
+    },
+    /// Show stake account details
+    Show { stake: Option<Pubkey> },
+    /// View cluster stake history
+    History,
+}
+
+/// Lockup settings for [`StakeArgs::Create`]. Passing any lockup field
+/// configures a lockup; `--no-lockup` skips the lockup prompt entirely.
+#[derive(Debug, Clone, Default, Args)]
+pub struct LockupArgs {
+    #[arg(long, conflicts_with_all = ["lockup_epoch", "lockup_unix_timestamp", "lockup_custodian"])]
+    no_lockup: bool,
+    #[arg(long)]
+    lockup_epoch: Option<u64>,
+    #[arg(long)]
+    lockup_unix_timestamp: Option<i64>,
+    #[arg(long)]
+    lockup_custodian: Option<Pubkey>,
+}
+
+impl LockupArgs {
+    fn resolve(self, ctx: &ScillaContext) -> anyhow::Result<Lockup> {
+        if self.no_lockup {
+            return Ok(Lockup::default());
+        }
+
+        let configure_lockup = self.lockup_epoch.is_some()
+            || self.lockup_unix_timestamp.is_some()
+            || self.lockup_custodian.is_some()
+            || prompt_confirmation("Would you like to set up lockup configuration? (y/n): ")?;
+
+        if !configure_lockup {
+            return Ok(Lockup::default());
+        }
+
+        Ok(Lockup {
+            epoch: self
+                .lockup_epoch
+                .map_or_else(|| prompt_input_data("Enter Lockup Epoch: "), Ok)?,
+            unix_timestamp: self.lockup_unix_timestamp.map_or_else(
+                || prompt_input_data("Enter Lockup Date (Unix TimeStamp): "),
+                Ok,
+            )?,
+            custodian: self
+                .lockup_custodian
+                .map_or_else(|| prompt_pubkey("Enter Lockup Custodian Pubkey: ", ctx), Ok)?,
+        })
+    }
+}
+
+impl StakeArgs {
+    pub fn command(&self) -> StakeCommand {
+        match self {
+            StakeArgs::Create { .. } => StakeCommand::Create,
+            StakeArgs::Delegate { .. } => StakeCommand::Delegate,
+            StakeArgs::Deactivate { .. } => StakeCommand::Deactivate,
+            StakeArgs::Withdraw { .. } => StakeCommand::Withdraw,
+            StakeArgs::Merge { .. } => StakeCommand::Merge,
+            StakeArgs::Split { .. } => StakeCommand::Split,
+            StakeArgs::Show { .. } => StakeCommand::Show,
+            StakeArgs::History => StakeCommand::History,
+        }
+    }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
         match self {
-            StakeCommand::Create => {
-                let stake_account_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Stake Account Keypair Path: ", ctx);
-                let amount_sol: SolAmount = prompt_input_data("Enter amount to stake (in SOL):");
-                let withdraw_authority_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Withdraw Authority Keypair Path: ", ctx);
-                let configure_lockup: bool =
-                    prompt_input_data("Would you like to set up lockup configuration? (y/n): ");
-
-                let lockup = if configure_lockup {
-                    let epoch: u64 = prompt_input_data("Enter Lockup Epoch: ");
-                    let unix_timestamp: i64 =
-                        prompt_input_data("Enter Lockup Date (Unix TimeStamp): ");
-                    let custodian: Pubkey = prompt_input_data("Enter Lockup Custodian Pubkey: ");
-
-                    Lockup {
-                        epoch,
-                        unix_timestamp,
-                        custodian,
-                    }
-                } else {
-                    Lockup::default()
-                };
-
-                show_spinner(
-                    self.spinner_msg(),
+            StakeArgs::Create {
+                stake_account_keypair,
+                amount,
+                withdraw_authority_keypair,
+                lockup,
+            } => {
+                let stake_account_keypair_path = stake_account_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Stake Account Keypair Path: ", ctx),
+                    Ok,
+                )?;
+                let amount_sol = amount
+                    .map_or_else(|| prompt_input_data("Enter amount to stake (in SOL):"), Ok)?;
+                let withdraw_authority_keypair_path = withdraw_authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Withdraw Authority Keypair Path: ", ctx),
+                    Ok,
+                )?;
+                let lockup = lockup.resolve(ctx)?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     create_stake_account(
                         ctx,
                         stake_account_keypair_path,
@@ -124,17 +302,25 @@ impl Command for StakeCommand {
                         lockup,
                     ),
                 )
-                .await;
+                .await?;
             }
-            StakeCommand::Delegate => {
-                let stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Pubkey: ");
-                let stake_authority_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
-
-                show_spinner(
-                    self.spinner_msg(),
+            StakeArgs::Delegate {
+                stake,
+                vote,
+                stake_authority_keypair,
+            } => {
+                let stake_account_pubkey =
+                    stake.map_or_else(|| prompt_pubkey("Enter Stake Account Pubkey: ", ctx), Ok)?;
+                let vote_account_pubkey =
+                    vote.map_or_else(|| prompt_pubkey("Enter Vote Account Pubkey: ", ctx), Ok)?;
+                let stake_authority_keypair_path = stake_authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx),
+                    Ok,
+                )?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     delegate_stake_account(
                         ctx,
                         &stake_account_pubkey,
@@ -142,53 +328,78 @@ impl Command for StakeCommand {
                         stake_authority_keypair_path,
                     ),
                 )
-                .await;
+                .await?;
             }
-            StakeCommand::Deactivate => {
-                let stake_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey to Deactivate:");
-
-                if !prompt_confirmation("Are you sure you want to deactivate this stake?") {
+            StakeArgs::Deactivate { stake, yes } => {
+                let stake_pubkey = stake.map_or_else(
+                    || prompt_pubkey("Enter Stake Account Pubkey to Deactivate:", ctx),
+                    Ok,
+                )?;
+
+                if !yes && !prompt_confirmation("Are you sure you want to deactivate this stake?")?
+                {
                     println!("{}", style("Deactivation cancelled.").yellow());
-                    return Ok(CommandFlow::Processed);
+                    return Ok(());
                 }
 
-                show_spinner(
-                    self.spinner_msg(),
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     deactivate_stake_account(ctx, &stake_pubkey),
                 )
-                .await;
+                .await?;
             }
-            StakeCommand::Withdraw => {
-                let stake_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey to Withdraw from:");
-                let recipient: Pubkey = prompt_input_data("Enter Recipient Address:");
-                let amount: SolAmount = prompt_input_data("Enter Amount to Withdraw (SOL):");
-
-                if !prompt_confirmation(&format!(
-                    "Are you sure you want to withdraw {} SOL?",
-                    amount.value()
-                )) {
+            StakeArgs::Withdraw {
+                stake,
+                recipient,
+                amount,
+                yes,
+            } => {
+                let stake_pubkey = stake.map_or_else(
+                    || prompt_pubkey("Enter Stake Account Pubkey to Withdraw from:", ctx),
+                    Ok,
+                )?;
+                let recipient =
+                    recipient.map_or_else(|| prompt_pubkey("Enter Recipient Address:", ctx), Ok)?;
+                let amount = amount
+                    .map_or_else(|| prompt_input_data("Enter Amount to Withdraw (SOL):"), Ok)?;
+
+                if !yes
+                    && !prompt_confirmation(&format!(
+                        "Are you sure you want to withdraw {} SOL?",
+                        amount.value()
+                    ))?
+                {
                     println!("{}", style("Withdrawal cancelled.").yellow());
-                    return Ok(CommandFlow::Processed);
+                    return Ok(());
                 }
 
-                show_spinner(
-                    self.spinner_msg(),
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     withdraw_stake(ctx, &stake_pubkey, &recipient, amount.value()),
                 )
-                .await;
+                .await?;
             }
-            StakeCommand::Merge => {
-                let destination_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let source_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Source Stake Account Pubkey: ");
-                let stake_authority_keypair_path =
-                    prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
-
-                show_spinner(
-                    self.spinner_msg(),
+            StakeArgs::Merge {
+                destination,
+                source,
+                stake_authority_keypair,
+            } => {
+                let destination_stake_account_pubkey = destination
+                    .map_or_else(|| prompt_pubkey("Enter Stake Account Pubkey: ", ctx), Ok)?;
+                let source_stake_account_pubkey = source.map_or_else(
+                    || prompt_pubkey("Enter Source Stake Account Pubkey: ", ctx),
+                    Ok,
+                )?;
+                let stake_authority_keypair_path = stake_authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx),
+                    Ok,
+                )?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     merge_stake(
                         ctx,
                         &destination_stake_account_pubkey,
@@ -196,19 +407,32 @@ impl Command for StakeCommand {
                         &stake_authority_keypair_path,
                     ),
                 )
-                .await;
+                .await?;
             }
-            StakeCommand::Split => {
-                let stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let split_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Split Stake Account Pubkey: ");
-                let stake_authority_keypair_path =
-                    prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
-                let amount_to_split: f64 = prompt_input_data("Enter Stake Amount (SOL) to Split: ");
-
-                show_spinner(
-                    self.spinner_msg(),
+            StakeArgs::Split {
+                stake,
+                split_stake: split_stake_account,
+                stake_authority_keypair,
+                amount,
+            } => {
+                let stake_account_pubkey =
+                    stake.map_or_else(|| prompt_pubkey("Enter Stake Account Pubkey: ", ctx), Ok)?;
+                let split_stake_account_pubkey = split_stake_account.map_or_else(
+                    || prompt_pubkey("Enter Split Stake Account Pubkey: ", ctx),
+                    Ok,
+                )?;
+                let stake_authority_keypair_path = stake_authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx),
+                    Ok,
+                )?;
+                let amount_to_split = amount.map_or_else(
+                    || prompt_input_data("Enter Stake Amount (SOL) to Split: "),
+                    Ok,
+                )?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     split_stake(
                         ctx,
                         &stake_account_pubkey,
@@ -217,26 +441,24 @@ impl Command for StakeCommand {
                         amount_to_split,
                     ),
                 )
-                .await;
+                .await?;
             }
-            StakeCommand::Show => {
-                let stake_acc_pubkey: Pubkey = prompt_input_data("Enter Stake Account Pubkey:");
-                show_spinner(
-                    self.spinner_msg(),
-                    show_stake_account(ctx, &stake_acc_pubkey),
+            StakeArgs::Show { stake } => {
+                let stake_acc_pubkey =
+                    stake.map_or_else(|| prompt_pubkey("Enter Stake Account Pubkey:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    fetch_stake_account(ctx, &stake_acc_pubkey),
                 )
-                .await;
+                .await?;
             }
-            StakeCommand::History => {
-                show_spinner(self.spinner_msg(), stake_history(ctx)).await;
-            }
-
-            StakeCommand::GoBack => {
-                return Ok(CommandFlow::NavigateTo(NavigationTarget::PreviousSection));
+            StakeArgs::History => {
+                render_with_spinner(ctx, spinner_msg, stake_history(ctx)).await?;
             }
         }
 
-        Ok(CommandFlow::Processed)
+        Ok(())
     }
 }
 
@@ -246,10 +468,9 @@ async fn create_stake_account(
     amount_sol: SolAmount,
     withdraw_authority_keypair_path: PathBuf,
     lockup: Lockup,
-) -> anyhow::Result<()> {
-    let stake_account_keypair = read_keypair_from_path(stake_account_keypair_path)?;
-    let withdraw_authority_pubkey =
-        read_keypair_from_path(withdraw_authority_keypair_path)?.pubkey();
+) -> anyhow::Result<StakeAccountOutput> {
+    let stake_account_keypair = read_signer(ctx, stake_account_keypair_path)?;
+    let withdraw_authority_pubkey = read_signer(ctx, withdraw_authority_keypair_path)?.pubkey();
 
     let lamports = amount_sol.to_lamports();
 
@@ -285,171 +506,11 @@ async fn create_stake_account(
 
     let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_account_keypair]).await?;
 
-    println!(
-        "{}\n{}",
-        style("Stake Account created successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
-    );
-
-    let accounts = ctx
-        .rpc()
-        .get_multiple_accounts(&[
-            stake_account_keypair.pubkey(),
-            stake_history::id(),
-            clock::id(),
-        ])
-        .await?;
-
-    let Some(Some(stake_account)) = accounts.first() else {
-        bail!("Failed to get stake account");
-    };
-
-    let Some(Some(stake_history_account)) = accounts.get(1) else {
-        bail!("Failed to get stake account");
-    };
-
-    let Some(Some(clock_account)) = accounts.get(2) else {
-        bail!("Failed to get stake account");
-    };
-
-    let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
-
-    let stake_history: StakeHistory =
-        bincode_deserialize(&stake_history_account.data, "stake history data")?;
-
-    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
-
-    let current_epoch = clock.epoch;
-
-    // Add stake state specific information
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Stake Account Pubkey"),
-            Cell::new(stake_account_keypair.pubkey()),
-        ])
-        .add_row(vec![
-            Cell::new("Delegated Stake"),
-            Cell::new(stake_state.stake().unwrap_or_default().delegation.stake),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (SOL)"),
-            Cell::new(lamports_to_sol(stake_account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (Lamports)"),
-            Cell::new(format!("{}", stake_account.lamports)),
-        ]);
-
-    match &stake_state {
-        StakeStateV2::Uninitialized => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Uninitialized")]);
-        }
-        StakeStateV2::Initialized(Meta {
-            rent_exempt_reserve,
-            authorized,
-            lockup,
-        }) => {
-            table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Initialized")])
-                .add_row(vec![
-                    Cell::new("Rent Exempt Reserve (Lamports)"),
-                    Cell::new(format!("{rent_exempt_reserve:.9}")),
-                ])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ]);
-
-            if !lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
-        }
-        StakeStateV2::Stake(
-            Meta {
-                authorized, lockup, ..
-            },
-            stake,
-            _,
-        ) => {
-            // Activation Status
-            let StakeActivationStatus {
-                effective: _,
-                activating: _,
-                deactivating: _,
-            } = stake.delegation.stake_activating_and_deactivating(
-                current_epoch,
-                &stake_history,
-                None,
-            );
+    let output = fetch_stake_account(ctx, &stake_account_keypair.pubkey()).await?;
 
-            table
-                .add_row(vec![
-                    Cell::new("Delegation State"),
-                    Cell::new("Undelegated"),
-                ])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ]);
-
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
-        }
-        StakeStateV2::RewardsPool => {
-            bail!("Cannot withdraw from rewards pool");
-        }
-    }
-
-    println!(
-        "\n{}",
-        style("NEW STAKE ACCOUNT INFORMATION").green().bold()
-    );
-    println!("{table}");
-
-    Ok(())
+    Ok(output
+        .with_title("NEW STAKE ACCOUNT INFORMATION")
+        .with_signature("Stake Account created successfully!", signature))
 }
 
 async fn delegate_stake_account(
@@ -457,9 +518,9 @@ async fn delegate_stake_account(
     stake_account_pubkey: &Pubkey,
     vote_account_pubkey: &Pubkey,
     stake_authority_keypair_path: PathBuf,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<StakeAccountOutput> {
     let stake_account = ctx.rpc().get_account(stake_account_pubkey).await?;
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    let stake_authority_keypair = read_signer(ctx, stake_authority_keypair_path)?;
     let stake_authority_pubkey = stake_authority_keypair.pubkey();
 
     if stake_account.owner != stake_program_id() {
@@ -515,202 +576,36 @@ async fn delegate_stake_account(
     let signature =
         build_and_send_tx(ctx, &[ix], &[ctx.keypair(), &stake_authority_keypair]).await?;
 
-    println!(
-        "{}\n{}",
-        style("Stake Delegated successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
-    );
-
-    let accounts = ctx
-        .rpc()
-        .get_multiple_accounts(&[*stake_account_pubkey, stake_history::id(), clock::id()])
-        .await?;
-
-    let Some(Some(stake_account)) = accounts.first() else {
-        bail!("Failed to fetch stake account");
-    };
-
-    let Some(Some(stake_history_account)) = accounts.get(1) else {
-        bail!("Failed to fetch stake history account");
-    };
+    let output = fetch_stake_account(ctx, stake_account_pubkey).await?;
 
-    let Some(Some(clock_account)) = accounts.get(2) else {
-        bail!("Failed to fetch clock account");
-    };
-
-    let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
-
-    let stake_history: StakeHistory =
-        bincode_deserialize(&stake_history_account.data, "stake history data")?;
-
-    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
-
-    // New Stake Account Info Table
-    let current_epoch = clock.epoch;
-
-    // Add stake state specific information
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Stake Account Pubkey"),
-            Cell::new(stake_account_pubkey),
-        ])
-        .add_row(vec![
-            Cell::new("Delegated Stake"),
-            Cell::new(stake_state.stake().unwrap_or_default().delegation.stake),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (SOL)"),
-            Cell::new(lamports_to_sol(stake_account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (Lamports)"),
-            Cell::new(format!("{}", stake_account.lamports)),
-        ]);
+    Ok(output
+        .with_title("DELEGATE STAKE ACCOUNT INFORMATION")
+        .with_signature("Stake Delegated successfully!", signature))
+}
 
-    match &stake_state {
-        StakeStateV2::Uninitialized => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Uninitialized")]);
-        }
-        StakeStateV2::Initialized(Meta {
-            rent_exempt_reserve,
-            authorized,
-            lockup,
-        }) => {
-            table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Initialized")])
-                .add_row(vec![
-                    Cell::new("Rent Exempt Reserve (Lamports)"),
-                    Cell::new(format!("{rent_exempt_reserve:.9}")),
-                ])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ]);
-
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
-        }
-        StakeStateV2::Stake(
-            Meta {
-                authorized, lockup, ..
-            },
-            stake,
-            _,
-        ) => {
-            // Activation Status
-            let StakeActivationStatus {
-                effective,
-                activating: _,
-                deactivating: _,
-            } = stake.delegation.stake_activating_and_deactivating(
-                current_epoch,
-                &stake_history,
-                None,
-            );
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeactivateStakeOutput {
+    pub stake_account: String,
+    pub signature: String,
+}
 
-            table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Delegated")])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ])
-                .add_row(vec![
-                    Cell::new("Delegated Vote Account"),
-                    Cell::new(stake.delegation.voter_pubkey),
-                ])
-                .add_row(vec![
-                    Cell::new("Delegated Stake (SOL)"),
-                    Cell::new(format!(
-                        "{:.9}",
-                        (stake.delegation.stake as f64).div(LAMPORTS_PER_SOL as f64)
-                    )),
-                ])
-                .add_row(vec![
-                    Cell::new("Activation Epoch"),
-                    Cell::new(match stake.delegation.activation_epoch {
-                        epoch if epoch < u64::MAX => format!("{epoch}"),
-                        _ => "N/A".into(),
-                    }),
-                ])
-                .add_row(vec![
-                    Cell::new("Deactivation Epoch"),
-                    Cell::new(match stake.delegation.deactivation_epoch {
-                        epoch if epoch < u64::MAX => format!("{epoch}"),
-                        _ => "N/A".into(),
-                    }),
-                ])
-                .add_row(vec![
-                    Cell::new("Active Stake (SOL)"),
-                    Cell::new(format!(
-                        "{:.9}",
-                        (effective as f64).div(LAMPORTS_PER_SOL as f64)
-                    )),
-                ]);
-
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
-        }
-        StakeStateV2::RewardsPool => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Rewards Pool")]);
-        }
+impl CommandOutput for DeactivateStakeOutput {
+    fn render_table(&self) {
+        println!(
+            "{} {}\n{}\n{}",
+            style("Stake Deactivated Successfully!").green().bold(),
+            style("(Cooldown will take 1-2 epochs ≈ 2-4 days)").yellow(),
+            style(format!("Stake Account: {}", self.stake_account)).yellow(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
     }
-
-    println!(
-        "\n{}",
-        style("DELEGATE STAKE ACCOUNT INFORMATION").green().bold()
-    );
-    println!("{table}");
-    Ok(())
 }
 
 async fn deactivate_stake_account(
     ctx: &ScillaContext,
     stake_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<DeactivateStakeOutput> {
     let account = ctx.rpc().get_account(stake_pubkey).await?;
 
     if account.owner != stake_program_id() {
@@ -748,15 +643,32 @@ async fn deactivate_stake_account(
 
     let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
 
-    println!(
-        "{} {}\n{}\n{}",
-        style("Stake Deactivated Successfully!").green().bold(),
-        style("(Cooldown will take 1-2 epochs ≈ 2-4 days)").yellow(),
-        style(format!("Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("Signature: {signature}")).cyan()
-    );
+    Ok(DeactivateStakeOutput {
+        stake_account: stake_pubkey.to_string(),
+        signature: signature.to_string(),
+    })
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawStakeOutput {
+    pub stake_account: String,
+    pub recipient: String,
+    pub amount_sol: f64,
+    pub signature: String,
+}
+
+impl CommandOutput for WithdrawStakeOutput {
+    fn render_table(&self) {
+        println!(
+            "{} {}\n{}\n{}\n{}",
+            style("Stake Withdrawn Successfully!").green().bold(),
+            style(format!("From Stake Account: {}", self.stake_account)).yellow(),
+            style(format!("To Recipient: {}", self.recipient)).yellow(),
+            style(format!("Amount: {} SOL", self.amount_sol)).cyan(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+    }
 }
 
 async fn withdraw_stake(
@@ -764,7 +676,7 @@ async fn withdraw_stake(
     stake_pubkey: &Pubkey,
     recipient: &Pubkey,
     amount_sol: f64,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<WithdrawStakeOutput> {
     let amount_lamports = sol_to_lamports(amount_sol);
 
     let (account, epoch_info) = fetch_account_with_epoch(ctx, stake_pubkey).await?;
@@ -838,16 +750,48 @@ async fn withdraw_stake(
 
     let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
 
-    println!(
-        "{} {}\n{}\n{}\n{}",
-        style("Stake Withdrawn Successfully!").green().bold(),
-        style(format!("From Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("To Recipient: {recipient}")).yellow(),
-        style(format!("Amount: {amount_sol} SOL")).cyan(),
-        style(format!("Signature: {signature}")).cyan()
-    );
+    Ok(WithdrawStakeOutput {
+        stake_account: stake_pubkey.to_string(),
+        recipient: recipient.to_string(),
+        amount_sol,
+        signature: signature.to_string(),
+    })
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeStakeOutput {
+    pub destination_stake_account: String,
+    pub source_stake_account: String,
+    pub stake_authority: String,
+    pub destination_lamports: u64,
+    pub signature: String,
+}
+
+impl CommandOutput for MergeStakeOutput {
+    fn render_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            style("Stake Merged successfully!").yellow().bold(),
+            style(format!(
+                "Destination Stake Account: {}",
+                self.destination_stake_account
+            ))
+            .yellow(),
+            style(format!(
+                "Source Stake Account: {}",
+                self.source_stake_account
+            ))
+            .yellow(),
+            style(format!("Stake Authority: {}", self.stake_authority)).yellow(),
+            style(format!(
+                "After Merge: {} SOL",
+                lamports_to_sol(self.destination_lamports)
+            ))
+            .cyan(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+    }
 }
 
 async fn merge_stake(
@@ -855,8 +799,8 @@ async fn merge_stake(
     destination_stake_account_pubkey: &Pubkey,
     source_stake_account_pubkey: &Pubkey,
     stake_authority_keypair_path: &PathBuf,
-) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+) -> anyhow::Result<MergeStakeOutput> {
+    let stake_authority_keypair = read_signer(ctx, stake_authority_keypair_path)?;
 
     // checks for unique pubkeys
     if destination_stake_account_pubkey == source_stake_account_pubkey {
@@ -945,27 +889,35 @@ async fn merge_stake(
     let signature =
         build_and_send_tx(ctx, &ixs, &[ctx.keypair(), &stake_authority_keypair]).await?;
 
-    println!(
-        "{}\n{}\n{}\n{}\n{}\n{}",
-        style("Stake Merged successfully!").yellow().bold(),
-        style(format!(
-            "Destination Stake Account: {destination_stake_account_pubkey}"
-        ))
-        .yellow(),
-        style(format!(
-            "Source Stake Account: {source_stake_account_pubkey}"
-        ))
-        .yellow(),
-        style(format!("Stake Authority: {stake_authority_pubkey}")).yellow(),
-        style(format!(
-            "After Merge: {} SOL",
-            lamports_to_sol(destination_stake_account.lamports)
-        ))
-        .cyan(),
-        style(format!("Signature: {signature}")).green()
-    );
+    Ok(MergeStakeOutput {
+        destination_stake_account: destination_stake_account_pubkey.to_string(),
+        source_stake_account: source_stake_account_pubkey.to_string(),
+        stake_authority: stake_authority_pubkey.to_string(),
+        destination_lamports: destination_stake_account.lamports,
+        signature: signature.to_string(),
+    })
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitStakeOutput {
+    pub stake_account: String,
+    pub split_stake_account: String,
+    pub stake_authority: String,
+    pub signature: String,
+}
+
+impl CommandOutput for SplitStakeOutput {
+    fn render_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}\n{}",
+            style("Split Stake successfully!").yellow().bold(),
+            style(format!("Stake Account: {}", self.stake_account)).yellow(),
+            style(format!("Split Stake Account: {}", self.split_stake_account)).yellow(),
+            style(format!("Stake Authority: {}", self.stake_authority)).yellow(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+    }
 }
 
 async fn split_stake(
@@ -974,8 +926,8 @@ async fn split_stake(
     split_stake_account_pubkey: &Pubkey,
     stake_authority_keypair_path: &PathBuf,
     amount_to_split: f64,
-) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+) -> anyhow::Result<SplitStakeOutput> {
+    let stake_authority_keypair = read_signer(ctx, stake_authority_keypair_path)?;
     let stake_authority_pubkey = stake_authority_keypair.pubkey();
     let lamports: u64 = sol_to_lamports(amount_to_split);
 
@@ -1004,19 +956,65 @@ async fn split_stake(
 
     let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_authority_keypair]).await?;
 
-    println!(
-        "{}\n{}\n{}\n{}\n{}",
-        style("Split Stake successfully!").yellow().bold(),
-        style(format!("Stake Account: {stake_account_pubkey}")).yellow(),
-        style(format!("Split Stake Account: {split_stake_account_pubkey}")).yellow(),
-        style(format!("Stake Authority: {stake_authority_pubkey}")).yellow(),
-        style(format!("Signature: {signature}")).green()
-    );
+    Ok(SplitStakeOutput {
+        stake_account: stake_account_pubkey.to_string(),
+        split_stake_account: split_stake_account_pubkey.to_string(),
+        stake_authority: stake_authority_pubkey.to_string(),
+        signature: signature.to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeHistoryRow {
+    pub epoch: u64,
+    pub effective: u64,
+    pub activating: u64,
+    pub deactivating: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeHistoryOutput {
+    pub entries: Vec<StakeHistoryRow>,
+}
+
+impl CommandOutput for StakeHistoryOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::new(
+            "CLUSTER STAKE HISTORY",
+            [
+                "Epoch",
+                "Effective Stake",
+                "Activating Stake",
+                "Deactivating Stake",
+            ],
+        );
+        for entry in &self.entries {
+            table.row([
+                TableCell::new(entry.epoch),
+                TableCell::new(lamports_to_sol(entry.effective)),
+                TableCell::new(lamports_to_sol(entry.activating)),
+                TableCell::new(lamports_to_sol(entry.deactivating)),
+            ]);
+        }
+
+        vec![table]
+    }
 
-    Ok(())
+    fn render_table(&self) {
+        if self.entries.is_empty() {
+            println!("{}", style("No stake history available").yellow());
+            return;
+        }
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
 }
 
-async fn stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
+async fn stake_history(ctx: &ScillaContext) -> anyhow::Result<StakeHistoryOutput> {
     let stake_history_sysvar = Pubkey::from_str_const(STAKE_HISTORY_SYSVAR_ADDR);
 
     let account = ctx.rpc().get_account(&stake_history_sysvar).await?;
@@ -1024,56 +1022,204 @@ async fn stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
     let stake_history: StakeHistory =
         bincode_deserialize_with_limit(account.data.len() as u64, &account.data, "stake history")?;
 
-    if stake_history.is_empty() {
-        println!("{}", style("No stake history available").yellow());
-        return Ok(());
+    let entries = stake_history
+        .iter()
+        .take(DEFAULT_EPOCH_LIMIT)
+        .map(|(epoch, entry)| {
+            let StakeHistoryEntry {
+                effective,
+                activating,
+                deactivating,
+            } = entry;
+
+            StakeHistoryRow {
+                epoch: *epoch,
+                effective: *effective,
+                activating: *activating,
+                deactivating: *deactivating,
+            }
+        })
+        .collect();
+
+    Ok(StakeHistoryOutput { entries })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StakeState {
+    Uninitialized,
+    Initialized,
+    Delegated,
+    RewardsPool,
+}
+
+impl fmt::Display for StakeState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            StakeState::Uninitialized => "Uninitialized",
+            StakeState::Initialized => "Initialized",
+            StakeState::Delegated => "Delegated",
+            StakeState::RewardsPool => "Rewards Pool",
+        };
+        f.write_str(state)
     }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeDelegationOutput {
+    pub vote_account: String,
+    pub stake: u64,
+    /// `None` while the stake has not been activated yet
+    pub activation_epoch: Option<u64>,
+    /// `None` while the stake has not been deactivated
+    pub deactivation_epoch: Option<u64>,
+    pub active_stake: u64,
+    pub activating_stake: u64,
+    pub deactivating_stake: u64,
+    pub credits_observed: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeLockupOutput {
+    pub epoch: u64,
+    pub unix_timestamp: i64,
+    pub custodian: String,
+}
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL).set_header(vec![
-        Cell::new("Epoch").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Effective Stake").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Activating Stake").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Deactivating Stake").add_attribute(comfy_table::Attribute::Bold),
-    ]);
-
-    for (epoch, entry) in stake_history.iter().take(DEFAULT_EPOCH_LIMIT) {
-        let StakeHistoryEntry {
-            effective,
-            activating,
-            deactivating,
-        } = entry;
-
-        table.add_row(vec![
-            Cell::new(epoch),
-            Cell::new(lamports_to_sol(*effective)),
-            Cell::new(lamports_to_sol(*activating)),
-            Cell::new(lamports_to_sol(*deactivating)),
-        ]);
+/// Decoded state of a stake account, shared by every command that displays
+/// one.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeAccountOutput {
+    #[serde(skip)]
+    title: &'static str,
+    #[serde(skip)]
+    success_msg: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub stake_account: String,
+    pub lamports: u64,
+    pub rent_epoch: u64,
+    pub state: StakeState,
+    pub rent_exempt_reserve: Option<u64>,
+    pub staker: Option<String>,
+    pub withdrawer: Option<String>,
+    pub delegation: Option<StakeDelegationOutput>,
+    /// Only present while the lockup is in force
+    pub lockup: Option<StakeLockupOutput>,
+}
+
+impl StakeAccountOutput {
+    fn with_title(mut self, title: &'static str) -> Self {
+        self.title = title;
+        self
+    }
+
+    fn with_signature(mut self, success_msg: &'static str, signature: impl ToString) -> Self {
+        self.success_msg = Some(success_msg);
+        self.signature = Some(signature.to_string());
+        self
     }
+}
+
+impl CommandOutput for StakeAccountOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value(self.title);
+        table
+            .field("Stake Account Pubkey", &self.stake_account)
+            .field("Account Balance (SOL)", lamports_to_sol(self.lamports))
+            .field("Account Balance (Lamports)", self.lamports)
+            .field("Rent Epoch", self.rent_epoch)
+            .field("Stake State", self.state.to_string());
+
+        if let Some(rent_exempt_reserve) = self.rent_exempt_reserve {
+            table.field("Rent Exempt Reserve (Lamports)", rent_exempt_reserve);
+        }
+
+        if let Some(staker) = &self.staker {
+            table.field("Stake Authority", staker);
+        }
+
+        if let Some(withdrawer) = &self.withdrawer {
+            table.field("Withdraw Authority", withdrawer);
+        }
 
-    println!("\n{}", style("CLUSTER STAKE HISTORY").green().bold());
-    println!("{table}");
+        if let Some(delegation) = &self.delegation {
+            let epoch_or_na = |epoch: Option<u64>| {
+                epoch
+                    .map(|epoch| epoch.to_string())
+                    .unwrap_or_else(|| "N/A".to_string())
+            };
 
-    Ok(())
+            table
+                .field("Delegated Vote Account", &delegation.vote_account)
+                .field("Delegated Stake (SOL)", lamports_to_sol(delegation.stake))
+                .field("Activation Epoch", epoch_or_na(delegation.activation_epoch))
+                .field(
+                    "Deactivation Epoch",
+                    epoch_or_na(delegation.deactivation_epoch),
+                )
+                .field(
+                    "Active Stake (SOL)",
+                    lamports_to_sol(delegation.active_stake),
+                )
+                .field(
+                    "Activating Stake (SOL)",
+                    lamports_to_sol(delegation.activating_stake),
+                )
+                .field(
+                    "Deactivating Stake (SOL)",
+                    lamports_to_sol(delegation.deactivating_stake),
+                )
+                .field("Credits Observed", delegation.credits_observed);
+        }
+
+        if let Some(lockup) = &self.lockup {
+            table
+                .field("Lockup Epoch", lockup.epoch)
+                .field("Lockup Unix Timestamp", lockup.unix_timestamp)
+                .field("Lockup Custodian", &lockup.custodian);
+        }
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        if let (Some(success_msg), Some(signature)) = (self.success_msg, &self.signature) {
+            println!(
+                "{}\n{}",
+                style(success_msg).yellow().bold(),
+                style(format!("Signature: {signature}")).green()
+            );
+        }
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
 }
 
-async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+pub(crate) async fn fetch_stake_account(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+) -> anyhow::Result<StakeAccountOutput> {
     let accounts = ctx
         .rpc()
         .get_multiple_accounts(&[*pubkey, stake_history::id(), clock::id()])
         .await?;
 
     let Some(Some(stake_account)) = accounts.first() else {
-        anyhow::bail!("Failed to get stake account");
+        bail!("Failed to get stake account");
     };
 
     let Some(Some(stake_history_account)) = accounts.get(1) else {
-        anyhow::bail!("Failed to get stake history account");
+        bail!("Failed to get stake history account");
     };
 
     let Some(Some(clock_account)) = accounts.get(2) else {
-        anyhow::bail!("Failed to get clock account");
+        bail!("Failed to get clock account");
     };
 
     let stake_history: StakeHistory =
@@ -1082,157 +1228,76 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
 
     let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
 
-    let current_epoch = clock.epoch;
-
-    // Build main table
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![Cell::new("Stake Account Pubkey"), Cell::new(pubkey)])
-        .add_row(vec![
-            Cell::new("Account Balance (SOL)"),
-            Cell::new(lamports_to_sol(stake_account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (Lamports)"),
-            Cell::new(stake_account.lamports),
-        ])
-        .add_row(vec![
-            Cell::new("Rent Epoch"),
-            Cell::new(stake_account.rent_epoch),
-        ]);
+    let mut output = StakeAccountOutput {
+        title: "STAKE ACCOUNT INFORMATION",
+        success_msg: None,
+        signature: None,
+        stake_account: pubkey.to_string(),
+        lamports: stake_account.lamports,
+        rent_epoch: stake_account.rent_epoch,
+        state: StakeState::Uninitialized,
+        rent_exempt_reserve: None,
+        staker: None,
+        withdrawer: None,
+        delegation: None,
+        lockup: None,
+    };
 
-    // Add stake state specific information
-    match &stake_state {
-        StakeStateV2::Uninitialized => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Uninitialized")]);
-        }
-        StakeStateV2::Initialized(Meta {
-            rent_exempt_reserve,
-            authorized,
-            lockup,
-        }) => {
-            table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Initialized")])
-                .add_row(vec![
-                    Cell::new("Rent Exempt Reserve (Lamports)"),
-                    Cell::new(rent_exempt_reserve),
-                ])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ]);
-
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![Cell::new("Lockup Epoch"), Cell::new(lockup.epoch)])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(lockup.unix_timestamp),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
+    let meta = match &stake_state {
+        StakeStateV2::Uninitialized => None,
+        StakeStateV2::Initialized(meta) => {
+            output.state = StakeState::Initialized;
+            output.rent_exempt_reserve = Some(meta.rent_exempt_reserve);
+            Some(meta)
         }
-        StakeStateV2::Stake(
-            Meta {
-                authorized, lockup, ..
-            },
-            stake,
-            _,
-        ) => {
+        StakeStateV2::Stake(meta, stake, _) => {
             // Calculate activation status
             let StakeActivationStatus {
                 effective,
                 activating,
                 deactivating,
             } = stake.delegation.stake_activating_and_deactivating(
-                current_epoch,
+                clock.epoch,
                 &stake_history,
                 None,
             );
 
-            table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Delegated")])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ])
-                .add_row(vec![
-                    Cell::new("Delegated Vote Account"),
-                    Cell::new(stake.delegation.voter_pubkey),
-                ])
-                .add_row(vec![
-                    Cell::new("Delegated Stake (SOL)"),
-                    Cell::new(lamports_to_sol(stake.delegation.stake)),
-                ])
-                .add_row(vec![
-                    Cell::new("Activation Epoch"),
-                    Cell::new(if stake.delegation.activation_epoch < u64::MAX {
-                        stake.delegation.activation_epoch.to_string()
-                    } else {
-                        "N/A".to_string()
-                    }),
-                ])
-                .add_row(vec![
-                    Cell::new("Deactivation Epoch"),
-                    Cell::new(if stake.delegation.deactivation_epoch < u64::MAX {
-                        stake.delegation.deactivation_epoch.to_string()
-                    } else {
-                        "N/A".to_string()
-                    }),
-                ])
-                .add_row(vec![
-                    Cell::new("Active Stake (SOL)"),
-                    Cell::new(lamports_to_sol(effective)),
-                ])
-                .add_row(vec![
-                    Cell::new("Activating Stake (SOL)"),
-                    Cell::new(lamports_to_sol(activating)),
-                ])
-                .add_row(vec![
-                    Cell::new("Deactivating Stake (SOL)"),
-                    Cell::new(lamports_to_sol(deactivating)),
-                ])
-                .add_row(vec![
-                    Cell::new("Credits Observed"),
-                    Cell::new(stake.credits_observed),
-                ]);
-
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![Cell::new("Lockup Epoch"), Cell::new(lockup.epoch)])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(lockup.unix_timestamp),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
+            output.state = StakeState::Delegated;
+            output.delegation = Some(StakeDelegationOutput {
+                vote_account: stake.delegation.voter_pubkey.to_string(),
+                stake: stake.delegation.stake,
+                activation_epoch: (stake.delegation.activation_epoch < u64::MAX)
+                    .then_some(stake.delegation.activation_epoch),
+                deactivation_epoch: (stake.delegation.deactivation_epoch < u64::MAX)
+                    .then_some(stake.delegation.deactivation_epoch),
+                active_stake: effective,
+                activating_stake: activating,
+                deactivating_stake: deactivating,
+                credits_observed: stake.credits_observed,
+            });
+            Some(meta)
         }
         StakeStateV2::RewardsPool => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Rewards Pool")]);
+            output.state = StakeState::RewardsPool;
+            None
         }
-    }
+    };
 
-    println!("{}\n", style("STAKE ACCOUNT INFORMATION").green().bold());
-    println!("{table}");
+    if let Some(Meta {
+        authorized, lockup, ..
+    }) = meta
+    {
+        output.staker = Some(authorized.staker.to_string());
+        output.withdrawer = Some(authorized.withdrawer.to_string());
+
+        if lockup.is_in_force(&clock, None) {
+            output.lockup = Some(StakeLockupOutput {
+                epoch: lockup.epoch,
+                unix_timestamp: lockup.unix_timestamp,
+                custodian: lockup.custodian.to_string(),
+            });
+        }
+    }
 
-    Ok(())
+    Ok(output)
 }
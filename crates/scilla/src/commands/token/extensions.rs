@@ -0,0 +1,572 @@
+use {
+    super::{
+        MintState, TokenAccountState, is_token_program, load_mint, load_token_account,
+        read_authority,
+    },
+    crate::{
+        context::ScillaContext,
+        misc::helpers::{TokenAmount, build_and_send_tx},
+        output::{CommandOutput, TableView, flatten_fields, humanize},
+    },
+    anyhow::{anyhow, bail},
+    console::style,
+    serde::Serialize,
+    serde_json::Value,
+    solana_account::Account,
+    solana_account_decoder::{
+        UiAccountEncoding,
+        parse_account_data::SplTokenAdditionalDataV2,
+        parse_token::{
+            TokenAccountType, UiAccountState, UiTokenAccount, parse_token_v3,
+            real_number_string_trimmed,
+        },
+        parse_token_extension::UiExtension,
+    },
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+    },
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_token_2022_interface::{
+        extension::{
+            BaseStateWithExtensions, StateWithExtensions,
+            interest_bearing_mint::InterestBearingConfig,
+            metadata_pointer::{self, MetadataPointer},
+            scaled_ui_amount::ScaledUiAmountConfig,
+            transfer_fee::{self, MAX_FEE_BASIS_POINTS, TransferFeeAmount, TransferFeeConfig},
+        },
+        state::Account as TokenAccount,
+    },
+    std::{cmp::Reverse, path::PathBuf},
+};
+
+/// Token accounts a single withdrawal can take withheld fees from before the
+/// transaction grows past the size limit.
+const MAX_WITHDRAW_SOURCES: usize = 20;
+
+/// Decodes a mint, token account or multisig owned by SPL Token or
+/// Token-2022, extensions included. Token account balances need the mint's
+/// decimals, so the mint is fetched as well.
+pub async fn decode_token_state(
+    ctx: &ScillaContext,
+    account: &Account,
+) -> anyhow::Result<TokenAccountType> {
+    if !is_token_program(&account.owner) {
+        bail!("Account is not owned by a token program");
+    }
+
+    let additional_data = match StateWithExtensions::<TokenAccount>::unpack(&account.data) {
+        Ok(token_account) => Some(mint_additional_data(
+            &load_mint(ctx, &token_account.base.mint).await?,
+        )?),
+        Err(_) => None,
+    };
+
+    parse_token_v3(&account.data, additional_data.as_ref())
+        .map_err(|e| anyhow!("Failed to decode token account: {e}"))
+}
+
+/// Decimals and UI amount configs used to show balances of `mint` the way
+/// wallets do. Interest and scaling are applied as of now.
+fn mint_additional_data(mint: &MintState) -> anyhow::Result<SplTokenAdditionalDataV2> {
+    let state = mint.extensions()?;
+    let now = chrono::Utc::now().timestamp();
+
+    Ok(SplTokenAdditionalDataV2 {
+        decimals: mint.base.decimals,
+        interest_bearing_config: state
+            .get_extension::<InterestBearingConfig>()
+            .ok()
+            .map(|config| (*config, now)),
+        scaled_ui_amount_config: state
+            .get_extension::<ScaledUiAmountConfig>()
+            .ok()
+            .map(|config| (*config, now)),
+    })
+}
+
+/// The base state table of a decoded token account, followed by one row per
+/// field of every extension it carries.
+pub fn token_state_tables(state: &TokenAccountType) -> Vec<TableView> {
+    let (table, extensions) = match state {
+        TokenAccountType::Mint(mint) => {
+            let supply = mint
+                .supply
+                .parse()
+                .map(|supply| real_number_string_trimmed(supply, mint.decimals))
+                .unwrap_or_else(|_| mint.supply.clone());
+
+            let mut table = TableView::key_value("MINT");
+            table
+                .field("Supply", supply)
+                .field("Decimals", mint.decimals)
+                .field(
+                    "Mint Authority",
+                    mint.mint_authority.as_deref().unwrap_or("None"),
+                )
+                .field(
+                    "Freeze Authority",
+                    mint.freeze_authority.as_deref().unwrap_or("None"),
+                );
+            (table, mint.extensions.as_slice())
+        }
+        TokenAccountType::Account(account) => {
+            (token_account_table(account), account.extensions.as_slice())
+        }
+        TokenAccountType::Multisig(multisig) => {
+            let mut table = TableView::key_value("MULTISIG");
+            table.field(
+                "Required Signers",
+                format!(
+                    "{} of {}",
+                    multisig.num_required_signers, multisig.num_valid_signers
+                ),
+            );
+            for (idx, signer) in multisig.signers.iter().enumerate() {
+                table.field(format!("Signer {}", idx + 1), signer);
+            }
+            (table, [].as_slice())
+        }
+    };
+
+    let mut tables = vec![table];
+    if !extensions.is_empty() {
+        let mut table = TableView::new("EXTENSIONS", ["Extension", "Field", "Value"]);
+        for extension in extensions {
+            for row in extension_rows(extension) {
+                table.row(row);
+            }
+        }
+        tables.push(table);
+    }
+    tables
+}
+
+fn token_account_table(account: &UiTokenAccount) -> TableView {
+    let state = match account.state {
+        UiAccountState::Uninitialized => "Uninitialized",
+        UiAccountState::Initialized => "Initialized",
+        UiAccountState::Frozen => "Frozen",
+    };
+
+    let mut table = TableView::key_value("TOKEN ACCOUNT");
+    table
+        .field("Mint", &account.mint)
+        .field("Owner", &account.owner)
+        .field("Balance", account.token_amount.real_number_string_trimmed())
+        .field("State", state);
+    if let Some(delegate) = &account.delegate {
+        table.field("Delegate", delegate);
+    }
+    if let Some(delegated) = &account.delegated_amount {
+        table.field("Delegated Amount", delegated.real_number_string_trimmed());
+    }
+    if let Some(reserve) = &account.rent_exempt_reserve {
+        table.field("Rent-Exempt Reserve", reserve.real_number_string_trimmed());
+    }
+    if let Some(close_authority) = &account.close_authority {
+        table.field("Close Authority", close_authority);
+    }
+    table
+}
+
+/// Flattens an extension into `[extension, field, value]` rows. Nested
+/// fields such as the older and newer transfer fee are joined with `›`.
+fn extension_rows(extension: &UiExtension) -> Vec<[String; 3]> {
+    let value = serde_json::to_value(extension).unwrap_or(Value::Null);
+    let name = value["extension"]
+        .as_str()
+        .map(humanize)
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut fields = Vec::new();
+    flatten_fields(None, &value["state"], &mut fields);
+    if fields.is_empty() {
+        // Marker extensions such as ImmutableOwner have no state
+        return vec![[name, String::new(), "Enabled".to_string()]];
+    }
+
+    fields
+        .into_iter()
+        .map(|(field, value)| [name.clone(), field, value])
+        .collect()
+}
+
+/// A mint or token account after an update, shown the way Fetch Account
+/// shows it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenStateOutput {
+    #[serde(skip)]
+    success_msg: &'static str,
+    pub signature: String,
+    pub address: String,
+    #[serde(flatten)]
+    pub state: TokenAccountType,
+}
+
+impl CommandOutput for TokenStateOutput {
+    fn tables(&self) -> Vec<TableView> {
+        token_state_tables(&self.state)
+    }
+
+    fn render_table(&self) {
+        println!(
+            "{}\n{}",
+            style(self.success_msg).yellow().bold(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+async fn fetch_token_state(
+    ctx: &ScillaContext,
+    address: &Pubkey,
+    success_msg: &'static str,
+    signature: impl ToString,
+) -> anyhow::Result<TokenStateOutput> {
+    let account = ctx.rpc().get_account(address).await?;
+
+    Ok(TokenStateOutput {
+        success_msg,
+        signature: signature.to_string(),
+        address: address.to_string(),
+        state: decode_token_state(ctx, &account).await?,
+    })
+}
+
+/// Makes sure `authority` is the one set in an extension, naming the
+/// extension's role in the error.
+fn check_extension_authority(
+    mint: &Pubkey,
+    role: &str,
+    expected: Option<Pubkey>,
+    authority: &Pubkey,
+) -> anyhow::Result<()> {
+    match expected {
+        None => bail!("Mint {mint} has no {role}, it can no longer be changed"),
+        Some(expected) if &expected != authority => {
+            bail!("{authority} is not the {role} of mint {mint}, {expected} is")
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+fn transfer_fee_config(mint: &Pubkey, state: &MintState) -> anyhow::Result<TransferFeeConfig> {
+    state
+        .extensions()?
+        .get_extension::<TransferFeeConfig>()
+        .copied()
+        .map_err(|_| anyhow!("Mint {mint} has no transfer fee"))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawWithheldOutput {
+    pub signature: String,
+    pub mint: String,
+    pub destination: String,
+    pub amount: String,
+    pub accounts_withdrawn: usize,
+    pub accounts_remaining: usize,
+}
+
+impl CommandOutput for WithdrawWithheldOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("WITHHELD FEES");
+        table
+            .field("Mint", &self.mint)
+            .field("Destination", &self.destination)
+            .field("Amount", &self.amount)
+            .field("Token Accounts Emptied", self.accounts_withdrawn)
+            .field("Token Accounts Remaining", self.accounts_remaining);
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        println!(
+            "{}\n{}",
+            style("Withheld fees withdrawn successfully!")
+                .yellow()
+                .bold(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+
+        for table in self.tables() {
+            table.print();
+        }
+
+        if self.accounts_remaining > 0 {
+            println!(
+                "{}",
+                style("Run the command again to withdraw from the remaining token accounts.")
+                    .yellow()
+            );
+        }
+    }
+}
+
+/// Token accounts of `mint` with transfer fees withheld, largest first.
+async fn find_withheld_accounts(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, u64)>> {
+    let config = RpcProgramAccountsConfig {
+        // Token accounts start with their mint
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            mint.as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let accounts = ctx
+        .rpc()
+        .get_program_ui_accounts_with_config(&spl_token_2022_interface::id(), config)
+        .await?;
+
+    let mut withheld: Vec<_> = accounts
+        .into_iter()
+        .filter_map(|(address, account)| {
+            let data = account.data.decode()?;
+            let state = StateWithExtensions::<TokenAccount>::unpack(&data).ok()?;
+            let amount = u64::from(
+                state
+                    .get_extension::<TransferFeeAmount>()
+                    .ok()?
+                    .withheld_amount,
+            );
+            (amount > 0).then_some((address, amount))
+        })
+        .collect();
+    withheld.sort_by_key(|(_, amount)| Reverse(*amount));
+
+    Ok(withheld)
+}
+
+pub(super) async fn withdraw_withheld_fees(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    destination: Option<Pubkey>,
+    withdraw_withheld_authority: Option<PathBuf>,
+) -> anyhow::Result<WithdrawWithheldOutput> {
+    let authority_signer = read_authority(ctx, withdraw_withheld_authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let mint_state = load_mint(ctx, mint).await?;
+    let fee_config = transfer_fee_config(mint, &mint_state)?;
+    check_extension_authority(
+        mint,
+        "withdraw withheld authority",
+        fee_config.withdraw_withheld_authority.into(),
+        &authority.pubkey(),
+    )?;
+
+    let program_id = mint_state.program_id;
+    let destination = destination.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(&authority.pubkey(), mint, &program_id)
+    });
+    let TokenAccountState { base, .. } = load_token_account(ctx, &destination).await?;
+    if &base.mint != mint {
+        bail!(
+            "Token account {destination} holds mint {}, not {mint}",
+            base.mint
+        );
+    }
+
+    let sources = find_withheld_accounts(ctx, mint).await?;
+    let batch = &sources[..sources.len().min(MAX_WITHDRAW_SOURCES)];
+    let from_mint = u64::from(fee_config.withheld_amount);
+    if from_mint == 0 && batch.is_empty() {
+        bail!("Mint {mint} has no withheld fees to withdraw");
+    }
+
+    let mut instructions = Vec::new();
+    if from_mint > 0 {
+        instructions.push(
+            transfer_fee::instruction::withdraw_withheld_tokens_from_mint(
+                &program_id,
+                mint,
+                &destination,
+                &authority.pubkey(),
+                &[],
+            )?,
+        );
+    }
+    if !batch.is_empty() {
+        let addresses: Vec<_> = batch.iter().map(|(address, _)| address).collect();
+        instructions.push(
+            transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+                &program_id,
+                mint,
+                &destination,
+                &authority.pubkey(),
+                &[],
+                &addresses,
+            )?,
+        );
+    }
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.keypair(), authority]).await?;
+
+    let amount = from_mint + batch.iter().map(|(_, amount)| amount).sum::<u64>();
+    Ok(WithdrawWithheldOutput {
+        signature: signature.to_string(),
+        mint: mint.to_string(),
+        destination: destination.to_string(),
+        amount: real_number_string_trimmed(amount, mint_state.base.decimals),
+        accounts_withdrawn: batch.len(),
+        accounts_remaining: sources.len() - batch.len(),
+    })
+}
+
+pub(super) async fn set_transfer_fee(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    basis_points: u16,
+    maximum_fee: &TokenAmount,
+    transfer_fee_authority: Option<PathBuf>,
+) -> anyhow::Result<TokenStateOutput> {
+    if basis_points > MAX_FEE_BASIS_POINTS {
+        bail!("The fee cannot exceed {MAX_FEE_BASIS_POINTS} basis points, got {basis_points}");
+    }
+    let authority_signer = read_authority(ctx, transfer_fee_authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let mint_state = load_mint(ctx, mint).await?;
+    let fee_config = transfer_fee_config(mint, &mint_state)?;
+    check_extension_authority(
+        mint,
+        "transfer fee authority",
+        fee_config.transfer_fee_config_authority.into(),
+        &authority.pubkey(),
+    )?;
+    let maximum_fee = maximum_fee.to_base_units(mint_state.base.decimals)?;
+
+    let instruction = transfer_fee::instruction::set_transfer_fee(
+        &mint_state.program_id,
+        mint,
+        &authority.pubkey(),
+        &[],
+        basis_points,
+        maximum_fee,
+    )?;
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    fetch_token_state(
+        ctx,
+        mint,
+        "Transfer fee updated! The new fee applies from two epochs after this one.",
+        signature,
+    )
+    .await
+}
+
+pub(super) async fn set_metadata_pointer(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    metadata_address: Option<Pubkey>,
+    authority: Option<PathBuf>,
+) -> anyhow::Result<TokenStateOutput> {
+    let authority_signer = read_authority(ctx, authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let mint_state = load_mint(ctx, mint).await?;
+    let pointer = *mint_state
+        .extensions()?
+        .get_extension::<MetadataPointer>()
+        .map_err(|_| anyhow!("Mint {mint} has no metadata pointer"))?;
+    check_extension_authority(
+        mint,
+        "metadata pointer authority",
+        pointer.authority.into(),
+        &authority.pubkey(),
+    )?;
+
+    let instruction = metadata_pointer::instruction::update(
+        &mint_state.program_id,
+        mint,
+        &authority.pubkey(),
+        &[],
+        metadata_address,
+    )?;
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    fetch_token_state(
+        ctx,
+        mint,
+        "Metadata pointer updated successfully!",
+        signature,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_program_pack::Pack,
+        spl_token_2022_interface::{
+            extension::{
+                BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+                non_transferable::NonTransferable,
+            },
+            state::Mint,
+        },
+    };
+
+    fn mint_with_transfer_fee() -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::NonTransferable,
+        ])
+        .unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+
+        let fee_config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        fee_config.newer_transfer_fee.transfer_fee_basis_points = 50.into();
+        fee_config.newer_transfer_fee.maximum_fee = 5_000.into();
+        fee_config.withheld_amount = 1_234.into();
+        state.init_extension::<NonTransferable>(true).unwrap();
+
+        state.base = Mint {
+            supply: 1_500_000,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_token_state_tables_list_every_extension() {
+        let data = mint_with_transfer_fee();
+        assert!(data.len() > Mint::LEN);
+        let state = parse_token_v3(&data, None).unwrap();
+
+        let tables = token_state_tables(&state);
+        assert_eq!(tables.len(), 2);
+
+        let csv = tables[1].to_csv();
+        assert!(csv.contains("Transfer Fee Config,Withheld Amount,1234"));
+        assert!(
+            csv.contains("Transfer Fee Config,Newer Transfer Fee › Transfer Fee Basis Points,50")
+        );
+        assert!(csv.contains("Transfer Fee Config,Transfer Fee Config Authority,None"));
+        assert!(csv.contains("Non Transferable,,Enabled"));
+
+        let mint = tables[0].to_csv();
+        assert!(mint.contains("Supply,1.5"));
+    }
+}
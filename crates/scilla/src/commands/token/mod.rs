@@ -0,0 +1,1295 @@
+use {
+    crate::{
+        address_book::{AddressLabels, with_label},
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
+        context::ScillaContext,
+        misc::helpers::{TokenAmount, build_and_send_tx, check_minimum_balance, read_signer},
+        output::{Color, CommandOutput, TableCell, TableView},
+        prompt::{prompt_confirmation, prompt_input_data, prompt_pubkey},
+        ui::render_with_spinner,
+    },
+    anyhow::{anyhow, bail},
+    clap::Subcommand,
+    console::style,
+    serde::Serialize,
+    solana_account_decoder::{
+        UiAccountData,
+        parse_token::{TokenAccountType, UiAccountState, real_number_string_trimmed},
+    },
+    solana_instruction::Instruction,
+    solana_keypair::{Keypair, Signer},
+    solana_program_pack::Pack,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::request::TokenAccountsFilter,
+    solana_system_interface::instruction::create_account,
+    spl_associated_token_account_interface::{
+        address::get_associated_token_address_with_program_id,
+        instruction::create_associated_token_account_idempotent,
+    },
+    spl_token_2022_interface::{
+        extension::{BaseStateWithExtensions, StateWithExtensions, transfer_hook::TransferHook},
+        instruction as token_instruction,
+        state::{Account as TokenAccount, Mint},
+    },
+    std::{fmt, path::PathBuf},
+    tokio::try_join,
+};
+
+mod extensions;
+
+pub use extensions::{decode_token_state, token_state_tables};
+
+/// Commands for SPL Token mints and token accounts
+#[derive(Debug, Clone, Copy)]
+pub enum TokenCommand {
+    CreateMint,
+    CreateAccount,
+    MintTo,
+    Transfer,
+    Burn,
+    Approve,
+    Revoke,
+    Freeze,
+    Thaw,
+    CloseAccount,
+    Balances,
+    WithdrawWithheld,
+    SetTransferFee,
+    SetMetadataPointer,
+}
+
+impl fmt::Display for TokenCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = match self {
+            TokenCommand::CreateMint => "Create mint",
+            TokenCommand::CreateAccount => "Create token account",
+            TokenCommand::MintTo => "Mint tokens",
+            TokenCommand::Transfer => "Transfer tokens",
+            TokenCommand::Burn => "Burn tokens",
+            TokenCommand::Approve => "Approve delegate",
+            TokenCommand::Revoke => "Revoke delegate",
+            TokenCommand::Freeze => "Freeze token account",
+            TokenCommand::Thaw => "Thaw token account",
+            TokenCommand::CloseAccount => "Close token account",
+            TokenCommand::Balances => "View token balances",
+            TokenCommand::WithdrawWithheld => "Withdraw withheld fees",
+            TokenCommand::SetTransferFee => "Update transfer fee",
+            TokenCommand::SetMetadataPointer => "Set metadata pointer",
+        };
+        write!(f, "{command}")
+    }
+}
+
+impl Command for TokenCommand {
+    fn spinner_msg(&self) -> &'static str {
+        match self {
+            TokenCommand::CreateMint => "Creating mint…",
+            TokenCommand::CreateAccount => "Creating token account…",
+            TokenCommand::MintTo => "Minting tokens…",
+            TokenCommand::Transfer => "Transferring tokens…",
+            TokenCommand::Burn => "Burning tokens…",
+            TokenCommand::Approve => "Approving delegate…",
+            TokenCommand::Revoke => "Revoking delegate…",
+            TokenCommand::Freeze => "Freezing token account…",
+            TokenCommand::Thaw => "Thawing token account…",
+            TokenCommand::CloseAccount => "Closing token account…",
+            TokenCommand::Balances => "Fetching token balances…",
+            TokenCommand::WithdrawWithheld => "Withdrawing withheld transfer fees…",
+            TokenCommand::SetTransferFee => "Updating transfer fee…",
+            TokenCommand::SetMetadataPointer => "Setting metadata pointer…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            TokenCommand::CreateMint => TokenArgs::CreateMint {
+                decimals: None,
+                mint_keypair: None,
+                freeze_authority: None,
+                no_freeze_authority: false,
+            },
+            TokenCommand::CreateAccount => TokenArgs::CreateAccount {
+                mint: None,
+                owner: None,
+            },
+            TokenCommand::MintTo => TokenArgs::MintTo {
+                mint: None,
+                amount: None,
+                recipient: None,
+                mint_authority: None,
+            },
+            TokenCommand::Transfer => TokenArgs::Transfer {
+                mint: None,
+                to: None,
+                amount: None,
+                owner: None,
+            },
+            TokenCommand::Burn => TokenArgs::Burn {
+                mint: None,
+                amount: None,
+                owner: None,
+            },
+            TokenCommand::Approve => TokenArgs::Approve {
+                mint: None,
+                delegate: None,
+                amount: None,
+                owner: None,
+            },
+            TokenCommand::Revoke => TokenArgs::Revoke {
+                mint: None,
+                owner: None,
+            },
+            TokenCommand::Freeze => TokenArgs::Freeze {
+                account: None,
+                freeze_authority: None,
+            },
+            TokenCommand::Thaw => TokenArgs::Thaw {
+                account: None,
+                freeze_authority: None,
+            },
+            TokenCommand::CloseAccount => TokenArgs::Close {
+                account: None,
+                recipient: None,
+                owner: None,
+                yes: false,
+            },
+            TokenCommand::Balances => TokenArgs::Balances { owner: None },
+            TokenCommand::WithdrawWithheld => TokenArgs::WithdrawWithheld {
+                mint: None,
+                destination: None,
+                withdraw_withheld_authority: None,
+            },
+            TokenCommand::SetTransferFee => TokenArgs::SetTransferFee {
+                mint: None,
+                basis_points: None,
+                maximum_fee: None,
+                transfer_fee_authority: None,
+            },
+            TokenCommand::SetMetadataPointer => TokenArgs::SetMetadataPointer {
+                mint: None,
+                metadata_address: None,
+                authority: None,
+            },
+        };
+
+        Box::pin(async move { CommandFlow::from_run(args.run(ctx).await) })
+    }
+}
+
+/// The Token menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Token").commands([
+        TokenCommand::Balances,
+        TokenCommand::CreateMint,
+        TokenCommand::CreateAccount,
+        TokenCommand::MintTo,
+        TokenCommand::Transfer,
+        TokenCommand::Burn,
+        TokenCommand::Approve,
+        TokenCommand::Revoke,
+        TokenCommand::Freeze,
+        TokenCommand::Thaw,
+        TokenCommand::CloseAccount,
+        TokenCommand::WithdrawWithheld,
+        TokenCommand::SetTransferFee,
+        TokenCommand::SetMetadataPointer,
+    ])
+}
+
+/// Non-interactive arguments for [`TokenCommand`]. Anything left out is
+/// prompted for interactively. Authorities default to the configured keypair.
+#[derive(Debug, Clone, Subcommand)]
+pub enum TokenArgs {
+    /// Create a new mint with the configured keypair as mint authority
+    CreateMint {
+        /// Number of decimal places of the token
+        #[arg(long)]
+        decimals: Option<u8>,
+        /// Keypair of the mint address; a new one is generated by default
+        #[arg(long)]
+        mint_keypair: Option<PathBuf>,
+        /// Pubkey allowed to freeze and thaw token accounts of the mint
+        #[arg(long)]
+        freeze_authority: Option<Pubkey>,
+        /// Create the mint without a freeze authority
+        #[arg(long, conflicts_with = "freeze_authority")]
+        no_freeze_authority: bool,
+    },
+    /// Create the associated token account of a wallet
+    CreateAccount {
+        mint: Option<Pubkey>,
+        /// Wallet owning the new account
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// Mint tokens to a wallet, creating its token account if needed
+    MintTo {
+        mint: Option<Pubkey>,
+        /// Amount in tokens, e.g. 1.5
+        #[arg(long)]
+        amount: Option<TokenAmount>,
+        /// Wallet or token account receiving the tokens
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        #[arg(long)]
+        mint_authority: Option<PathBuf>,
+    },
+    /// Transfer tokens, creating the recipient's token account if needed
+    Transfer {
+        mint: Option<Pubkey>,
+        /// Wallet or token account receiving the tokens
+        #[arg(long)]
+        to: Option<Pubkey>,
+        /// Amount in tokens, e.g. 1.5
+        #[arg(long)]
+        amount: Option<TokenAmount>,
+        /// Keypair owning the source token account
+        #[arg(long)]
+        owner: Option<PathBuf>,
+    },
+    /// Burn tokens from a token account
+    Burn {
+        mint: Option<Pubkey>,
+        /// Amount in tokens, e.g. 1.5
+        #[arg(long)]
+        amount: Option<TokenAmount>,
+        #[arg(long)]
+        owner: Option<PathBuf>,
+    },
+    /// Let a delegate transfer or burn up to an amount of tokens
+    Approve {
+        mint: Option<Pubkey>,
+        #[arg(long)]
+        delegate: Option<Pubkey>,
+        /// Amount in tokens, e.g. 1.5
+        #[arg(long)]
+        amount: Option<TokenAmount>,
+        #[arg(long)]
+        owner: Option<PathBuf>,
+    },
+    /// Remove the delegate of a token account
+    Revoke {
+        mint: Option<Pubkey>,
+        #[arg(long)]
+        owner: Option<PathBuf>,
+    },
+    /// Freeze a token account
+    Freeze {
+        /// Token account address
+        account: Option<Pubkey>,
+        #[arg(long)]
+        freeze_authority: Option<PathBuf>,
+    },
+    /// Thaw a frozen token account
+    Thaw {
+        /// Token account address
+        account: Option<Pubkey>,
+        #[arg(long)]
+        freeze_authority: Option<PathBuf>,
+    },
+    /// Close an empty token account and reclaim its rent
+    Close {
+        /// Token account address
+        account: Option<Pubkey>,
+        /// Receives the reclaimed rent; defaults to the configured keypair
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        #[arg(long)]
+        owner: Option<PathBuf>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// List the token accounts and balances of a wallet
+    Balances { owner: Option<Pubkey> },
+    /// Withdraw the transfer fees withheld in a Token-2022 mint and its
+    /// token accounts
+    WithdrawWithheld {
+        mint: Option<Pubkey>,
+        /// Token account receiving the fees; defaults to the authority's
+        /// associated token account
+        #[arg(long)]
+        destination: Option<Pubkey>,
+        #[arg(long)]
+        withdraw_withheld_authority: Option<PathBuf>,
+    },
+    /// Change the transfer fee of a Token-2022 mint
+    SetTransferFee {
+        mint: Option<Pubkey>,
+        /// Fee in basis points of the amount transferred (0-10000)
+        #[arg(long)]
+        basis_points: Option<u16>,
+        /// Most a single transfer is charged, in tokens
+        #[arg(long)]
+        maximum_fee: Option<TokenAmount>,
+        #[arg(long)]
+        transfer_fee_authority: Option<PathBuf>,
+    },
+    /// Point a Token-2022 mint at the account holding its metadata
+    SetMetadataPointer {
+        mint: Option<Pubkey>,
+        #[arg(long)]
+        metadata_address: Option<Pubkey>,
+        /// Metadata pointer authority keypair
+        #[arg(long)]
+        authority: Option<PathBuf>,
+    },
+}
+
+impl TokenArgs {
+    pub fn command(&self) -> TokenCommand {
+        match self {
+            TokenArgs::CreateMint { .. } => TokenCommand::CreateMint,
+            TokenArgs::CreateAccount { .. } => TokenCommand::CreateAccount,
+            TokenArgs::MintTo { .. } => TokenCommand::MintTo,
+            TokenArgs::Transfer { .. } => TokenCommand::Transfer,
+            TokenArgs::Burn { .. } => TokenCommand::Burn,
+            TokenArgs::Approve { .. } => TokenCommand::Approve,
+            TokenArgs::Revoke { .. } => TokenCommand::Revoke,
+            TokenArgs::Freeze { .. } => TokenCommand::Freeze,
+            TokenArgs::Thaw { .. } => TokenCommand::Thaw,
+            TokenArgs::Close { .. } => TokenCommand::CloseAccount,
+            TokenArgs::Balances { .. } => TokenCommand::Balances,
+            TokenArgs::WithdrawWithheld { .. } => TokenCommand::WithdrawWithheld,
+            TokenArgs::SetTransferFee { .. } => TokenCommand::SetTransferFee,
+            TokenArgs::SetMetadataPointer { .. } => TokenCommand::SetMetadataPointer,
+        }
+    }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
+        match self {
+            TokenArgs::CreateMint {
+                decimals,
+                mint_keypair,
+                freeze_authority,
+                no_freeze_authority,
+            } => {
+                let decimals =
+                    decimals.map_or_else(|| prompt_input_data("Enter decimals (0-9):"), Ok)?;
+                let freeze_authority = match freeze_authority {
+                    Some(authority) => Some(authority),
+                    None if no_freeze_authority => None,
+                    None => prompt_confirmation(&format!(
+                        "Let {} freeze token accounts of this mint?",
+                        ctx.pubkey()
+                    ))?
+                    .then_some(*ctx.pubkey()),
+                };
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    create_mint(ctx, decimals, mint_keypair, freeze_authority),
+                )
+                .await?;
+            }
+            TokenArgs::CreateAccount { mint, owner } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                let owner = owner.map_or_else(|| prompt_owner(ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, create_token_account(ctx, &mint, &owner))
+                    .await?;
+            }
+            TokenArgs::MintTo {
+                mint,
+                amount,
+                recipient,
+                mint_authority,
+            } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                let amount = amount.map_or_else(|| prompt_input_data("Enter amount:"), Ok)?;
+                let recipient = recipient.map_or_else(|| prompt_owner(ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    mint_tokens(ctx, &mint, &amount, &recipient, mint_authority),
+                )
+                .await?;
+            }
+            TokenArgs::Transfer {
+                mint,
+                to,
+                amount,
+                owner,
+            } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                let to = to.map_or_else(|| prompt_pubkey("Enter recipient Pubkey:", ctx), Ok)?;
+                let amount = amount.map_or_else(|| prompt_input_data("Enter amount:"), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    transfer_tokens(ctx, &mint, &to, &amount, owner),
+                )
+                .await?;
+            }
+            TokenArgs::Burn {
+                mint,
+                amount,
+                owner,
+            } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                let amount =
+                    amount.map_or_else(|| prompt_input_data("Enter amount to burn:"), Ok)?;
+                render_with_spinner(ctx, spinner_msg, burn_tokens(ctx, &mint, &amount, owner))
+                    .await?;
+            }
+            TokenArgs::Approve {
+                mint,
+                delegate,
+                amount,
+                owner,
+            } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                let delegate =
+                    delegate.map_or_else(|| prompt_pubkey("Enter delegate Pubkey:", ctx), Ok)?;
+                let amount =
+                    amount.map_or_else(|| prompt_input_data("Enter amount to approve:"), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    approve_delegate(ctx, &mint, &delegate, &amount, owner),
+                )
+                .await?;
+            }
+            TokenArgs::Revoke { mint, owner } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, revoke_delegate(ctx, &mint, owner)).await?;
+            }
+            TokenArgs::Freeze {
+                account,
+                freeze_authority,
+            } => {
+                let account = account
+                    .map_or_else(|| prompt_pubkey("Enter token account pubkey:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    set_frozen(ctx, &account, true, freeze_authority),
+                )
+                .await?;
+            }
+            TokenArgs::Thaw {
+                account,
+                freeze_authority,
+            } => {
+                let account = account
+                    .map_or_else(|| prompt_pubkey("Enter token account pubkey:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    set_frozen(ctx, &account, false, freeze_authority),
+                )
+                .await?;
+            }
+            TokenArgs::Close {
+                account,
+                recipient,
+                owner,
+                yes,
+            } => {
+                let account = account
+                    .map_or_else(|| prompt_pubkey("Enter token account pubkey:", ctx), Ok)?;
+                let recipient = recipient.unwrap_or(*ctx.pubkey());
+
+                if !yes
+                    && !prompt_confirmation(&format!(
+                        "Are you sure you want to close token account {account}?"
+                    ))?
+                {
+                    println!("{}", style("Close cancelled.").yellow());
+                    return Ok(());
+                }
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    close_token_account(ctx, &account, &recipient, owner),
+                )
+                .await?;
+            }
+            TokenArgs::Balances { owner } => {
+                let owner = owner.map_or_else(|| prompt_owner(ctx), Ok)?;
+                render_with_spinner(ctx, spinner_msg, fetch_token_balances(ctx, &owner)).await?;
+            }
+            TokenArgs::WithdrawWithheld {
+                mint,
+                destination,
+                withdraw_withheld_authority,
+            } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    extensions::withdraw_withheld_fees(
+                        ctx,
+                        &mint,
+                        destination,
+                        withdraw_withheld_authority,
+                    ),
+                )
+                .await?;
+            }
+            TokenArgs::SetTransferFee {
+                mint,
+                basis_points,
+                maximum_fee,
+                transfer_fee_authority,
+            } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                let basis_points = basis_points.map_or_else(
+                    || prompt_input_data("Enter fee in basis points (0-10000):"),
+                    Ok,
+                )?;
+                let maximum_fee = maximum_fee
+                    .map_or_else(|| prompt_input_data("Enter maximum fee (tokens):"), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    extensions::set_transfer_fee(
+                        ctx,
+                        &mint,
+                        basis_points,
+                        &maximum_fee,
+                        transfer_fee_authority,
+                    ),
+                )
+                .await?;
+            }
+            TokenArgs::SetMetadataPointer {
+                mint,
+                metadata_address,
+                authority,
+            } => {
+                let mint = mint.map_or_else(|| prompt_pubkey("Enter mint pubkey:", ctx), Ok)?;
+                let metadata_address = metadata_address
+                    .map_or_else(|| prompt_pubkey("Enter metadata account pubkey:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
+                    extensions::set_metadata_pointer(ctx, &mint, Some(metadata_address), authority),
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Asks whether the configured keypair is the wallet to use, and for another
+/// one otherwise.
+fn prompt_owner(ctx: &ScillaContext) -> anyhow::Result<Pubkey> {
+    if prompt_confirmation(&format!("Use wallet {}?", ctx.pubkey()))? {
+        Ok(*ctx.pubkey())
+    } else {
+        prompt_pubkey("Enter wallet Pubkey:", ctx)
+    }
+}
+
+/// Loads the authority keypair at `path`, or `None` for the configured one.
+fn read_authority(
+    ctx: &ScillaContext,
+    path: Option<PathBuf>,
+) -> anyhow::Result<Option<Box<dyn Signer>>> {
+    path.map(|path| read_signer(ctx, path)).transpose()
+}
+
+/// Whether `program_id` is SPL Token or Token-2022.
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    program_id == &spl_token_interface::id() || program_id == &spl_token_2022_interface::id()
+}
+
+/// A mint and the token program owning it. Token-2022 mints keep their
+/// extensions after the base state.
+struct MintState {
+    program_id: Pubkey,
+    base: Mint,
+    data: Vec<u8>,
+}
+
+impl MintState {
+    /// The mint with its extensions, which classic SPL Token mints have none
+    /// of.
+    fn extensions(&self) -> anyhow::Result<StateWithExtensions<'_, Mint>> {
+        StateWithExtensions::<Mint>::unpack(&self.data)
+            .map_err(|e| anyhow!("Failed to decode mint extensions: {e}"))
+    }
+}
+
+/// A token account and the token program owning it.
+struct TokenAccountState {
+    program_id: Pubkey,
+    base: TokenAccount,
+}
+
+async fn load_mint(ctx: &ScillaContext, mint: &Pubkey) -> anyhow::Result<MintState> {
+    let account = ctx.rpc().get_account(mint).await?;
+    if !is_token_program(&account.owner) {
+        bail!(
+            "{mint} is not a token mint, it is owned by {}",
+            account.owner
+        );
+    }
+
+    let base = StateWithExtensions::<Mint>::unpack(&account.data)
+        .map_err(|_| anyhow!("{mint} is not a token mint"))?
+        .base;
+    Ok(MintState {
+        program_id: account.owner,
+        base,
+        data: account.data,
+    })
+}
+
+async fn load_token_account(
+    ctx: &ScillaContext,
+    address: &Pubkey,
+) -> anyhow::Result<TokenAccountState> {
+    let account = ctx.rpc().get_account(address).await?;
+    if !is_token_program(&account.owner) {
+        bail!(
+            "{address} is not a token account, it is owned by {}",
+            account.owner
+        );
+    }
+
+    let base = StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .map_err(|_| anyhow!("{address} is not a token account"))?
+        .base;
+    Ok(TokenAccountState {
+        program_id: account.owner,
+        base,
+    })
+}
+
+/// Resolves `recipient` to a token account for `mint`. An existing token
+/// account is used as is; a wallet gets its associated token account, along
+/// with the instruction creating it when it does not exist yet.
+async fn resolve_recipient(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+    recipient: &Pubkey,
+) -> anyhow::Result<(Pubkey, Option<Instruction>)> {
+    let account = ctx
+        .rpc()
+        .get_account_with_commitment(recipient, ctx.rpc().commitment())
+        .await?
+        .value;
+    if let Some(account) = account
+        && is_token_program(&account.owner)
+    {
+        let token_account = StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .map_err(|_| anyhow!("{recipient} is not a token account or a wallet"))?
+            .base;
+        if &token_account.mint != mint {
+            bail!(
+                "Token account {recipient} holds mint {}, not {mint}",
+                token_account.mint
+            );
+        }
+        return Ok((*recipient, None));
+    }
+
+    let address = get_associated_token_address_with_program_id(recipient, mint, program_id);
+    let exists = ctx
+        .rpc()
+        .get_account_with_commitment(&address, ctx.rpc().commitment())
+        .await?
+        .value
+        .is_some();
+    let create = (!exists).then(|| {
+        create_associated_token_account_idempotent(ctx.pubkey(), recipient, mint, program_id)
+    });
+
+    Ok((address, create))
+}
+
+/// Converts `amount` to base units and makes sure `account` holds that many.
+fn check_token_balance(
+    account: &TokenAccount,
+    amount: &TokenAmount,
+    decimals: u8,
+) -> anyhow::Result<u64> {
+    let base_units = amount.to_base_units(decimals)?;
+    if base_units > account.amount {
+        bail!(
+            "Insufficient token balance. You have {} but tried to use {amount}",
+            real_number_string_trimmed(account.amount, decimals)
+        );
+    }
+    Ok(base_units)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMintOutput {
+    pub signature: String,
+    pub mint: String,
+    pub decimals: u8,
+    pub mint_authority: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freeze_authority: Option<String>,
+}
+
+impl CommandOutput for CreateMintOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("MINT");
+        table
+            .field("Mint", &self.mint)
+            .field("Decimals", self.decimals)
+            .field("Mint Authority", &self.mint_authority)
+            .field(
+                "Freeze Authority",
+                self.freeze_authority.as_deref().unwrap_or("None"),
+            );
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        println!(
+            "{}\n{}",
+            style("Mint created successfully!").yellow().bold(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+async fn create_mint(
+    ctx: &ScillaContext,
+    decimals: u8,
+    mint_keypair: Option<PathBuf>,
+    freeze_authority: Option<Pubkey>,
+) -> anyhow::Result<CreateMintOutput> {
+    let mint_keypair: Box<dyn Signer> = match mint_keypair {
+        Some(path) => read_signer(ctx, path)?,
+        None => Box::new(Keypair::new()),
+    };
+    let mint = mint_keypair.pubkey();
+    if &mint == ctx.pubkey() {
+        bail!("Mint {mint} cannot be the same as fee payer account");
+    }
+
+    let program_id = spl_token_interface::id();
+    let rent = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(Mint::LEN)
+        .await?;
+    check_minimum_balance(ctx, ctx.pubkey(), rent).await?;
+
+    let instructions = [
+        create_account(ctx.pubkey(), &mint, rent, Mint::LEN as u64, &program_id),
+        token_instruction::initialize_mint2(
+            &program_id,
+            &mint,
+            ctx.pubkey(),
+            freeze_authority.as_ref(),
+            decimals,
+        )?,
+    ];
+    let signature =
+        build_and_send_tx(ctx, &instructions, &[ctx.keypair(), mint_keypair.as_ref()]).await?;
+
+    Ok(CreateMintOutput {
+        signature: signature.to_string(),
+        mint: mint.to_string(),
+        decimals,
+        mint_authority: ctx.pubkey().to_string(),
+        freeze_authority: freeze_authority.map(|authority| authority.to_string()),
+    })
+}
+
+/// The result of a token instruction sent for one token account.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTxOutput {
+    #[serde(skip)]
+    success_msg: &'static str,
+    pub signature: String,
+    pub mint: String,
+    pub account: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegate: Option<String>,
+}
+
+impl TokenTxOutput {
+    fn new(
+        success_msg: &'static str,
+        signature: impl ToString,
+        mint: &Pubkey,
+        account: &Pubkey,
+    ) -> Self {
+        Self {
+            success_msg,
+            signature: signature.to_string(),
+            mint: mint.to_string(),
+            account: account.to_string(),
+            amount: None,
+            destination: None,
+            delegate: None,
+        }
+    }
+
+    fn with_amount(mut self, amount: u64, decimals: u8) -> Self {
+        self.amount = Some(real_number_string_trimmed(amount, decimals));
+        self
+    }
+
+    fn with_destination(mut self, destination: &Pubkey) -> Self {
+        self.destination = Some(destination.to_string());
+        self
+    }
+
+    fn with_delegate(mut self, delegate: &Pubkey) -> Self {
+        self.delegate = Some(delegate.to_string());
+        self
+    }
+}
+
+impl CommandOutput for TokenTxOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::key_value("TOKEN ACCOUNT");
+        table
+            .field("Mint", &self.mint)
+            .field("Token Account", &self.account);
+        if let Some(amount) = &self.amount {
+            table.field("Amount", amount);
+        }
+        if let Some(destination) = &self.destination {
+            table.field("Destination", destination);
+        }
+        if let Some(delegate) = &self.delegate {
+            table.field("Delegate", delegate);
+        }
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        println!(
+            "{}\n{}",
+            style(self.success_msg).yellow().bold(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+async fn create_token_account(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> anyhow::Result<TokenTxOutput> {
+    let mint_state = load_mint(ctx, mint).await?;
+    let (address, create) = resolve_recipient(ctx, mint, &mint_state.program_id, owner).await?;
+    let Some(instruction) = create else {
+        bail!("Token account {address} of {owner} already exists");
+    };
+
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+
+    Ok(TokenTxOutput::new(
+        "Token account created successfully!",
+        signature,
+        mint,
+        &address,
+    ))
+}
+
+async fn mint_tokens(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    amount: &TokenAmount,
+    recipient: &Pubkey,
+    mint_authority: Option<PathBuf>,
+) -> anyhow::Result<TokenTxOutput> {
+    let mint_authority = read_authority(ctx, mint_authority)?;
+    let authority = mint_authority.as_deref().unwrap_or(ctx.keypair());
+
+    let mint_state = load_mint(ctx, mint).await?;
+    match Option::<Pubkey>::from(mint_state.base.mint_authority) {
+        None => bail!("Mint {mint} has a fixed supply, no more tokens can be minted"),
+        Some(expected) if expected != authority.pubkey() => bail!(
+            "{} is not the mint authority of {mint}, {expected} is",
+            authority.pubkey()
+        ),
+        Some(_) => {}
+    }
+    let decimals = mint_state.base.decimals;
+    let base_units = amount.to_base_units(decimals)?;
+    let (destination, create) =
+        resolve_recipient(ctx, mint, &mint_state.program_id, recipient).await?;
+
+    let mut instructions: Vec<_> = create.into_iter().collect();
+    instructions.push(token_instruction::mint_to_checked(
+        &mint_state.program_id,
+        mint,
+        &destination,
+        &authority.pubkey(),
+        &[],
+        base_units,
+        decimals,
+    )?);
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.keypair(), authority]).await?;
+
+    Ok(
+        TokenTxOutput::new("Tokens minted successfully!", signature, mint, &destination)
+            .with_amount(base_units, decimals),
+    )
+}
+
+async fn transfer_tokens(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    to: &Pubkey,
+    amount: &TokenAmount,
+    owner: Option<PathBuf>,
+) -> anyhow::Result<TokenTxOutput> {
+    let owner_signer = read_authority(ctx, owner)?;
+    let owner = owner_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let mint_state = load_mint(ctx, mint).await?;
+    // The hook program needs extra accounts that are resolved on-chain
+    if let Ok(hook) = mint_state.extensions()?.get_extension::<TransferHook>()
+        && let Some(hook_program) = Option::<Pubkey>::from(hook.program_id)
+    {
+        bail!("Mint {mint} runs transfer hook program {hook_program}, which is not supported yet");
+    }
+    let program_id = mint_state.program_id;
+    let decimals = mint_state.base.decimals;
+    let source = get_associated_token_address_with_program_id(&owner.pubkey(), mint, &program_id);
+    let source_account = load_token_account(ctx, &source).await?;
+    let base_units = check_token_balance(&source_account.base, amount, decimals)?;
+    let (destination, create) = resolve_recipient(ctx, mint, &program_id, to).await?;
+    if destination == source {
+        bail!("Source and destination token accounts are the same: {source}");
+    }
+
+    let mut instructions: Vec<_> = create.into_iter().collect();
+    instructions.push(token_instruction::transfer_checked(
+        &program_id,
+        &source,
+        mint,
+        &destination,
+        &owner.pubkey(),
+        &[],
+        base_units,
+        decimals,
+    )?);
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.keypair(), owner]).await?;
+
+    Ok(
+        TokenTxOutput::new("Transfer successful!", signature, mint, &source)
+            .with_amount(base_units, decimals)
+            .with_destination(&destination),
+    )
+}
+
+async fn burn_tokens(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    amount: &TokenAmount,
+    owner: Option<PathBuf>,
+) -> anyhow::Result<TokenTxOutput> {
+    let owner_signer = read_authority(ctx, owner)?;
+    let owner = owner_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let mint_state = load_mint(ctx, mint).await?;
+    let program_id = mint_state.program_id;
+    let decimals = mint_state.base.decimals;
+    let account = get_associated_token_address_with_program_id(&owner.pubkey(), mint, &program_id);
+    let token_account = load_token_account(ctx, &account).await?;
+    let base_units = check_token_balance(&token_account.base, amount, decimals)?;
+
+    let instruction = token_instruction::burn_checked(
+        &program_id,
+        &account,
+        mint,
+        &owner.pubkey(),
+        &[],
+        base_units,
+        decimals,
+    )?;
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), owner]).await?;
+
+    Ok(
+        TokenTxOutput::new("Tokens burned successfully!", signature, mint, &account)
+            .with_amount(base_units, decimals),
+    )
+}
+
+async fn approve_delegate(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    delegate: &Pubkey,
+    amount: &TokenAmount,
+    owner: Option<PathBuf>,
+) -> anyhow::Result<TokenTxOutput> {
+    let owner_signer = read_authority(ctx, owner)?;
+    let owner = owner_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let mint_state = load_mint(ctx, mint).await?;
+    let program_id = mint_state.program_id;
+    let decimals = mint_state.base.decimals;
+    let account = get_associated_token_address_with_program_id(&owner.pubkey(), mint, &program_id);
+    load_token_account(ctx, &account).await?;
+    let base_units = amount.to_base_units(decimals)?;
+
+    let instruction = token_instruction::approve_checked(
+        &program_id,
+        &account,
+        mint,
+        delegate,
+        &owner.pubkey(),
+        &[],
+        base_units,
+        decimals,
+    )?;
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), owner]).await?;
+
+    Ok(
+        TokenTxOutput::new("Delegate approved successfully!", signature, mint, &account)
+            .with_amount(base_units, decimals)
+            .with_delegate(delegate),
+    )
+}
+
+async fn revoke_delegate(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    owner: Option<PathBuf>,
+) -> anyhow::Result<TokenTxOutput> {
+    let owner_signer = read_authority(ctx, owner)?;
+    let owner = owner_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let program_id = load_mint(ctx, mint).await?.program_id;
+    let account = get_associated_token_address_with_program_id(&owner.pubkey(), mint, &program_id);
+    let token_account = load_token_account(ctx, &account).await?;
+    if token_account.base.delegate.is_none() {
+        bail!("Token account {account} has no delegate");
+    }
+
+    let instruction = token_instruction::revoke(&program_id, &account, &owner.pubkey(), &[])?;
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), owner]).await?;
+
+    Ok(TokenTxOutput::new(
+        "Delegate revoked successfully!",
+        signature,
+        mint,
+        &account,
+    ))
+}
+
+async fn set_frozen(
+    ctx: &ScillaContext,
+    account: &Pubkey,
+    freeze: bool,
+    freeze_authority: Option<PathBuf>,
+) -> anyhow::Result<TokenTxOutput> {
+    let authority_signer = read_authority(ctx, freeze_authority)?;
+    let authority = authority_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let token_account = load_token_account(ctx, account).await?;
+    if token_account.base.is_frozen() == freeze {
+        let state = if freeze { "frozen" } else { "not frozen" };
+        bail!("Token account {account} is already {state}");
+    }
+    let mint = token_account.base.mint;
+    let mint_state = load_mint(ctx, &mint).await?;
+    match Option::<Pubkey>::from(mint_state.base.freeze_authority) {
+        None => bail!("Mint {mint} has no freeze authority"),
+        Some(expected) if expected != authority.pubkey() => bail!(
+            "{} is not the freeze authority of mint {mint}, {expected} is",
+            authority.pubkey()
+        ),
+        Some(_) => {}
+    }
+
+    let program_id = token_account.program_id;
+    let (instruction, success_msg) = if freeze {
+        (
+            token_instruction::freeze_account(
+                &program_id,
+                account,
+                &mint,
+                &authority.pubkey(),
+                &[],
+            )?,
+            "Token account frozen successfully!",
+        )
+    } else {
+        (
+            token_instruction::thaw_account(&program_id, account, &mint, &authority.pubkey(), &[])?,
+            "Token account thawed successfully!",
+        )
+    };
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), authority]).await?;
+
+    Ok(TokenTxOutput::new(success_msg, signature, &mint, account))
+}
+
+async fn close_token_account(
+    ctx: &ScillaContext,
+    account: &Pubkey,
+    recipient: &Pubkey,
+    owner: Option<PathBuf>,
+) -> anyhow::Result<TokenTxOutput> {
+    let owner_signer = read_authority(ctx, owner)?;
+    let owner = owner_signer.as_deref().unwrap_or(ctx.keypair());
+
+    let TokenAccountState {
+        program_id,
+        base: token_account,
+    } = load_token_account(ctx, account).await?;
+    // Wrapped SOL is unwrapped by closing the account, everything else has to
+    // be emptied first
+    if !token_account.is_native() && token_account.amount > 0 {
+        let decimals = load_mint(ctx, &token_account.mint).await?.base.decimals;
+        bail!(
+            "Token account {account} still holds {} tokens. Transfer or burn them before closing \
+             it",
+            real_number_string_trimmed(token_account.amount, decimals)
+        );
+    }
+    let close_authority =
+        Option::<Pubkey>::from(token_account.close_authority).unwrap_or(token_account.owner);
+    if close_authority != owner.pubkey() {
+        bail!(
+            "{} cannot close token account {account}, only {close_authority} can",
+            owner.pubkey()
+        );
+    }
+
+    let instruction =
+        token_instruction::close_account(&program_id, account, recipient, &owner.pubkey(), &[])?;
+    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair(), owner]).await?;
+
+    Ok(TokenTxOutput::new(
+        "Token account closed successfully!",
+        signature,
+        &token_account.mint,
+        account,
+    )
+    .with_destination(recipient))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalance {
+    pub address: String,
+    pub mint: String,
+    /// `spl-token` or `spl-token-2022`
+    pub program: String,
+    pub amount: String,
+    pub decimals: u8,
+    pub frozen: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegate: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalancesOutput {
+    pub owner: String,
+    pub accounts: Vec<TokenBalance>,
+    #[serde(skip_serializing_if = "AddressLabels::is_empty")]
+    pub labels: AddressLabels,
+}
+
+impl CommandOutput for TokenBalancesOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut table = TableView::new(
+            "TOKEN BALANCES",
+            ["Mint", "Token Account", "Program", "Balance", "State"],
+        );
+        for account in &self.accounts {
+            let state = if account.frozen {
+                TableCell::new("Frozen").fg(Color::Red)
+            } else {
+                TableCell::new("Active").fg(Color::Green)
+            };
+            table.row([
+                TableCell::new(with_label(&account.mint, &self.labels)),
+                TableCell::new(&account.address),
+                TableCell::new(&account.program),
+                TableCell::new(&account.amount),
+                state,
+            ]);
+        }
+
+        vec![table]
+    }
+
+    fn render_table(&self) {
+        if self.accounts.is_empty() {
+            println!(
+                "{}",
+                style(format!("No token accounts found for {}", self.owner)).yellow()
+            );
+            return;
+        }
+
+        for table in self.tables() {
+            table.print();
+        }
+    }
+}
+
+async fn fetch_token_balances(
+    ctx: &ScillaContext,
+    owner: &Pubkey,
+) -> anyhow::Result<TokenBalancesOutput> {
+    let (token_accounts, token_2022_accounts) = try_join!(
+        ctx.rpc().get_token_accounts_by_owner(
+            owner,
+            TokenAccountsFilter::ProgramId(spl_token_interface::id()),
+        ),
+        ctx.rpc().get_token_accounts_by_owner(
+            owner,
+            TokenAccountsFilter::ProgramId(spl_token_2022_interface::id()),
+        ),
+    )?;
+
+    let mut accounts = Vec::with_capacity(token_accounts.len() + token_2022_accounts.len());
+    for keyed in token_accounts.into_iter().chain(token_2022_accounts) {
+        let UiAccountData::Json(parsed) = keyed.account.data else {
+            bail!("RPC returned token account {} undecoded", keyed.pubkey);
+        };
+        let TokenAccountType::Account(account) = serde_json::from_value(parsed.parsed)? else {
+            continue;
+        };
+
+        accounts.push(TokenBalance {
+            address: keyed.pubkey,
+            mint: account.mint,
+            program: parsed.program,
+            amount: account.token_amount.real_number_string_trimmed(),
+            decimals: account.token_amount.decimals,
+            frozen: account.state == UiAccountState::Frozen,
+            delegate: account.delegate,
+        });
+    }
+    accounts.sort_by(|a, b| a.mint.cmp(&b.mint));
+
+    let labels = ctx
+        .address_book()
+        .labels(accounts.iter().map(|account| account.mint.as_str()));
+
+    Ok(TokenBalancesOutput {
+        owner: owner.to_string(),
+        accounts,
+        labels,
+    })
+}
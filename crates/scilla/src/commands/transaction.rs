@@ -1,20 +1,40 @@
 use {
     crate::{
-        commands::{Command, CommandFlow, NavigationTarget, navigation::NavigationSection},
+        address_book::{AddressLabels, with_label},
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
+        config::Transport,
         context::ScillaContext,
-        misc::helpers::decode_and_deserialize_transaction,
+        error::ScillaError,
+        misc::{
+            helpers::{decode_and_deserialize_transaction, send_and_confirm},
+            offline::{
+                PubkeySignature, SignedTransactionOutput, add_signature, missing_signers, sign_with,
+            },
+            signer::load_signer,
+        },
+        output::{Color, CommandOutput, SignatureOutput, TableCell, TableView},
         prompt::{prompt_confirmation, prompt_encoding_options, prompt_input_data},
-        ui::show_spinner,
+        ui::{print_error, render_output, render_with_spinner},
     },
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    anyhow::bail,
+    clap::{Subcommand, ValueEnum},
     console::style,
+    serde::Serialize,
     solana_account_decoder::UiAccount,
-    solana_rpc_client_api::config::{RpcSimulateTransactionConfig, RpcTransactionConfig},
+    solana_keypair::Signer,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{
+        config::{RpcSimulateTransactionConfig, RpcTransactionConfig},
+        response::RpcSimulateTransactionResult,
+    },
     solana_signature::Signature,
+    solana_transaction::versioned::VersionedTransaction,
     solana_transaction_status::{
-        EncodedTransaction, UiInnerInstructions, UiMessage, UiTransactionEncoding,
+        EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+        TransactionConfirmationStatus, TransactionStatus, UiInnerInstructions, UiMessage,
+        UiTransactionEncoding,
     },
-    std::fmt,
+    std::{fmt, path::PathBuf},
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -24,20 +44,7 @@ pub enum TransactionCommand {
     FetchTransaction,
     SendTransaction,
     SimulateTransaction,
-    GoBack,
-}
-
-impl TransactionCommand {
-    pub fn spinner_msg(&self) -> &'static str {
-        match self {
-            Self::CheckConfirmation => "Checking transaction confirmation…",
-            Self::FetchStatus => "Fetching transaction status…",
-            Self::FetchTransaction => "Fetching full transaction data…",
-            Self::SendTransaction => "Sending transaction…",
-            Self::SimulateTransaction => "Simulating transaction…",
-            Self::GoBack => "Going back…",
-        }
-    }
+    AddSignatures,
 }
 
 impl fmt::Display for TransactionCommand {
@@ -48,181 +55,486 @@ impl fmt::Display for TransactionCommand {
             Self::FetchTransaction => "Fetch Transaction",
             Self::SendTransaction => "Send Transaction",
             Self::SimulateTransaction => "Simulate Transaction",
-            Self::GoBack => "Go back",
+            Self::AddSignatures => "Add Signatures and Submit",
         })
     }
 }
 
 impl Command for TransactionCommand {
-    async fn process_command(&self, ctx: &mut ScillaContext) -> anyhow::Result<CommandFlow> {
-        ctx.get_nav_context_mut()
-            .checked_push(NavigationSection::Transaction);
+    fn spinner_msg(&self) -> &'static str {
         match self {
+            Self::CheckConfirmation => "Checking transaction confirmation…",
+            Self::FetchStatus => "Fetching transaction status…",
+            Self::FetchTransaction => "Fetching full transaction data…",
+            Self::SendTransaction => "Sending transaction…",
+            Self::SimulateTransaction => "Simulating transaction…",
+            Self::AddSignatures => "Submitting transaction…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
             TransactionCommand::CheckConfirmation => {
-                let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(self.spinner_msg(), check_confirmation(ctx, &signature)).await;
+                TransactionArgs::CheckConfirmation { signature: None }
             }
-            TransactionCommand::FetchStatus => {
-                let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(
-                    self.spinner_msg(),
-                    fetch_transaction_status(ctx, &signature),
-                )
-                .await;
+            TransactionCommand::FetchStatus => TransactionArgs::Status { signature: None },
+            TransactionCommand::FetchTransaction => TransactionArgs::Fetch { signature: None },
+            TransactionCommand::SendTransaction => TransactionArgs::Send {
+                encoding: None,
+                transaction: None,
+            },
+            TransactionCommand::SimulateTransaction => TransactionArgs::Simulate {
+                relaxed: None,
+                encoding: None,
+                transaction: None,
+            },
+            TransactionCommand::AddSignatures => TransactionArgs::Submit {
+                encoding: None,
+                transaction: None,
+                signers: Vec::new(),
+                keypairs: Vec::new(),
+            },
+        };
+
+        // A malformed transaction or a missing signature should not end the
+        // interactive session
+        Box::pin(async move {
+            CommandFlow::from_run(args.run(ctx).await).or_else(|e| {
+                print_error(e);
+                Ok(CommandFlow::Processed)
+            })
+        })
+    }
+}
+
+/// The Transaction menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Transaction").commands([
+        TransactionCommand::CheckConfirmation,
+        TransactionCommand::FetchStatus,
+        TransactionCommand::FetchTransaction,
+        TransactionCommand::SendTransaction,
+        TransactionCommand::SimulateTransaction,
+        TransactionCommand::AddSignatures,
+    ])
+}
+
+/// Non-interactive arguments for [`TransactionCommand`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum TransactionArgs {
+    /// Check whether a transaction has been confirmed
+    CheckConfirmation { signature: Option<Signature> },
+    /// Fetch the status of a transaction
+    Status { signature: Option<Signature> },
+    /// Fetch full transaction data
+    Fetch { signature: Option<Signature> },
+    /// Send an encoded VersionedTransaction
+    Send {
+        #[arg(long, value_enum)]
+        encoding: Option<TransactionEncoding>,
+        /// Encoded transaction
+        transaction: Option<String>,
+    },
+    /// Simulate an encoded VersionedTransaction
+    Simulate {
+        /// Skip signature verification and refresh the blockhash
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        relaxed: Option<bool>,
+        #[arg(long, value_enum)]
+        encoding: Option<TransactionEncoding>,
+        /// Encoded transaction
+        transaction: Option<String>,
+    },
+    /// Add the signatures collected in sign-only mode to a transaction, then
+    /// send it
+    Submit {
+        #[arg(long, value_enum)]
+        encoding: Option<TransactionEncoding>,
+        /// Encoded, partially signed transaction
+        transaction: Option<String>,
+        /// A signature made elsewhere, as PUBKEY=SIGNATURE
+        #[arg(long = "signer", value_name = "PUBKEY=SIGNATURE")]
+        signers: Vec<PubkeySignature>,
+        /// A keypair on this machine that still has to sign
+        #[arg(long = "keypair", value_name = "KEYPAIR_PATH")]
+        keypairs: Vec<PathBuf>,
+    },
+}
+
+/// Command-line names for the supported [`UiTransactionEncoding`]s.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum TransactionEncoding {
+    Base64,
+    Base58,
+    Binary,
+    Json,
+    JsonParsed,
+}
+
+impl From<TransactionEncoding> for UiTransactionEncoding {
+    fn from(encoding: TransactionEncoding) -> Self {
+        match encoding {
+            TransactionEncoding::Base64 => UiTransactionEncoding::Base64,
+            TransactionEncoding::Base58 => UiTransactionEncoding::Base58,
+            TransactionEncoding::Binary => UiTransactionEncoding::Binary,
+            TransactionEncoding::Json => UiTransactionEncoding::Json,
+            TransactionEncoding::JsonParsed => UiTransactionEncoding::JsonParsed,
+        }
+    }
+}
+
+impl TransactionArgs {
+    pub fn command(&self) -> TransactionCommand {
+        match self {
+            TransactionArgs::CheckConfirmation { .. } => TransactionCommand::CheckConfirmation,
+            TransactionArgs::Status { .. } => TransactionCommand::FetchStatus,
+            TransactionArgs::Fetch { .. } => TransactionCommand::FetchTransaction,
+            TransactionArgs::Send { .. } => TransactionCommand::SendTransaction,
+            TransactionArgs::Simulate { .. } => TransactionCommand::SimulateTransaction,
+            TransactionArgs::Submit { .. } => TransactionCommand::AddSignatures,
+        }
+    }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
+        match self {
+            TransactionArgs::CheckConfirmation { signature } => {
+                let signature = signature
+                    .map_or_else(|| prompt_input_data("Enter transaction signature:"), Ok)?;
+                render_with_spinner(ctx, spinner_msg, check_confirmation(ctx, &signature)).await?;
             }
-            TransactionCommand::FetchTransaction => {
-                let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(self.spinner_msg(), fetch_transaction(ctx, &signature)).await;
+            TransactionArgs::Status { signature } => {
+                let signature = signature
+                    .map_or_else(|| prompt_input_data("Enter transaction signature:"), Ok)?;
+                render_with_spinner(ctx, spinner_msg, fetch_transaction_status(ctx, &signature))
+                    .await?;
             }
-            TransactionCommand::SendTransaction => {
-                println!(
-                    "{}",
-                    style("Note: Only VersionedTransaction format is supported")
-                        .yellow()
-                        .dim()
-                );
-
-                let encoding = prompt_encoding_options();
+            TransactionArgs::Fetch { signature } => {
+                let signature = signature
+                    .map_or_else(|| prompt_input_data("Enter transaction signature:"), Ok)?;
+                render_with_spinner(ctx, spinner_msg, fetch_transaction(ctx, &signature)).await?;
+            }
+            TransactionArgs::Send {
+                encoding,
+                transaction,
+            } => {
+                let encoding = encoding.map(UiTransactionEncoding::from).map_or_else(
+                    || {
+                        println!(
+                            "{}",
+                            style("Note: Only VersionedTransaction format is supported")
+                                .yellow()
+                                .dim()
+                        );
+                        prompt_encoding_options()
+                    },
+                    Ok,
+                )?;
 
-                let encoded_tx: String = prompt_input_data("Enter encoded transaction:");
+                let encoded_tx = transaction
+                    .map_or_else(|| prompt_input_data("Enter encoded transaction:"), Ok)?;
 
-                show_spinner(
-                    self.spinner_msg(),
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     send_transaction(ctx, encoding, &encoded_tx),
                 )
-                .await;
+                .await?;
             }
-            TransactionCommand::SimulateTransaction => {
-                println!(
-                    "{}",
-                    style("Note: Only VersionedTransaction format is supported")
-                        .yellow()
-                        .dim()
-                );
-
-                let relaxed = prompt_confirmation(
-                    "Use relaxed mode (skip signature verification, refresh blockhash)? (y/n):",
-                );
-
-                let encoding = prompt_encoding_options();
+            TransactionArgs::Simulate {
+                relaxed,
+                encoding,
+                transaction,
+            } => {
+                let relaxed = relaxed.map_or_else(
+                    || {
+                        prompt_confirmation(
+                            "Use relaxed mode (skip signature verification, refresh blockhash)? \
+                             (y/n):",
+                        )
+                    },
+                    Ok,
+                )?;
+
+                let encoding = encoding.map(UiTransactionEncoding::from).map_or_else(
+                    || {
+                        println!(
+                            "{}",
+                            style("Note: Only VersionedTransaction format is supported")
+                                .yellow()
+                                .dim()
+                        );
+                        prompt_encoding_options()
+                    },
+                    Ok,
+                )?;
 
-                let encoded_tx: String = prompt_input_data("Enter encoded transaction:");
+                let encoded_tx = transaction
+                    .map_or_else(|| prompt_input_data("Enter encoded transaction:"), Ok)?;
 
-                show_spinner(
-                    self.spinner_msg(),
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     simulate_transaction(ctx, encoding, &encoded_tx, relaxed),
                 )
-                .await;
+                .await?;
             }
-            TransactionCommand::GoBack => {
-                return Ok(CommandFlow::NavigateTo(NavigationTarget::PreviousSection));
+            TransactionArgs::Submit {
+                encoding,
+                transaction,
+                signers,
+                keypairs,
+            } => {
+                let encoding = encoding
+                    .map(UiTransactionEncoding::from)
+                    .map_or_else(prompt_encoding_options, Ok)?;
+
+                let encoded_tx = transaction.map_or_else(
+                    || prompt_input_data("Enter partially signed transaction:"),
+                    Ok,
+                )?;
+
+                let mut tx = decode_and_deserialize_transaction(encoding, &encoded_tx)?;
+                for pair in signers {
+                    add_signature(&mut tx, pair)?;
+                }
+                for path in keypairs {
+                    sign_with(&mut tx, load_signer(path)?.as_ref())?;
+                }
+                if ctx.is_interactive() {
+                    prompt_missing_signatures(&mut tx)?;
+                }
+
+                let missing = missing_signers(&tx);
+                if !missing.is_empty() {
+                    if ctx.is_sign_only() {
+                        render_output(ctx, &SignedTransactionOutput::from(&tx))?;
+                        return Err(ScillaError::SignOnly.into());
+                    }
+                    bail!(
+                        "Still missing signatures from {}",
+                        missing
+                            .iter()
+                            .map(Pubkey::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                }
+
+                if ctx.is_dry_run() {
+                    render_with_spinner(ctx, spinner_msg, async {
+                        simulate_signed_transaction(ctx, &tx).await
+                    })
+                    .await?;
+                    return Err(ScillaError::DryRun.into());
+                }
+
+                render_with_spinner(ctx, spinner_msg, submit_transaction(ctx, &tx)).await?;
             }
         }
 
-        Ok(CommandFlow::Processed)
+        Ok(())
+    }
+}
+
+fn status_cell<E: fmt::Debug>(err: &Option<E>) -> TableCell {
+    match err {
+        None => TableCell::new("Success").fg(Color::Green),
+        Some(err) => TableCell::new(format!("Error: {err:?}")).fg(Color::Red),
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmationOutput {
+    pub signature: String,
+    pub confirmed: bool,
+}
+
+impl CommandOutput for ConfirmationOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let status = if self.confirmed {
+            TableCell::new("Confirmed").fg(Color::Green)
+        } else {
+            TableCell::new("Not Confirmed").fg(Color::Yellow)
+        };
+
+        let mut table = TableView::key_value("TRANSACTION CONFIRMATION");
+        table
+            .field("Signature", &self.signature)
+            .field("Status", status);
+
+        vec![table]
     }
 }
 
-async fn check_confirmation(ctx: &ScillaContext, signature: &Signature) -> anyhow::Result<()> {
+async fn check_confirmation(
+    ctx: &ScillaContext,
+    signature: &Signature,
+) -> anyhow::Result<ConfirmationOutput> {
     let confirmed = ctx.rpc().confirm_transaction(signature).await?;
 
-    let status_styled = if confirmed {
-        style("Confirmed").green()
-    } else {
-        style("Not Confirmed").yellow()
-    };
+    Ok(ConfirmationOutput {
+        signature: signature.to_string(),
+        confirmed,
+    })
+}
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Status"), Cell::new(status_styled)]);
-
-    println!("\n{}", style("TRANSACTION CONFIRMATION").green().bold());
-    println!("{table}");
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatusOutput {
+    pub signature: String,
+    #[serde(flatten)]
+    pub status: TransactionStatus,
+}
 
-    Ok(())
+impl CommandOutput for TransactionStatusOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let tx_status = &self.status;
+
+        let mut table = TableView::key_value("TRANSACTION STATUS");
+        table
+            .field("Signature", &self.signature)
+            .field("Slot", tx_status.slot);
+
+        if let Some(confirmations) = tx_status.confirmations {
+            table.field("Confirmations", confirmations);
+        } else {
+            table.field(
+                "Confirmations",
+                TableCell::new("Finalized").fg(Color::Green),
+            );
+        }
+
+        if let Some(confirmation_status) = &tx_status.confirmation_status {
+            table.field(
+                "Confirmation Status",
+                match confirmation_status {
+                    TransactionConfirmationStatus::Processed => {
+                        TableCell::new("Processed").fg(Color::Yellow)
+                    }
+                    TransactionConfirmationStatus::Confirmed => {
+                        TableCell::new("Confirmed").fg(Color::Cyan)
+                    }
+                    TransactionConfirmationStatus::Finalized => {
+                        TableCell::new("Finalized").fg(Color::Green)
+                    }
+                },
+            );
+        }
+
+        table.field("Status", status_cell(&tx_status.err));
+
+        vec![table]
+    }
 }
 
 async fn fetch_transaction_status(
     ctx: &ScillaContext,
     signature: &Signature,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<TransactionStatusOutput> {
     let status = ctx
         .rpc()
         .get_signature_statuses_with_history(&[*signature])
         .await?;
 
-    let Some(Some(tx_status)) = status.value.first() else {
+    let Some(Some(tx_status)) = status.value.into_iter().next() else {
         anyhow::bail!("Transaction not found");
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Slot"), Cell::new(tx_status.slot)]);
-
-    if let Some(confirmations) = tx_status.confirmations {
-        table.add_row(vec![Cell::new("Confirmations"), Cell::new(confirmations)]);
-    } else {
-        table.add_row(vec![
-            Cell::new("Confirmations"),
-            Cell::new(style("Finalized").green()),
-        ]);
-    }
+    Ok(TransactionStatusOutput {
+        signature: signature.to_string(),
+        status: tx_status,
+    })
+}
 
-    if let Some(confirmation_status) = &tx_status.confirmation_status {
-        table.add_row(vec![
-            Cell::new("Confirmation Status"),
-            Cell::new(match confirmation_status {
-                solana_transaction_status::TransactionConfirmationStatus::Processed => {
-                    style("Processed").yellow().to_string()
-                }
-                solana_transaction_status::TransactionConfirmationStatus::Confirmed => {
-                    style("Confirmed").cyan().to_string()
-                }
-                solana_transaction_status::TransactionConfirmationStatus::Finalized => {
-                    style("Finalized").green().to_string()
-                }
-            }),
-        ]);
-    }
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionOutput {
+    pub signature: String,
+    #[serde(flatten)]
+    pub tx: EncodedConfirmedTransactionWithStatusMeta,
+    #[serde(skip_serializing_if = "AddressLabels::is_empty")]
+    pub labels: AddressLabels,
+}
 
-    table.add_row(vec![
-        Cell::new("Status"),
-        Cell::new(if tx_status.err.is_none() {
-            style("Success").green().to_string()
-        } else {
-            style(format!("Error: {:?}", tx_status.err))
-                .red()
-                .to_string()
-        }),
-    ]);
+impl CommandOutput for TransactionOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut details = TableView::key_value("TRANSACTION DETAILS");
+        details
+            .field("Signature", &self.signature)
+            .field("Slot", self.tx.slot);
 
-    println!("\n{}", style("TRANSACTION STATUS").green().bold());
-    println!("{table}");
+        if let Some(block_time) = self.tx.block_time {
+            details.field("Block Time", block_time);
+        }
 
-    Ok(())
+        if let Some(meta) = &self.tx.transaction.meta {
+            details
+                .field("Fee (lamports)", meta.fee)
+                .field("Status", status_cell(&meta.err));
+        }
+
+        let EncodedTransaction::Json(ui_tx) = &self.tx.transaction.transaction else {
+            return vec![details];
+        };
+
+        let (message_title, account_count, recent_blockhash) = match &ui_tx.message {
+            UiMessage::Parsed(parsed_msg) => (
+                "TRANSACTION MESSAGE",
+                parsed_msg.account_keys.len(),
+                &parsed_msg.recent_blockhash,
+            ),
+            UiMessage::Raw(raw_msg) => (
+                "TRANSACTION MESSAGE (Raw)",
+                raw_msg.account_keys.len(),
+                &raw_msg.recent_blockhash,
+            ),
+        };
+
+        let mut message = TableView::key_value(message_title);
+        message
+            .field("Account Keys", account_count)
+            .field("Recent Blockhash", recent_blockhash);
+
+        let accounts = match &ui_tx.message {
+            UiMessage::Parsed(parsed_msg) => {
+                let mut accounts =
+                    TableView::new("ACCOUNT KEYS", ["Index", "Pubkey", "Signer", "Writable"]);
+                for (idx, account) in parsed_msg.account_keys.iter().enumerate() {
+                    accounts.row([
+                        TableCell::new(idx),
+                        TableCell::new(with_label(&account.pubkey, &self.labels)),
+                        TableCell::new(if account.signer { "✓" } else { "" }),
+                        TableCell::new(if account.writable { "✓" } else { "" }),
+                    ]);
+                }
+                accounts
+            }
+            UiMessage::Raw(raw_msg) => {
+                let mut accounts = TableView::new("ACCOUNT KEYS", ["Index", "Pubkey"]);
+                for (idx, key) in raw_msg.account_keys.iter().enumerate() {
+                    accounts.row([
+                        TableCell::new(idx),
+                        TableCell::new(with_label(key, &self.labels)),
+                    ]);
+                }
+                accounts
+            }
+        };
+
+        [details, message, accounts]
+            .into_iter()
+            .filter(|table| !table.is_empty())
+            .collect()
+    }
 }
 
-async fn fetch_transaction(ctx: &ScillaContext, signature: &Signature) -> anyhow::Result<()> {
+async fn fetch_transaction(
+    ctx: &ScillaContext,
+    signature: &Signature,
+) -> anyhow::Result<TransactionOutput> {
     let tx = ctx
         .rpc()
         .get_transaction_with_config(
@@ -235,143 +547,207 @@ async fn fetch_transaction(ctx: &ScillaContext, signature: &Signature) -> anyhow
         )
         .await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Slot"), Cell::new(tx.slot)]);
-
-    if let Some(block_time) = tx.block_time {
-        table.add_row(vec![Cell::new("Block Time"), Cell::new(block_time)]);
-    }
+    let labels = match &tx.transaction.transaction {
+        EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
+            UiMessage::Parsed(parsed_msg) => ctx.address_book().labels(
+                parsed_msg
+                    .account_keys
+                    .iter()
+                    .map(|account| account.pubkey.as_str()),
+            ),
+            UiMessage::Raw(raw_msg) => ctx
+                .address_book()
+                .labels(raw_msg.account_keys.iter().map(String::as_str)),
+        },
+        _ => AddressLabels::new(),
+    };
 
-    if let Some(meta) = &tx.transaction.meta {
-        table.add_row(vec![Cell::new("Fee (lamports)"), Cell::new(meta.fee)]);
-        table.add_row(vec![
-            Cell::new("Status"),
-            Cell::new(if meta.err.is_none() {
-                style("Success").green().to_string()
-            } else {
-                style(format!("Error: {:?}", meta.err)).red().to_string()
-            }),
-        ]);
-    }
+    Ok(TransactionOutput {
+        signature: signature.to_string(),
+        tx,
+        labels,
+    })
+}
 
-    println!("\n{}", style("TRANSACTION DETAILS").green().bold());
-    println!("{table}");
+async fn send_transaction(
+    ctx: &ScillaContext,
+    encoding: UiTransactionEncoding,
+    encoded_tx: &str,
+) -> anyhow::Result<SignatureOutput> {
+    let tx = decode_and_deserialize_transaction(encoding, encoded_tx)?;
 
-    let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
-        anyhow::bail!("Transaction encoding is not JSON");
+    // There is no fire-and-forget over QUIC: the TPU client resends until
+    // the transaction lands
+    let signature = match ctx.transport() {
+        Transport::Rpc => ctx.rpc().send_transaction(&tx).await?,
+        Transport::Tpu => send_and_confirm(ctx, &tx).await?,
     };
 
-    match &ui_tx.message {
-        UiMessage::Parsed(parsed_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE").cyan().bold());
-
-            let mut msg_table = Table::new();
-            msg_table
-                .load_preset(UTF8_FULL)
-                .set_header(vec![
-                    Cell::new("Field")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                    Cell::new("Value")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                ])
-                .add_row(vec![
-                    Cell::new("Account Keys"),
-                    Cell::new(parsed_msg.account_keys.len()),
-                ])
-                .add_row(vec![
-                    Cell::new("Recent Blockhash"),
-                    Cell::new(&parsed_msg.recent_blockhash),
-                ]);
+    Ok(SignatureOutput::new(
+        "Transaction sent successfully!",
+        signature,
+    ))
+}
 
-            println!("{msg_table}");
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationOutput {
+    #[serde(flatten)]
+    pub result: RpcSimulateTransactionResult,
+}
 
-            if !parsed_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
-                let mut accounts_table = Table::new();
-                accounts_table.load_preset(UTF8_FULL).set_header(vec![
-                    Cell::new("Index").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Pubkey").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Signer").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Writable").add_attribute(comfy_table::Attribute::Bold),
-                ]);
+impl CommandOutput for SimulationOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let result = &self.result;
+
+        let mut summary = TableView::key_value("SIMULATION RESULT");
+        summary
+            .field("Status", status_cell(&result.err))
+            .field("Units Consumed", result.units_consumed.unwrap_or_default())
+            .field("Fee (lamports)", result.fee.unwrap_or(0))
+            .field(
+                "Replacement Blockhash",
+                result
+                    .replacement_blockhash
+                    .as_ref()
+                    .map(|b| b.blockhash.clone())
+                    .unwrap_or_else(|| "-".to_string()),
+            )
+            .field(
+                "Loaded Data Size",
+                result.loaded_accounts_data_size.unwrap_or_default(),
+            );
+
+        let mut logs = TableView::new("LOGS", ["Log"]);
+        for log in result.logs.iter().flatten() {
+            logs.row([log]);
+        }
 
-                for (idx, account) in parsed_msg.account_keys.iter().enumerate() {
-                    accounts_table.add_row(vec![
-                        Cell::new(idx),
-                        Cell::new(&account.pubkey),
-                        Cell::new(if account.signer { "✓" } else { "" }),
-                        Cell::new(if account.writable { "✓" } else { "" }),
-                    ]);
-                }
-                println!("{accounts_table}");
-            }
+        let mut return_data = TableView::key_value("RETURN DATA");
+        if let Some(data) = &result.return_data {
+            return_data
+                .field("Program", &data.program_id)
+                .field("Encoding", format!("{:?}", data.data.1))
+                .field("Data", &data.data.0);
         }
-        UiMessage::Raw(raw_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE (Raw)").cyan().bold());
-
-            let mut msg_table = Table::new();
-            msg_table
-                .load_preset(UTF8_FULL)
-                .set_header(vec![
-                    Cell::new("Field")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                    Cell::new("Value")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                ])
-                .add_row(vec![
-                    Cell::new("Account Keys"),
-                    Cell::new(raw_msg.account_keys.len()),
-                ])
-                .add_row(vec![
-                    Cell::new("Recent Blockhash"),
-                    Cell::new(&raw_msg.recent_blockhash),
-                ]);
 
-            println!("{msg_table}");
+        let mut inner_instructions = TableView::new(
+            "INNER INSTRUCTIONS",
+            ["Instruction", "Index", "Inner Instruction"],
+        );
+        for UiInnerInstructions {
+            index,
+            instructions,
+        } in result.inner_instructions.iter().flatten()
+        {
+            for (idx, ix) in instructions.iter().enumerate() {
+                inner_instructions.row([
+                    TableCell::new(index),
+                    TableCell::new(idx),
+                    TableCell::new(format!("{ix:?}")),
+                ]);
+            }
+        }
 
-            if !raw_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
-                for (idx, key) in raw_msg.account_keys.iter().enumerate() {
-                    println!("  {idx}. {key}");
+        let mut balances =
+            TableView::new("BALANCES (lamports)", ["Account", "Pre", "Post", "Delta"]);
+        if let (Some(pre), Some(post)) = (&result.pre_balances, &result.post_balances) {
+            let mut display_idx = 1;
+            for (&pre_amt, &post_amt) in pre.iter().zip(post) {
+                if pre_amt == 1 && post_amt == 1 {
+                    continue;
                 }
+                let delta: i128 = post_amt as i128 - pre_amt as i128;
+                balances.row([
+                    TableCell::new(display_idx),
+                    TableCell::new(pre_amt),
+                    TableCell::new(post_amt),
+                    TableCell::new(if delta > 0 {
+                        format!("+{delta}")
+                    } else {
+                        format!("{delta}")
+                    }),
+                ]);
+                display_idx += 1;
             }
         }
-    }
 
-    Ok(())
-}
-
-async fn send_transaction(
-    ctx: &ScillaContext,
-    encoding: UiTransactionEncoding,
-    encoded_tx: &str,
-) -> anyhow::Result<()> {
-    let tx = decode_and_deserialize_transaction(encoding, encoded_tx)?;
+        let mut accounts = TableView::new(
+            "ACCOUNTS",
+            [
+                "Index",
+                "Pubkey",
+                "Lamports",
+                "Owner",
+                "Executable",
+                "Rent Epoch",
+            ],
+        );
+        for (idx, account_opt) in result.accounts.iter().flatten().enumerate() {
+            if let Some(UiAccount {
+                lamports,
+                owner,
+                executable,
+                rent_epoch,
+                ..
+            }) = account_opt
+            {
+                accounts.row([
+                    TableCell::new(idx),
+                    TableCell::new("-"),
+                    TableCell::new(lamports),
+                    TableCell::new(owner),
+                    TableCell::new(if *executable { "✓" } else { "" }),
+                    TableCell::new(rent_epoch),
+                ]);
+            }
+        }
 
-    let signature = ctx.rpc().send_transaction(&tx).await?;
+        let mut token_balances =
+            TableView::new("TOKEN BALANCES", ["Index", "Mint", "Owner", "Pre", "Post"]);
+        if let (Some(pre_tokens), Some(post_tokens)) =
+            (&result.pre_token_balances, &result.post_token_balances)
+        {
+            for (idx, (pre, post)) in pre_tokens.iter().zip(post_tokens).enumerate() {
+                token_balances.row([
+                    TableCell::new(idx),
+                    TableCell::new(&pre.mint),
+                    TableCell::new(pre.owner.as_ref().map_or("-", String::as_str)),
+                    TableCell::new(&pre.ui_token_amount.ui_amount_string),
+                    TableCell::new(&post.ui_token_amount.ui_amount_string),
+                ]);
+            }
+        }
 
-    println!(
-        "{} {}",
-        style("Transaction sent successfully!").green().bold(),
-        style(signature).cyan()
-    );
+        let mut loaded_addresses = TableView::new("LOADED ADDRESSES", ["Writable", "Readonly"]);
+        if let Some(addresses) = &result.loaded_addresses {
+            let join_or_dash = |keys: &[String]| {
+                if keys.is_empty() {
+                    "-".to_string()
+                } else {
+                    keys.join(", ")
+                }
+            };
+            loaded_addresses.row([
+                join_or_dash(&addresses.writable),
+                join_or_dash(&addresses.readonly),
+            ]);
+        }
 
-    Ok(())
+        [
+            summary,
+            logs,
+            return_data,
+            inner_instructions,
+            balances,
+            accounts,
+            token_balances,
+            loaded_addresses,
+        ]
+        .into_iter()
+        .filter(|table| !table.is_empty())
+        .collect()
+    }
 }
 
 async fn simulate_transaction(
@@ -379,7 +755,7 @@ async fn simulate_transaction(
     encoding: UiTransactionEncoding,
     encoded_tx: &str,
     relaxed: bool,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<SimulationOutput> {
     let tx = decode_and_deserialize_transaction(encoding, encoded_tx)?;
 
     let response = match relaxed {
@@ -410,206 +786,72 @@ async fn simulate_transaction(
                 .await?
         }
     };
-    let value = response.value;
-
-    println!("\n{}", style("SIMULATION RESULT").green().bold());
-
-    let mut summary = Table::new();
-    summary
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Status"),
-            Cell::new(match &value.err {
-                None => style("Success").green().to_string(),
-                Some(err) => style(format!("{err:?}")).red().to_string(),
-            }),
-        ])
-        .add_row(vec![
-            Cell::new("Units Consumed"),
-            Cell::new(value.units_consumed.unwrap_or_default()),
-        ])
-        .add_row(vec![
-            Cell::new("Fee (lamports)"),
-            Cell::new(value.fee.unwrap_or(0)),
-        ])
-        .add_row(vec![
-            Cell::new("Replacement Blockhash"),
-            Cell::new(
-                value
-                    .replacement_blockhash
-                    .as_ref()
-                    .map(|b| b.blockhash.clone())
-                    .unwrap_or_else(|| "-".to_string()),
-            ),
-        ])
-        .add_row(vec![
-            Cell::new("Loaded Data Size"),
-            Cell::new(value.loaded_accounts_data_size.unwrap_or_default()),
-        ]);
-
-    println!("{summary}");
-
-    if let Some(logs) = value.logs
-        && !logs.is_empty()
-    {
-        println!("\n{}", style("LOGS").cyan().bold());
-        for log in logs {
-            println!("  • {log}");
-        }
-    }
 
-    if let Some(return_data) = value.return_data {
-        println!("\n{}", style("RETURN DATA").cyan().bold());
-        println!("  Program: {}", return_data.program_id);
-        println!("  Encoding: {:?}", return_data.data.1);
-        println!("  Data: {}", return_data.data.0);
-    }
+    Ok(SimulationOutput {
+        result: response.value,
+    })
+}
 
-    if let Some(inner) = value.inner_instructions
-        && !inner.is_empty()
-    {
-        println!("\n{}", style("INNER INSTRUCTIONS").cyan().bold());
-        for UiInnerInstructions {
-            index,
-            instructions,
-        } in inner
-        {
-            println!("  At instruction {index}:");
-            for (idx, ix) in instructions.into_iter().enumerate() {
-                println!("    {idx}. {ix:?}");
+/// Asks, for every signer still missing, for its signature or the path of its
+/// keypair. Leaving the answer empty keeps it missing.
+fn prompt_missing_signatures(tx: &mut VersionedTransaction) -> anyhow::Result<()> {
+    for pubkey in missing_signers(tx) {
+        loop {
+            let input: String =
+                prompt_input_data(&format!("Signature or keypair path for {pubkey}:"))?;
+            let input = input.trim();
+            if input.is_empty() {
+                break;
             }
-        }
-    }
 
-    if let (Some(pre), Some(post)) = (value.pre_balances, value.post_balances) {
-        println!("\n{}", style("BALANCES (lamports)").cyan().bold());
-        let mut bal_table = Table::new();
-        bal_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("Account").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Pre").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Post").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Delta").add_attribute(comfy_table::Attribute::Bold),
-        ]);
-        let mut display_idx = 1;
-        let mut has_rows = false;
-        for (pre_amt, post_amt) in pre.into_iter().zip(post.into_iter()) {
-            if pre_amt == 1 && post_amt == 1 {
-                continue;
-            }
-            let delta: i128 = post_amt as i128 - pre_amt as i128;
-            bal_table.add_row(vec![
-                Cell::new(display_idx),
-                Cell::new(pre_amt),
-                Cell::new(post_amt),
-                Cell::new(if delta > 0 {
-                    format!("+{delta}")
-                } else {
-                    format!("{delta}")
+            let added = match input.parse::<Signature>() {
+                Ok(signature) => add_signature(tx, PubkeySignature { pubkey, signature }),
+                Err(_) => load_signer(input).and_then(|signer| {
+                    if signer.pubkey() != pubkey {
+                        bail!("{input} holds {}, not {pubkey}", signer.pubkey());
+                    }
+                    sign_with(tx, signer.as_ref())
                 }),
-            ]);
-            display_idx += 1;
-            has_rows = true;
-        }
-        if has_rows {
-            println!("{bal_table}");
-        }
-    }
-
-    if let Some(accounts) = value.accounts
-        && !accounts.is_empty()
-    {
-        println!("\n{}", style("ACCOUNTS").cyan().bold());
-        let mut accounts_table = Table::new();
-        accounts_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("Index").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Pubkey").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Lamports").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Owner").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Executable").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Rent Epoch").add_attribute(comfy_table::Attribute::Bold),
-        ]);
-
-        for (idx, account_opt) in accounts.into_iter().enumerate() {
-            if let Some(UiAccount {
-                lamports,
-                owner,
-                executable,
-                rent_epoch,
-                ..
-            }) = account_opt
-            {
-                accounts_table.add_row(vec![
-                    Cell::new(idx),
-                    Cell::new("-"),
-                    Cell::new(lamports),
-                    Cell::new(owner),
-                    Cell::new(if executable { "✓" } else { "" }),
-                    Cell::new(rent_epoch),
-                ]);
+            };
+            match added {
+                Ok(()) => break,
+                Err(e) => print_error(format!("{e}. Please try again.")),
             }
         }
-
-        println!("{accounts_table}");
     }
 
-    if let (Some(pre_tokens), Some(post_tokens)) =
-        (value.pre_token_balances, value.post_token_balances)
-    {
-        println!("\n{}", style("TOKEN BALANCES").cyan().bold());
-        let mut tok_table = Table::new();
-        tok_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("Index").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Mint").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Owner").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Pre").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Post").add_attribute(comfy_table::Attribute::Bold),
-        ]);
-
-        for (idx, (pre, post)) in pre_tokens
-            .into_iter()
-            .zip(post_tokens.into_iter())
-            .enumerate()
-        {
-            tok_table.add_row(vec![
-                Cell::new(idx),
-                Cell::new(pre.mint),
-                Cell::new(pre.owner.unwrap_or_else(|| "-".to_string())),
-                Cell::new(pre.ui_token_amount.ui_amount_string),
-                Cell::new(post.ui_token_amount.ui_amount_string),
-            ]);
-        }
+    Ok(())
+}
 
-        println!("{tok_table}");
-    }
+async fn simulate_signed_transaction(
+    ctx: &ScillaContext,
+    tx: &VersionedTransaction,
+) -> anyhow::Result<SimulationOutput> {
+    let response = ctx
+        .rpc()
+        .simulate_transaction_with_config(
+            tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: true,
+                commitment: Some(ctx.rpc().commitment()),
+                ..Default::default()
+            },
+        )
+        .await?;
 
-    if let Some(loaded_addresses) = value.loaded_addresses {
-        println!("\n{}", style("LOADED ADDRESSES").cyan().bold());
-        let mut addr_table = Table::new();
-        addr_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("Writable").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Readonly").add_attribute(comfy_table::Attribute::Bold),
-        ]);
-        let writable = if loaded_addresses.writable.is_empty() {
-            "-".to_string()
-        } else {
-            loaded_addresses.writable.join(", ")
-        };
-        let readonly = if loaded_addresses.readonly.is_empty() {
-            "-".to_string()
-        } else {
-            loaded_addresses.readonly.join(", ")
-        };
-        addr_table.add_row(vec![Cell::new(writable), Cell::new(readonly)]);
-        println!("{addr_table}");
-    }
+    Ok(SimulationOutput {
+        result: response.value,
+    })
+}
 
-    Ok(())
+async fn submit_transaction(
+    ctx: &ScillaContext,
+    tx: &VersionedTransaction,
+) -> anyhow::Result<SignatureOutput> {
+    let signature = send_and_confirm(ctx, tx).await?;
+
+    Ok(SignatureOutput::new(
+        "Transaction submitted successfully!",
+        signature,
+    ))
 }
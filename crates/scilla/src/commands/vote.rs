@@ -1,20 +1,20 @@
 use {
     crate::{
         ScillaContext,
-        commands::{
-            Command, CommandFlow,
-            navigation::{NavigationSection, NavigationTarget},
-        },
+        commands::{Command, CommandFlow, CommandFuture, navigation::Menu},
         misc::helpers::{
             Commission, SolAmount, build_and_send_tx, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path,
+            read_signer,
         },
-        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path},
-        ui::show_spinner,
+        output::{CommandOutput, SignatureOutput, TableView},
+        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path, prompt_pubkey},
+        ui::render_with_spinner,
     },
     anyhow::{anyhow, bail},
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    clap::Subcommand,
     console::style,
+    serde::Serialize,
+    solana_account::Account,
     solana_keypair::Signer,
     solana_pubkey::Pubkey,
     solana_rpc_client_api::config::RpcGetVoteAccountsConfig,
@@ -33,20 +33,6 @@ pub enum VoteCommand {
     WithdrawFromVoteAccount,
     ShowVoteAccount,
     CloseVoteAccount,
-    GoBack,
-}
-
-impl VoteCommand {
-    pub fn spinner_msg(&self) -> &'static str {
-        match self {
-            VoteCommand::CreateVoteAccount => "Creating vote account…",
-            VoteCommand::AuthorizeVoter => "Authorizing voter…",
-            VoteCommand::WithdrawFromVoteAccount => "Withdrawing SOL from vote account…",
-            VoteCommand::ShowVoteAccount => "Fetching vote account details…",
-            VoteCommand::CloseVoteAccount => "Closing vote account…",
-            VoteCommand::GoBack => "Going back…",
-        }
-    }
 }
 
 impl fmt::Display for VoteCommand {
@@ -57,29 +43,159 @@ impl fmt::Display for VoteCommand {
             VoteCommand::WithdrawFromVoteAccount => "Withdraw from vote account",
             VoteCommand::ShowVoteAccount => "Show vote account",
             VoteCommand::CloseVoteAccount => "Close vote account",
-            VoteCommand::GoBack => "Go back",
         };
         write!(f, "{text}")
     }
 }
 
 impl Command for VoteCommand {
-    async fn process_command(&self, ctx: &mut ScillaContext) -> anyhow::Result<CommandFlow> {
-        ctx.get_nav_context_mut()
-            .checked_push(NavigationSection::Vote);
+    fn spinner_msg(&self) -> &'static str {
         match self {
-            VoteCommand::CreateVoteAccount => {
-                let vote_account_keypair_path =
-                    prompt_keypair_path("Enter Vote Account Keypair Path:", ctx);
-                let identity_keypair_path =
-                    prompt_keypair_path("Enter Identity Keypair Path:", ctx);
-                let withdraw_keypair_path =
-                    prompt_keypair_path("Enter Withdraw Keypair Path:", ctx);
-                let commission: Commission =
-                    prompt_input_data("Enter Commission 0-100 (default 0):");
-
-                show_spinner(
-                    self.spinner_msg(),
+            VoteCommand::CreateVoteAccount => "Creating vote account…",
+            VoteCommand::AuthorizeVoter => "Authorizing voter…",
+            VoteCommand::WithdrawFromVoteAccount => "Withdrawing SOL from vote account…",
+            VoteCommand::ShowVoteAccount => "Fetching vote account details…",
+            VoteCommand::CloseVoteAccount => "Closing vote account…",
+        }
+    }
+
+    fn process_command<'a>(&'a self, ctx: &'a mut ScillaContext) -> CommandFuture<'a> {
+        let args = match self {
+            VoteCommand::CreateVoteAccount => VoteArgs::Create {
+                vote_account_keypair: None,
+                identity_keypair: None,
+                withdraw_keypair: None,
+                commission: None,
+            },
+            VoteCommand::AuthorizeVoter => VoteArgs::AuthorizeVoter {
+                vote_account: None,
+                authorized_keypair: None,
+                new_authorized: None,
+            },
+            VoteCommand::WithdrawFromVoteAccount => VoteArgs::Withdraw {
+                vote_account: None,
+                authorized_withdrawer_keypair: None,
+                recipient: None,
+                amount: None,
+            },
+            VoteCommand::ShowVoteAccount => VoteArgs::Show { vote_account: None },
+            VoteCommand::CloseVoteAccount => VoteArgs::Close {
+                vote_account: None,
+                withdraw_authority_keypair: None,
+                destination: None,
+                yes: false,
+            },
+        };
+
+        Box::pin(async move { CommandFlow::from_run(args.run(ctx).await) })
+    }
+}
+
+/// The Vote menu, in display order.
+pub fn menu() -> Menu {
+    Menu::new("Vote").commands([
+        VoteCommand::CreateVoteAccount,
+        VoteCommand::AuthorizeVoter,
+        VoteCommand::WithdrawFromVoteAccount,
+        VoteCommand::ShowVoteAccount,
+        VoteCommand::CloseVoteAccount,
+    ])
+}
+
+/// Non-interactive arguments for [`VoteCommand`]. Anything left out is
+/// prompted for interactively.
+#[derive(Debug, Clone, Subcommand)]
+pub enum VoteArgs {
+    /// Create a new vote account
+    Create {
+        #[arg(long)]
+        vote_account_keypair: Option<PathBuf>,
+        #[arg(long)]
+        identity_keypair: Option<PathBuf>,
+        #[arg(long)]
+        withdraw_keypair: Option<PathBuf>,
+        /// Commission percentage (0-100)
+        #[arg(long)]
+        commission: Option<Commission>,
+    },
+    /// Change the authorized voter of a vote account
+    AuthorizeVoter {
+        #[arg(long)]
+        vote_account: Option<Pubkey>,
+        /// Current authorized voter or withdrawer keypair
+        #[arg(long)]
+        authorized_keypair: Option<PathBuf>,
+        #[arg(long)]
+        new_authorized: Option<Pubkey>,
+    },
+    /// Withdraw SOL from a vote account
+    Withdraw {
+        #[arg(long)]
+        vote_account: Option<Pubkey>,
+        #[arg(long)]
+        authorized_withdrawer_keypair: Option<PathBuf>,
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        /// Amount to withdraw in SOL
+        #[arg(long)]
+        amount: Option<SolAmount>,
+    },
+    /// Show vote account details
+    Show { vote_account: Option<Pubkey> },
+    /// Close a vote account and reclaim its balance
+    Close {
+        #[arg(long)]
+        vote_account: Option<Pubkey>,
+        #[arg(long)]
+        withdraw_authority_keypair: Option<PathBuf>,
+        #[arg(long)]
+        destination: Option<Pubkey>,
+        /// Skip the confirmation prompt
+        #[arg(long, short)]
+        yes: bool,
+    },
+}
+
+impl VoteArgs {
+    pub fn command(&self) -> VoteCommand {
+        match self {
+            VoteArgs::Create { .. } => VoteCommand::CreateVoteAccount,
+            VoteArgs::AuthorizeVoter { .. } => VoteCommand::AuthorizeVoter,
+            VoteArgs::Withdraw { .. } => VoteCommand::WithdrawFromVoteAccount,
+            VoteArgs::Show { .. } => VoteCommand::ShowVoteAccount,
+            VoteArgs::Close { .. } => VoteCommand::CloseVoteAccount,
+        }
+    }
+
+    pub async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        let spinner_msg = self.command().spinner_msg();
+        match self {
+            VoteArgs::Create {
+                vote_account_keypair,
+                identity_keypair,
+                withdraw_keypair,
+                commission,
+            } => {
+                let vote_account_keypair_path = vote_account_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Vote Account Keypair Path:", ctx),
+                    Ok,
+                )?;
+                let identity_keypair_path = identity_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Identity Keypair Path:", ctx),
+                    Ok,
+                )?;
+                let withdraw_keypair_path = withdraw_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Withdraw Keypair Path:", ctx),
+                    Ok,
+                )?;
+                let commission = commission.map_or_else(
+                    || prompt_input_data("Enter Commission 0-100 (default 0):"),
+                    Ok,
+                )?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     process_create_vote_account(
                         ctx,
                         &vote_account_keypair_path,
@@ -88,17 +204,25 @@ impl Command for VoteCommand {
                         commission.value(),
                     ),
                 )
-                .await;
+                .await?;
             }
-            VoteCommand::AuthorizeVoter => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                let authorized_keypair_path =
-                    prompt_keypair_path("Enter Authorized Keypair Path:", ctx);
-                let new_authorized_pubkey: Pubkey =
-                    prompt_input_data("Enter New Authorized Address:");
-
-                show_spinner(
-                    self.spinner_msg(),
+            VoteArgs::AuthorizeVoter {
+                vote_account,
+                authorized_keypair,
+                new_authorized,
+            } => {
+                let vote_account_pubkey = vote_account
+                    .map_or_else(|| prompt_pubkey("Enter Vote Account Address:", ctx), Ok)?;
+                let authorized_keypair_path = authorized_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Authorized Keypair Path:", ctx),
+                    Ok,
+                )?;
+                let new_authorized_pubkey = new_authorized
+                    .map_or_else(|| prompt_pubkey("Enter New Authorized Address:", ctx), Ok)?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     process_authorize_voter(
                         ctx,
                         &vote_account_pubkey,
@@ -106,18 +230,29 @@ impl Command for VoteCommand {
                         &new_authorized_pubkey,
                     ),
                 )
-                .await;
+                .await?;
             }
-            VoteCommand::WithdrawFromVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                let authorized_withdrawer_keypair_path =
-                    prompt_keypair_path("Enter Authorized Withdraw Keypair Path:", ctx);
-                let recipient_address: Pubkey = prompt_input_data("Enter Recipient Address:");
-
-                let amount: SolAmount = prompt_input_data("Enter withdraw amount in SOL:");
-
-                show_spinner(
-                    self.spinner_msg(),
+            VoteArgs::Withdraw {
+                vote_account,
+                authorized_withdrawer_keypair,
+                recipient,
+                amount,
+            } => {
+                let vote_account_pubkey = vote_account
+                    .map_or_else(|| prompt_pubkey("Enter Vote Account Address:", ctx), Ok)?;
+                let authorized_withdrawer_keypair_path = authorized_withdrawer_keypair
+                    .map_or_else(
+                        || prompt_keypair_path("Enter Authorized Withdraw Keypair Path:", ctx),
+                        Ok,
+                    )?;
+                let recipient_address =
+                    recipient.map_or_else(|| prompt_pubkey("Enter Recipient Address:", ctx), Ok)?;
+                let amount = amount
+                    .map_or_else(|| prompt_input_data("Enter withdraw amount in SOL:"), Ok)?;
+
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     process_sol_withdraw_from_vote_account(
                         ctx,
                         &vote_account_pubkey,
@@ -126,29 +261,43 @@ impl Command for VoteCommand {
                         amount.to_lamports(),
                     ),
                 )
-                .await;
+                .await?;
             }
-            VoteCommand::ShowVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                show_spinner(
-                    self.spinner_msg(),
+            VoteArgs::Show { vote_account } => {
+                let vote_account_pubkey = vote_account
+                    .map_or_else(|| prompt_pubkey("Enter Vote Account Address:", ctx), Ok)?;
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     process_fetch_vote_account(ctx, &vote_account_pubkey),
                 )
-                .await;
+                .await?;
             }
-            VoteCommand::CloseVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                let withdraw_authority_keypair_path =
-                    prompt_keypair_path("Enter Withdraw Authority Keypair Path:", ctx);
-                let destination_pubkey: Pubkey = prompt_input_data("Enter Destination Address:");
-
-                if !prompt_confirmation("Are you sure you want to close this vote account?") {
+            VoteArgs::Close {
+                vote_account,
+                withdraw_authority_keypair,
+                destination,
+                yes,
+            } => {
+                let vote_account_pubkey = vote_account
+                    .map_or_else(|| prompt_pubkey("Enter Vote Account Address:", ctx), Ok)?;
+                let withdraw_authority_keypair_path = withdraw_authority_keypair.map_or_else(
+                    || prompt_keypair_path("Enter Withdraw Authority Keypair Path:", ctx),
+                    Ok,
+                )?;
+                let destination_pubkey = destination
+                    .map_or_else(|| prompt_pubkey("Enter Destination Address:", ctx), Ok)?;
+
+                if !yes
+                    && !prompt_confirmation("Are you sure you want to close this vote account?")?
+                {
                     println!("{}", style("Close vote account cancelled.").yellow());
-                    return Ok(CommandFlow::Processed);
+                    return Ok(());
                 }
 
-                show_spinner(
-                    self.spinner_msg(),
+                render_with_spinner(
+                    ctx,
+                    spinner_msg,
                     close_vote_account(
                         ctx,
                         &vote_account_pubkey,
@@ -156,14 +305,33 @@ impl Command for VoteCommand {
                         &destination_pubkey,
                     ),
                 )
-                .await;
-            }
-            VoteCommand::GoBack => {
-                return Ok(CommandFlow::NavigateTo(NavigationTarget::PreviousSection));
+                .await?;
             }
         }
 
-        Ok(CommandFlow::Processed)
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVoteAccountOutput {
+    pub vote_account: String,
+    pub signature: String,
+}
+
+impl CommandOutput for CreateVoteAccountOutput {
+    fn render_table(&self) {
+        println!(
+            "{} {}",
+            style("Vote account created successfully!").green().bold(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+        println!(
+            "{} {}",
+            style("Vote account address:").green(),
+            style(&self.vote_account).cyan()
+        );
     }
 }
 
@@ -173,10 +341,10 @@ async fn process_create_vote_account(
     identity_keypair_path: &PathBuf,
     withdraw_keypair_path: &PathBuf,
     commission: u8,
-) -> anyhow::Result<()> {
-    let vote_account_keypair = read_keypair_from_path(vote_account_keypair_path)?;
-    let identity_keypair = read_keypair_from_path(identity_keypair_path)?;
-    let withdraw_keypair = read_keypair_from_path(withdraw_keypair_path)?;
+) -> anyhow::Result<CreateVoteAccountOutput> {
+    let vote_account_keypair = read_signer(ctx, vote_account_keypair_path)?;
+    let identity_keypair = read_signer(ctx, identity_keypair_path)?;
+    let withdraw_keypair = read_signer(ctx, withdraw_keypair_path)?;
     let vote_account_pubkey = vote_account_keypair.pubkey();
     let identity_pubkey = identity_keypair.pubkey();
     let withdrawer_pubkey = withdraw_keypair.pubkey();
@@ -231,18 +399,10 @@ async fn process_create_vote_account(
     )
     .await?;
 
-    println!(
-        "{} {}",
-        style("Vote account created successfully!").green().bold(),
-        style(format!("Signature: {signature}")).cyan()
-    );
-    println!(
-        "{} {}",
-        style("Vote account address:").green(),
-        style(vote_account_pubkey).cyan()
-    );
-
-    Ok(())
+    Ok(CreateVoteAccountOutput {
+        vote_account: vote_account_pubkey.to_string(),
+        signature: signature.to_string(),
+    })
 }
 
 async fn process_authorize_voter(
@@ -250,8 +410,8 @@ async fn process_authorize_voter(
     vote_account_pubkey: &Pubkey,
     authorized_keypair_path: &PathBuf,
     new_authorized_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
-    let authorized = read_keypair_from_path(authorized_keypair_path)?;
+) -> anyhow::Result<SignatureOutput> {
+    let authorized = read_signer(ctx, authorized_keypair_path)?;
     let authorized_pubkey = authorized.pubkey();
 
     let (vote_account, epoch_info) = fetch_account_with_epoch(ctx, vote_account_pubkey).await?;
@@ -290,13 +450,7 @@ async fn process_authorize_voter(
 
     let signature = build_and_send_tx(ctx, &[vote_ix], &[ctx.keypair(), &authorized]).await?;
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
-    );
-
-    Ok(())
+    Ok(SignatureOutput::new("Signature:", signature))
 }
 
 async fn process_sol_withdraw_from_vote_account(
@@ -305,8 +459,8 @@ async fn process_sol_withdraw_from_vote_account(
     authorized_withdrawer_keypair_path: &PathBuf,
     recipient_address: &Pubkey,
     amount: u64,
-) -> anyhow::Result<()> {
-    let authorized_withdrawer = read_keypair_from_path(authorized_withdrawer_keypair_path)?;
+) -> anyhow::Result<SignatureOutput> {
+    let authorized_withdrawer = read_signer(ctx, authorized_withdrawer_keypair_path)?;
     let withdrawer_pubkey = authorized_withdrawer.pubkey();
 
     let vote_account = ctx
@@ -344,13 +498,7 @@ async fn process_sol_withdraw_from_vote_account(
     )
     .await?;
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
-    );
-
-    Ok(())
+    Ok(SignatureOutput::new("Signature:", signature))
 }
 
 async fn close_vote_account(
@@ -358,8 +506,8 @@ async fn close_vote_account(
     vote_account_pubkey: &Pubkey,
     withdraw_authority_keypair_path: &PathBuf,
     destination_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
-    let withdraw_authority = read_keypair_from_path(withdraw_authority_keypair_path)?;
+) -> anyhow::Result<SignatureOutput> {
+    let withdraw_authority = read_signer(ctx, withdraw_authority_keypair_path)?;
     let vote_account_status = ctx
         .rpc()
         .get_vote_accounts_with_config(RpcGetVoteAccountsConfig {
@@ -394,19 +542,64 @@ async fn close_vote_account(
     let signature =
         build_and_send_tx(ctx, &[withdraw_ix], &[ctx.keypair(), &withdraw_authority]).await?;
 
-    println!(
-        "{} {}",
-        style("Vote account closed! Signature:").green().bold(),
-        style(signature).cyan()
-    );
+    Ok(SignatureOutput::new(
+        "Vote account closed! Signature:",
+        signature,
+    ))
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteAccountOutput {
+    pub vote_account: String,
+    pub lamports: u64,
+    pub validator_identity: String,
+    pub vote_authority: String,
+    pub withdraw_authority: String,
+    pub credits: u64,
+    pub commission_bps: u16,
+    pub root_slot: Option<u64>,
+    pub last_timestamp: i64,
+    pub last_timestamp_slot: u64,
+}
+
+impl CommandOutput for VoteAccountOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let root_slot = match self.root_slot {
+            Some(slot) => slot.to_string(),
+            None => "~".to_string(),
+        };
+
+        let timestamp = chrono::DateTime::from_timestamp(self.last_timestamp, 0)
+            .expect("Solana timestamp should always be in valid range")
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+
+        let mut table = TableView::key_value("VOTE ACCOUNT INFORMATION");
+        table
+            .field(
+                "Account Balance",
+                format!("{} SOL", lamports_to_sol(self.lamports)),
+            )
+            .field("Validator Identity", &self.validator_identity)
+            .field("Vote Authority", &self.vote_authority)
+            .field("Withdraw Authority", &self.withdraw_authority)
+            .field("Credits", self.credits)
+            .field("Commission", format!("{}%", self.commission_bps / 100))
+            .field("Root Slot", root_slot)
+            .field(
+                "Recent Timestamp",
+                format!("{} from slot {}", timestamp, self.last_timestamp_slot),
+            );
+
+        vec![table]
+    }
 }
 
 async fn process_fetch_vote_account(
     ctx: &ScillaContext,
     vote_account_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<VoteAccountOutput> {
     let vote_account = ctx
         .rpc()
         .get_account(vote_account_pubkey)
@@ -417,70 +610,34 @@ async fn process_fetch_vote_account(
         bail!("{vote_account_pubkey} is not a vote account");
     }
 
+    decode_vote_account(vote_account_pubkey, &vote_account)
+}
+
+/// Decodes the vote state of an account already known to be owned by the
+/// vote program.
+pub(crate) fn decode_vote_account(
+    vote_account_pubkey: &Pubkey,
+    vote_account: &Account,
+) -> anyhow::Result<VoteAccountOutput> {
     let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
         .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
 
-    let balance_sol = lamports_to_sol(vote_account.lamports);
-
-    let root_slot = match vote_state.root_slot {
-        Some(slot) => slot.to_string(),
-        None => "~".to_string(),
-    };
-
-    let timestamp = chrono::DateTime::from_timestamp(vote_state.last_timestamp.timestamp, 0)
-        .expect("Solana timestamp should always be in valid range")
-        .format("%Y-%m-%dT%H:%M:%SZ")
-        .to_string();
-
     let vote_authority = vote_state
         .authorized_voters
         .last()
         .map(|(_, v)| v.to_string())
         .unwrap_or_else(|| vote_state.node_pubkey.to_string());
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance"),
-            Cell::new(format!("{balance_sol} SOL")),
-        ])
-        .add_row(vec![
-            Cell::new("Validator Identity"),
-            Cell::new(vote_state.node_pubkey),
-        ])
-        .add_row(vec![Cell::new("Vote Authority"), Cell::new(vote_authority)])
-        .add_row(vec![
-            Cell::new("Withdraw Authority"),
-            Cell::new(vote_state.authorized_withdrawer),
-        ])
-        .add_row(vec![Cell::new("Credits"), Cell::new(vote_state.credits())])
-        .add_row(vec![
-            Cell::new("Commission"),
-            Cell::new(format!(
-                "{}%",
-                vote_state.inflation_rewards_commission_bps / 100
-            )),
-        ])
-        .add_row(vec![Cell::new("Root Slot"), Cell::new(root_slot)])
-        .add_row(vec![
-            Cell::new("Recent Timestamp"),
-            Cell::new(format!(
-                "{} from slot {}",
-                timestamp, vote_state.last_timestamp.slot
-            )),
-        ]);
-
-    println!("\n{}", style("VOTE ACCOUNT INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(VoteAccountOutput {
+        vote_account: vote_account_pubkey.to_string(),
+        lamports: vote_account.lamports,
+        validator_identity: vote_state.node_pubkey.to_string(),
+        vote_authority,
+        withdraw_authority: vote_state.authorized_withdrawer.to_string(),
+        credits: vote_state.credits(),
+        commission_bps: vote_state.inflation_rewards_commission_bps,
+        root_slot: vote_state.root_slot,
+        last_timestamp: vote_state.last_timestamp.timestamp,
+        last_timestamp_slot: vote_state.last_timestamp.slot,
+    })
 }
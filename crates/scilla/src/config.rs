@@ -1,13 +1,18 @@
 use {
     crate::{
         commands::config::generate_config,
-        constants::{DEFAULT_KEYPAIR_PATH, DEVNET_RPC, SCILLA_CONFIG_RELATIVE_PATH},
+        constants::{
+            DEFAULT_KEYPAIR_PATH, DEFAULT_PRIORITY_FEE_PERCENTILE, DEFAULT_PROFILE, DEVNET_RPC,
+            SCILLA_CONFIG_RELATIVE_PATH,
+        },
         error::ScillaError,
+        history::HistoryKind,
     },
+    clap::ValueEnum,
     console::style,
     serde::{Deserialize, Serialize},
     solana_commitment_config::CommitmentLevel,
-    std::{env::home_dir, fs, path::PathBuf},
+    std::{collections::BTreeMap, env::home_dir, fmt, fs, path::PathBuf},
 };
 
 pub fn scilla_config_path() -> PathBuf {
@@ -34,6 +39,9 @@ where
     Ok(expand_tilde(&s))
 }
 
+/// The top-level `rpc-url`, `commitment-level` and `keypair-path` form the
+/// `default` profile. Additional profiles live under `[profiles.<name>]` and
+/// `active-profile` selects which one is used at startup.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct ScillaConfig {
@@ -41,6 +49,127 @@ pub struct ScillaConfig {
     pub commitment_level: CommitmentLevel,
     #[serde(deserialize_with = "deserialize_path_with_tilde")]
     pub keypair_path: PathBuf,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_preview: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "PriorityFeeConfig::is_default")]
+    pub priority_fee: PriorityFeeConfig,
+    #[serde(default, skip_serializing_if = "Transport::is_rpc")]
+    pub transport: Transport,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ScillaProfile>,
+}
+
+/// Privacy settings for the prompt history kept in
+/// `~/.config/scilla/history.toml`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    /// Prompt kinds that are never remembered, e.g. `["keypair-path"]`
+    pub exclude: Vec<HistoryKind>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl HistoryConfig {
+    pub fn records(&self, kind: HistoryKind) -> bool {
+        self.enabled && !self.exclude.contains(&kind)
+    }
+}
+
+/// Compute budget instructions added to every transaction Scilla sends.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct PriorityFeeConfig {
+    pub enabled: bool,
+    /// Percentile of the recent prioritization fees paid on the transaction's
+    /// writable accounts, from 0 to 100
+    pub percentile: u8,
+    /// Fixed price in micro-lamports per compute unit, used instead of the
+    /// percentile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_unit_price: Option<u64>,
+    /// Upper bound on the price in micro-lamports per compute unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_compute_unit_price: Option<u64>,
+    /// Fixed compute unit limit, used instead of simulating each transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_unit_limit: Option<u32>,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            percentile: DEFAULT_PRIORITY_FEE_PERCENTILE,
+            compute_unit_price: None,
+            max_compute_unit_price: None,
+            compute_unit_limit: None,
+        }
+    }
+}
+
+impl PriorityFeeConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How signed transactions reach the cluster. Confirmation always goes
+/// through the RPC.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transport {
+    /// `sendTransaction` on the configured RPC
+    #[default]
+    Rpc,
+    /// QUIC straight to the TPU of the upcoming leaders, looked up through
+    /// the configured RPC
+    Tpu,
+}
+
+impl Transport {
+    fn is_rpc(&self) -> bool {
+        *self == Transport::Rpc
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Transport::Rpc => "RPC",
+            Transport::Tpu => "TPU (QUIC)",
+        })
+    }
+}
+
+/// Cluster and keypair settings for a single named profile.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScillaProfile {
+    pub rpc_url: String,
+    pub commitment_level: CommitmentLevel,
+    #[serde(deserialize_with = "deserialize_path_with_tilde")]
+    pub keypair_path: PathBuf,
+    /// Send transactions without the preview and confirmation step, e.g. on
+    /// devnet or a local validator
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_preview: bool,
+    #[serde(default, skip_serializing_if = "PriorityFeeConfig::is_default")]
+    pub priority_fee: PriorityFeeConfig,
+    #[serde(default, skip_serializing_if = "Transport::is_rpc")]
+    pub transport: Transport,
 }
 
 impl Default for ScillaConfig {
@@ -53,6 +182,12 @@ impl Default for ScillaConfig {
             rpc_url: DEVNET_RPC.to_string(),
             commitment_level: CommitmentLevel::Confirmed,
             keypair_path: default_keypair_path,
+            skip_preview: false,
+            active_profile: None,
+            priority_fee: PriorityFeeConfig::default(),
+            transport: Transport::default(),
+            history: HistoryConfig::default(),
+            profiles: BTreeMap::new(),
         }
     }
 }
@@ -86,7 +221,8 @@ impl ScillaConfig {
             );
         }
 
-        println!(
+        // Status goes to stderr so machine-readable output on stdout stays clean
+        eprintln!(
             "{}",
             style(format!("Using Scilla config path : {scilla_config_path:?}")).dim()
         );
@@ -103,6 +239,73 @@ impl ScillaConfig {
         let config: ScillaConfig = toml::from_str(&data)?;
         Ok(config)
     }
+
+    /// Writes the config back to the Scilla config path.
+    pub fn save(&self) -> Result<(), ScillaError> {
+        let config_path = scilla_config_path();
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&config_path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Name of the profile selected by `active-profile`, or `default` when
+    /// none is set.
+    pub fn active_profile_name(&self) -> &str {
+        self.active_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// All profile names, starting with `default`.
+    pub fn profile_names(&self) -> Vec<String> {
+        std::iter::once(DEFAULT_PROFILE.to_string())
+            .chain(
+                self.profiles
+                    .keys()
+                    .filter(|name| name.as_str() != DEFAULT_PROFILE)
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    pub fn profile(&self, name: &str) -> Result<ScillaProfile, ScillaError> {
+        if name == DEFAULT_PROFILE {
+            return Ok(ScillaProfile {
+                rpc_url: self.rpc_url.clone(),
+                commitment_level: self.commitment_level,
+                keypair_path: self.keypair_path.clone(),
+                skip_preview: self.skip_preview,
+                priority_fee: self.priority_fee.clone(),
+                transport: self.transport,
+            });
+        }
+
+        self.profiles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ScillaError::UnknownProfile(name.to_string()))
+    }
+
+    pub fn set_profile(&mut self, name: &str, profile: ScillaProfile) {
+        if name == DEFAULT_PROFILE {
+            self.rpc_url = profile.rpc_url;
+            self.commitment_level = profile.commitment_level;
+            self.keypair_path = profile.keypair_path;
+            self.skip_preview = profile.skip_preview;
+            self.priority_fee = profile.priority_fee;
+            self.transport = profile.transport;
+        } else {
+            self.profiles.insert(name.to_string(), profile);
+        }
+    }
+
+    /// Marks `name` as the profile to use on the next launch.
+    pub fn set_active_profile(&mut self, name: &str) -> Result<(), ScillaError> {
+        self.profile(name)?;
+        self.active_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -150,4 +353,142 @@ commitment-level = "confirmed"
         assert_eq!(config.commitment_level, CommitmentLevel::Confirmed);
         assert_eq!(config.keypair_path, home.join("my/key.json"));
     }
+
+    #[test]
+    fn test_load_from_path_with_profiles() {
+        let home = env::home_dir().expect("HOME should be set");
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "~/devnet.json"
+commitment-level = "confirmed"
+active-profile = "mainnet"
+
+[profiles.mainnet]
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "~/mainnet.json"
+commitment-level = "finalized"
+
+[profiles.mainnet.priority-fee]
+enabled = true
+percentile = 90
+max-compute-unit-price = 50000
+
+[profiles.local]
+rpc-url = "http://127.0.0.1:8899"
+keypair-path = "/tmp/local.json"
+commitment-level = "processed"
+skip-preview = true
+transport = "tpu"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(config.history, HistoryConfig::default());
+        assert_eq!(config.active_profile_name(), "mainnet");
+        assert_eq!(config.profile_names(), ["default", "local", "mainnet"]);
+
+        let mainnet = config.profile("mainnet").expect("mainnet profile exists");
+        assert_eq!(mainnet.rpc_url, "https://api.mainnet-beta.solana.com");
+        assert_eq!(mainnet.commitment_level, CommitmentLevel::Finalized);
+        assert_eq!(mainnet.keypair_path, home.join("mainnet.json"));
+        assert!(!mainnet.skip_preview);
+        assert_eq!(
+            mainnet.priority_fee,
+            PriorityFeeConfig {
+                enabled: true,
+                percentile: 90,
+                compute_unit_price: None,
+                max_compute_unit_price: Some(50_000),
+                compute_unit_limit: None,
+            }
+        );
+        let local = config.profile("local").expect("local profile exists");
+        assert!(local.skip_preview);
+        assert_eq!(local.transport, Transport::Tpu);
+        assert_eq!(mainnet.transport, Transport::Rpc);
+
+        let default = config
+            .profile(DEFAULT_PROFILE)
+            .expect("default profile exists");
+        assert_eq!(default.rpc_url, "https://api.devnet.solana.com");
+        assert_eq!(default.keypair_path, home.join("devnet.json"));
+        assert_eq!(default.priority_fee, PriorityFeeConfig::default());
+
+        assert!(matches!(
+            config.profile("testnet"),
+            Err(ScillaError::UnknownProfile(name)) if name == "testnet"
+        ));
+    }
+
+    #[test]
+    fn test_load_from_path_with_history_settings() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/id.json"
+commitment-level = "confirmed"
+
+[history]
+exclude = ["keypair-path", "signature"]
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert!(config.history.enabled);
+        assert!(config.history.records(HistoryKind::Pubkey));
+        assert!(!config.history.records(HistoryKind::KeypairPath));
+        assert!(!config.history.records(HistoryKind::Signature));
+    }
+
+    #[test]
+    fn test_set_active_profile() {
+        let mut config = ScillaConfig::default();
+        let local = ScillaProfile {
+            rpc_url: "http://127.0.0.1:8899".to_string(),
+            commitment_level: CommitmentLevel::Processed,
+            keypair_path: PathBuf::from("/tmp/local.json"),
+            skip_preview: true,
+            priority_fee: PriorityFeeConfig {
+                enabled: true,
+                compute_unit_price: Some(1_000),
+                ..Default::default()
+            },
+            transport: Transport::Tpu,
+        };
+        config.set_profile("local", local.clone());
+
+        config
+            .set_active_profile("local")
+            .expect("local profile exists");
+        assert_eq!(config.active_profile.as_deref(), Some("local"));
+        assert_eq!(config.profile(config.active_profile_name()).unwrap(), local);
+
+        assert!(config.set_active_profile("mainnet").is_err());
+        assert_eq!(config.active_profile.as_deref(), Some("local"));
+
+        config
+            .set_active_profile(DEFAULT_PROFILE)
+            .expect("default profile always exists");
+        assert_eq!(config.active_profile, None);
+
+        let toml_string = toml::to_string_pretty(&config).expect("config serializes");
+        let reloaded: ScillaConfig = toml::from_str(&toml_string).expect("config round-trips");
+        assert_eq!(reloaded.profile("local").unwrap(), local);
+    }
 }
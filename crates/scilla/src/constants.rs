@@ -2,8 +2,14 @@ pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 
 pub const SCILLA_CONFIG_RELATIVE_PATH: &str = ".config/scilla.toml";
 
+pub const ADDRESS_BOOK_RELATIVE_PATH: &str = ".config/scilla/addresses.toml";
+
+pub const HISTORY_RELATIVE_PATH: &str = ".config/scilla/history.toml";
+
 pub const DEFAULT_KEYPAIR_PATH: &str = ".config/solana/id.json";
 
+pub const DEFAULT_PROFILE: &str = "default";
+
 pub const ACTIVE_STAKE_EPOCH_BOUND: u64 = u64::MAX;
 
 pub const DEVNET_RPC: &str = "https://api.devnet.solana.com";
@@ -17,3 +23,11 @@ pub const DEFAULT_EPOCH_LIMIT: usize = 10;
 pub const STAKE_HISTORY_SYSVAR_ADDR: &str = "SysvarStakeHistory1111111111111111111111111";
 
 pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+pub const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 75;
+
+/// The most compute units a single transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Headroom added on top of the simulated compute units, in percent.
+pub const COMPUTE_UNIT_LIMIT_MARGIN: u64 = 10;
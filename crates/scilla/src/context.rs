@@ -1,22 +1,55 @@
 use {
-    crate::{commands::navigation::NavContext, config::ScillaConfig},
-    anyhow::anyhow,
+    crate::{
+        address_book::{AddressBook, address_book_path},
+        cli::PriorityFeeArgs,
+        commands::navigation::NavContext,
+        config::{PriorityFeeConfig, ScillaConfig, ScillaProfile, Transport},
+        misc::{
+            helpers::is_world_readable, nonce::DurableNonce, signer::SignerSource, tpu::TpuSender,
+        },
+        output::OutputFormat,
+    },
+    console::style,
     solana_commitment_config::CommitmentConfig,
-    solana_keypair::{EncodableKey, Keypair, Signer},
+    solana_hash::Hash,
     solana_pubkey::Pubkey,
     solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_signer::{Signer, null_signer::NullSigner},
     std::path::PathBuf,
+    tokio::sync::OnceCell,
 };
 
+/// Settings passed on the command line for this run. They win over the
+/// profile's, including the profiles the session switches to.
+#[derive(Debug, Clone, Default)]
+pub struct CliOverrides {
+    pub skip_preview: bool,
+    pub transport: Option<Transport>,
+    pub priority_fee: PriorityFeeArgs,
+}
+
 pub struct ScillaContext {
     rpc_client: RpcClient,
-    keypair: Keypair,
+    keypair: Box<dyn Signer>,
     pubkey: Pubkey, // Cache the pubkey to avoid repeated stack allocations
     keypair_path: PathBuf,
+    profile: String,
+    preview_transactions: bool,
+    priority_fee: PriorityFeeConfig,
+    transport: Transport,
+    dry_run: bool,
+    sign_only: bool,
+    blockhash: Option<Hash>,
+    durable_nonce: Option<DurableNonce>,
+    overrides: CliOverrides,
+    address_book: AddressBook,
     navigation_context: NavContext,
+    output_format: OutputFormat,
+    interactive: bool,
+    tpu_sender: OnceCell<TpuSender>,
 }
 
-fn create_rpc_client(config: &ScillaConfig) -> anyhow::Result<RpcClient> {
+fn create_rpc_client(config: &ScillaProfile) -> anyhow::Result<RpcClient> {
     let rpc_client = RpcClient::new_with_commitment(
         config.rpc_url.clone(),
         CommitmentConfig {
@@ -27,19 +60,48 @@ fn create_rpc_client(config: &ScillaConfig) -> anyhow::Result<RpcClient> {
     Ok(rpc_client)
 }
 
-fn load_keypair(config: &ScillaConfig) -> anyhow::Result<Keypair> {
-    Keypair::read_from_file(&config.keypair_path).map_err(|e| {
-        anyhow!(
-            "Failed to read keypair from {}: {}",
-            config.keypair_path.display(),
-            e
-        )
+/// The fee payer can be any signer URI, not just a keypair file.
+fn load_keypair(config: &ScillaProfile) -> anyhow::Result<Box<dyn Signer>> {
+    let source = SignerSource::parse(&config.keypair_path)?;
+    if let Some(path) = source.file()
+        && is_world_readable(path)
+    {
+        eprintln!(
+            "{}",
+            style(format!(
+                "Warning: every user on this machine can read the keypair at {}. Run `chmod 600 \
+                 {}` or encrypt it with Keypair > Encrypt Keypair",
+                path.display(),
+                path.display()
+            ))
+            .yellow()
+        );
+    }
+
+    source.load()
+}
+
+/// A broken address book should not keep Scilla from starting, so fall back
+/// to an empty one and say why.
+fn load_address_book() -> AddressBook {
+    AddressBook::load().unwrap_or_else(|e| {
+        eprintln!(
+            "{}",
+            style(format!(
+                "Ignoring address book at {}: {:#}",
+                address_book_path().display(),
+                anyhow::Error::from(e)
+            ))
+            .yellow()
+        );
+        AddressBook::default()
     })
 }
 
 impl ScillaContext {
-    pub fn keypair(&self) -> &Keypair {
-        &self.keypair
+    /// The fee payer, which also signs as the default authority.
+    pub fn keypair(&self) -> &dyn Signer {
+        self.keypair.as_ref()
     }
 
     pub fn rpc(&self) -> &RpcClient {
@@ -54,20 +116,164 @@ impl ScillaContext {
         &self.keypair_path
     }
 
-    pub fn reload(&mut self, new_config: ScillaConfig) -> anyhow::Result<()> {
-        let rpc_client = create_rpc_client(&new_config)?;
-        let keypair = load_keypair(&new_config)?;
+    /// Name of the config profile this session is using.
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Switches the session to `profile` from `config`, replacing the RPC
+    /// client and keypair in place. Command line overrides still apply
+    /// afterwards, while a durable nonce or blockhash is dropped when the
+    /// cluster changes, since it belongs to the previous one.
+    pub fn reload(&mut self, config: &ScillaConfig, profile: &str) -> anyhow::Result<()> {
+        let settings = config.profile(profile)?;
+        let rpc_client = create_rpc_client(&settings)?;
+        let keypair = load_keypair(&settings)?;
         let pubkey = keypair.pubkey();
 
+        let cluster_changed = profile != self.profile || settings.rpc_url != self.rpc_client.url();
+        if cluster_changed && (self.durable_nonce.is_some() || self.blockhash.is_some()) {
+            self.durable_nonce = None;
+            self.blockhash = None;
+            eprintln!(
+                "{}",
+                style(
+                    "The durable nonce and blockhash were cleared, they belong to the previous \
+                     cluster"
+                )
+                .yellow()
+            );
+        }
+
         // Preserve navigation context, only update RPC/keypair
+        self.tpu_sender = OnceCell::new();
         self.rpc_client = rpc_client;
         self.keypair = keypair;
         self.pubkey = pubkey;
-        self.keypair_path = new_config.keypair_path;
+        self.keypair_path = settings.keypair_path;
+        self.profile = profile.to_string();
+        self.preview_transactions = !settings.skip_preview;
+        self.priority_fee = settings.priority_fee;
+        self.transport = settings.transport;
+        self.apply_overrides();
 
         Ok(())
     }
 
+    /// Applies the command line `overrides` for the rest of the session.
+    pub fn set_overrides(&mut self, overrides: CliOverrides) {
+        self.overrides = overrides;
+        self.apply_overrides();
+    }
+
+    fn apply_overrides(&mut self) {
+        if self.overrides.skip_preview {
+            self.preview_transactions = false;
+        }
+        if let Some(transport) = self.overrides.transport {
+            self.transport = transport;
+        }
+        self.overrides.priority_fee.apply(&mut self.priority_fee);
+    }
+
+    /// Whether transactions are previewed and confirmed before they are
+    /// signed, which profiles can turn off with `skip-preview`.
+    pub fn preview_transactions(&self) -> bool {
+        self.preview_transactions
+    }
+
+    /// Compute budget settings for the transactions this session sends.
+    pub fn priority_fee(&self) -> &PriorityFeeConfig {
+        &self.priority_fee
+    }
+
+    /// How signed transactions are sent.
+    pub fn transport(&self) -> Transport {
+        self.transport
+    }
+
+    /// The TPU sender of the current command, started on first use.
+    pub async fn tpu_sender(&self) -> anyhow::Result<&TpuSender> {
+        self.tpu_sender
+            .get_or_try_init(|| TpuSender::start(&self.rpc_client))
+            .await
+    }
+
+    /// Shuts down the TPU sender once a command is done with it. Its
+    /// transactions are already confirmed or given up on by then, so a failed
+    /// shutdown is of no consequence.
+    pub async fn close_tpu_sender(&mut self) {
+        if let Some(sender) = self.tpu_sender.take() {
+            let _ = sender.shutdown().await;
+        }
+    }
+
+    /// Whether transactions are simulated instead of sent for the rest of the
+    /// session.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Whether transactions are signed with the keypairs available here and
+    /// shown for other signers, instead of sent.
+    pub fn is_sign_only(&self) -> bool {
+        self.sign_only
+    }
+
+    pub fn set_sign_only(&mut self, sign_only: bool) {
+        self.sign_only = sign_only;
+    }
+
+    /// Blockhash to sign with instead of asking the cluster, so sign-only
+    /// transactions can be built to match one signed elsewhere.
+    pub fn blockhash(&self) -> Option<Hash> {
+        self.blockhash
+    }
+
+    pub fn set_blockhash(&mut self, blockhash: Option<Hash>) {
+        self.blockhash = blockhash;
+    }
+
+    /// The nonce account transactions are built against, if any, instead of
+    /// a recent blockhash.
+    pub fn durable_nonce(&self) -> Option<&DurableNonce> {
+        self.durable_nonce.as_ref()
+    }
+
+    pub fn set_durable_nonce(&mut self, durable_nonce: Option<DurableNonce>) {
+        self.durable_nonce = durable_nonce;
+    }
+
+    pub fn address_book(&self) -> &AddressBook {
+        &self.address_book
+    }
+
+    pub fn address_book_mut(&mut self) -> &mut AddressBook {
+        &mut self.address_book
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    pub fn set_output_format(&mut self, output_format: OutputFormat) {
+        self.output_format = output_format;
+    }
+
+    /// Whether Scilla is running the interactive menus, as opposed to a
+    /// single subcommand.
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+    }
+
     pub fn get_nav_context_mut(&mut self) -> &mut NavContext {
         &mut self.navigation_context
     }
@@ -77,12 +283,24 @@ impl ScillaContext {
     }
 }
 
-impl TryFrom<ScillaConfig> for ScillaContext {
-    type Error = anyhow::Error;
+impl ScillaContext {
+    /// A context for the keypair tools, which never sign with the fee payer,
+    /// so they work before the profile's keypair exists. `keypair()` is a
+    /// placeholder that signs nothing.
+    pub fn offline(config: ScillaConfig) -> anyhow::Result<Self> {
+        Self::new(config, |_| {
+            Ok(Box::new(NullSigner::new(&Pubkey::default())))
+        })
+    }
 
-    fn try_from(config: ScillaConfig) -> anyhow::Result<Self> {
-        let rpc_client = create_rpc_client(&config)?;
-        let keypair = load_keypair(&config)?;
+    fn new(
+        config: ScillaConfig,
+        load_keypair: impl FnOnce(&ScillaProfile) -> anyhow::Result<Box<dyn Signer>>,
+    ) -> anyhow::Result<Self> {
+        let profile = config.active_profile_name().to_string();
+        let settings = config.profile(&profile)?;
+        let rpc_client = create_rpc_client(&settings)?;
+        let keypair = load_keypair(&settings)?;
 
         let pubkey = keypair.pubkey();
 
@@ -90,8 +308,29 @@ impl TryFrom<ScillaConfig> for ScillaContext {
             rpc_client,
             keypair,
             pubkey,
-            keypair_path: config.keypair_path,
+            keypair_path: settings.keypair_path,
+            profile,
+            preview_transactions: !settings.skip_preview,
+            priority_fee: settings.priority_fee,
+            transport: settings.transport,
+            dry_run: false,
+            sign_only: false,
+            blockhash: None,
+            durable_nonce: None,
+            overrides: CliOverrides::default(),
+            address_book: load_address_book(),
             navigation_context: NavContext::new(),
+            output_format: OutputFormat::default(),
+            interactive: true,
+            tpu_sender: OnceCell::new(),
         })
     }
 }
+
+impl TryFrom<ScillaConfig> for ScillaContext {
+    type Error = anyhow::Error;
+
+    fn try_from(config: ScillaConfig) -> anyhow::Result<Self> {
+        Self::new(config, load_keypair)
+    }
+}
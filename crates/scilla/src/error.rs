@@ -1,4 +1,4 @@
-use {crate::commands::CommandFlow, thiserror::Error};
+use {crate::commands::CommandFlow, inquire::InquireError, thiserror::Error};
 
 pub type ScillaResult = Result<CommandFlow, ScillaError>;
 
@@ -10,6 +10,41 @@ pub enum ScillaError {
     IoError(#[from] std::io::Error),
     #[error("Toml Parse error")]
     TomlParseError(#[from] toml::de::Error),
+    #[error("Toml Serialize error")]
+    TomlSerializeError(#[from] toml::ser::Error),
+    #[error("Profile \"{0}\" is not defined in the Scilla config")]
+    UnknownProfile(String),
+    /// Returned by `build_and_send_tx` in dry-run mode once the simulation has
+    /// been shown, so the command stops instead of reporting a send.
+    #[error("Dry run: the transaction was simulated, not sent")]
+    DryRun,
+    /// Returned by `build_and_send_tx` in sign-only mode once the partially
+    /// signed transaction has been shown.
+    #[error("Sign only: the transaction was signed, not sent")]
+    SignOnly,
     #[error("Anyhow err")]
     Anyhow(#[from] anyhow::Error),
 }
+
+/// Whether `err` marks a transaction that was only simulated because the
+/// session is in dry-run mode.
+pub fn is_dry_run(err: &anyhow::Error) -> bool {
+    matches!(err.downcast_ref::<ScillaError>(), Some(ScillaError::DryRun))
+}
+
+/// Whether `err` marks a transaction that was only signed because the session
+/// is in sign-only mode.
+pub fn is_sign_only(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<ScillaError>(),
+        Some(ScillaError::SignOnly)
+    )
+}
+
+/// Whether `err` comes from the user pressing Esc or Ctrl-C in a prompt.
+pub fn is_cancelled(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<InquireError>(),
+        Some(InquireError::OperationCanceled | InquireError::OperationInterrupted)
+    )
+}
@@ -0,0 +1,254 @@
+use {
+    crate::{
+        config::HistoryConfig,
+        constants::HISTORY_RELATIVE_PATH,
+        error::ScillaError,
+        misc::helpers::{SolAmount, TokenAmount, write_private_file},
+    },
+    serde::{Deserialize, Serialize},
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    std::{
+        any::TypeId,
+        collections::BTreeMap,
+        env::home_dir,
+        fmt, fs,
+        path::{Path, PathBuf},
+        sync::Mutex,
+    },
+};
+
+/// Number of entries remembered per prompt kind.
+const MAX_ENTRIES_PER_KIND: usize = 50;
+
+/// History shared by every prompt, set up once the config is loaded.
+static HISTORY: Mutex<Option<PromptHistory>> = Mutex::new(None);
+
+pub fn history_path() -> PathBuf {
+    let mut path = home_dir().expect("Error getting home path");
+    path.push(HISTORY_RELATIVE_PATH);
+    path
+}
+
+/// Kinds of prompt input that are remembered separately.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryKind {
+    Pubkey,
+    Signature,
+    Amount,
+    KeypairPath,
+}
+
+impl HistoryKind {
+    pub fn all() -> Vec<Self> {
+        vec![
+            HistoryKind::Pubkey,
+            HistoryKind::Signature,
+            HistoryKind::Amount,
+            HistoryKind::KeypairPath,
+        ]
+    }
+
+    /// The history kind for values parsed as `T`, if that type is remembered
+    /// at all. Paths are not: a `PathBuf` may be a program or an output file
+    /// as well as a keypair, so keypair paths are only recorded by
+    /// [`prompt_keypair_path`](crate::prompt::prompt_keypair_path).
+    pub fn of<T: 'static>() -> Option<Self> {
+        let id = TypeId::of::<T>();
+        if id == TypeId::of::<Pubkey>() {
+            Some(HistoryKind::Pubkey)
+        } else if id == TypeId::of::<Signature>() {
+            Some(HistoryKind::Signature)
+        } else if id == TypeId::of::<f64>()
+            || id == TypeId::of::<SolAmount>()
+            || id == TypeId::of::<TokenAmount>()
+        {
+            Some(HistoryKind::Amount)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for HistoryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            HistoryKind::Pubkey => "Pubkeys",
+            HistoryKind::Signature => "Signatures",
+            HistoryKind::Amount => "Amounts",
+            HistoryKind::KeypairPath => "Keypair paths",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Recently entered values per [`HistoryKind`], most recent first.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct PromptHistory {
+    #[serde(flatten)]
+    entries: BTreeMap<HistoryKind, Vec<String>>,
+    #[serde(skip)]
+    settings: HistoryConfig,
+}
+
+impl PromptHistory {
+    pub fn load_from_path(path: &Path, settings: HistoryConfig) -> Result<Self, ScillaError> {
+        let mut history = if path.exists() {
+            toml::from_str(&fs::read_to_string(path)?)?
+        } else {
+            PromptHistory::default()
+        };
+        history.settings = settings;
+        Ok(history)
+    }
+
+    /// Only the owner can read the file, since it holds addresses, amounts
+    /// and signer URIs.
+    pub fn save_to_path(&self, path: &Path) -> Result<(), ScillaError> {
+        write_private_file(path, &toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn recent(&self, kind: HistoryKind) -> &[String] {
+        if !self.settings.records(kind) {
+            return &[];
+        }
+        self.entries.get(&kind).map_or(&[], Vec::as_slice)
+    }
+
+    /// Moves `value` to the front of the history for `kind`. Returns whether
+    /// anything changed.
+    pub fn record(&mut self, kind: HistoryKind, value: &str) -> bool {
+        let value = value.trim();
+        if value.is_empty() || !self.settings.records(kind) {
+            return false;
+        }
+
+        let entries = self.entries.entry(kind).or_default();
+        entries.retain(|entry| entry != value);
+        entries.insert(0, value.to_string());
+        entries.truncate(MAX_ENTRIES_PER_KIND);
+        true
+    }
+}
+
+/// Loads the saved history with the given privacy settings. Called at startup
+/// and whenever the settings change.
+pub fn init(settings: HistoryConfig) {
+    let history =
+        PromptHistory::load_from_path(&history_path(), settings.clone()).unwrap_or_else(|_| {
+            PromptHistory {
+                settings,
+                ..PromptHistory::default()
+            }
+        });
+
+    if let Ok(mut guard) = HISTORY.lock() {
+        *guard = Some(history);
+    }
+}
+
+pub fn recent(kind: HistoryKind) -> Vec<String> {
+    HISTORY
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|history| history.recent(kind).to_vec()))
+        .unwrap_or_default()
+}
+
+/// Remembers a successfully parsed prompt value. History is best effort, so a
+/// failed write never interrupts the command being run.
+pub fn record(kind: HistoryKind, value: &str) {
+    let Ok(mut guard) = HISTORY.lock() else {
+        return;
+    };
+    if let Some(history) = guard.as_mut()
+        && history.record(kind, value)
+    {
+        let _ = history.save_to_path(&history_path());
+    }
+}
+
+/// Forgets every saved entry, on disk and in memory.
+pub fn clear() -> Result<(), ScillaError> {
+    let path = history_path();
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+
+    if let Ok(mut guard) = HISTORY.lock()
+        && let Some(history) = guard.as_mut()
+    {
+        history.entries.clear();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::misc::helpers::is_world_readable, tempfile::TempDir};
+
+    #[test]
+    fn test_record_dedupes_and_caps_entries() {
+        let mut history = PromptHistory::default();
+        for i in 0..MAX_ENTRIES_PER_KIND + 5 {
+            history.record(HistoryKind::Amount, &i.to_string());
+        }
+        history.record(HistoryKind::Amount, "10");
+
+        let recent = history.recent(HistoryKind::Amount);
+        assert_eq!(recent.len(), MAX_ENTRIES_PER_KIND);
+        assert_eq!(recent[0], "10");
+        assert_eq!(recent.iter().filter(|entry| *entry == "10").count(), 1);
+        assert!(history.recent(HistoryKind::Pubkey).is_empty());
+    }
+
+    #[test]
+    fn test_privacy_settings_skip_history() {
+        let mut disabled = PromptHistory {
+            settings: HistoryConfig {
+                enabled: false,
+                exclude: Vec::new(),
+            },
+            ..PromptHistory::default()
+        };
+        assert!(!disabled.record(HistoryKind::Pubkey, "abc"));
+
+        let mut excluded = PromptHistory {
+            settings: HistoryConfig {
+                enabled: true,
+                exclude: vec![HistoryKind::KeypairPath],
+            },
+            ..PromptHistory::default()
+        };
+        assert!(!excluded.record(HistoryKind::KeypairPath, "/tmp/id.json"));
+        assert!(excluded.record(HistoryKind::Pubkey, "abc"));
+        assert_eq!(excluded.recent(HistoryKind::Pubkey), ["abc"]);
+    }
+
+    #[test]
+    fn test_history_round_trips_through_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("history.toml");
+
+        let mut history = PromptHistory::default();
+        history.record(HistoryKind::Signature, "sig1");
+        history.record(HistoryKind::KeypairPath, "~/id.json");
+        history.save_to_path(&path).expect("history saves");
+        assert!(!is_world_readable(&path));
+
+        let loaded =
+            PromptHistory::load_from_path(&path, HistoryConfig::default()).expect("history loads");
+        assert_eq!(loaded, history);
+    }
+
+    #[test]
+    fn test_kind_of_type() {
+        assert_eq!(HistoryKind::of::<Pubkey>(), Some(HistoryKind::Pubkey));
+        assert_eq!(HistoryKind::of::<SolAmount>(), Some(HistoryKind::Amount));
+        assert_eq!(HistoryKind::of::<TokenAmount>(), Some(HistoryKind::Amount));
+        assert_eq!(HistoryKind::of::<PathBuf>(), None);
+        assert_eq!(HistoryKind::of::<String>(), None);
+    }
+}
@@ -1,80 +1,88 @@
 use {
     crate::{
-        commands::{
-            Command,
-            navigation::{NavigationSection, NavigationTarget},
-        },
-        error::ScillaResult,
+        cli::{Cli, CliCommand},
+        commands::{main_command::main_menu, navigation::run_menus},
+        error::{ScillaResult, is_cancelled, is_dry_run, is_sign_only},
+        misc::{helpers::read_signer, nonce::DurableNonce},
     },
+    clap::Parser,
     commands::CommandFlow,
     config::ScillaConfig,
     console::style,
-    context::ScillaContext,
-    prompt::prompt_main_section,
+    context::{CliOverrides, ScillaContext},
 };
 
+pub mod address_book;
+pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod constants;
 pub mod context;
 pub mod error;
+pub mod history;
 pub mod misc;
+pub mod output;
 pub mod prompt;
 pub mod ui;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> ScillaResult {
-    println!(
-        "{}",
-        style("⚡ Scilla — Hacking Through the Solana Matrix")
-            .bold()
-            .cyan()
-    );
+    let cli = Cli::parse();
 
-    let config = ScillaConfig::load()?;
-    let mut ctx = ScillaContext::try_from(config)?;
-
-    let command = prompt_main_section()?;
-
-    let mut res = command.process_command(&mut ctx).await?;
+    if cli.command.is_none() {
+        println!(
+            "{}",
+            style("⚡ Scilla — Hacking Through the Solana Matrix")
+                .bold()
+                .cyan()
+        );
+    }
 
-    loop {
-        match res {
-            CommandFlow::Processed => {
-                let current = ctx
-                    .get_nav_context()
-                    .current()
-                    .expect("navigation stack is empty; expected a root section to exist");
-                res = current.prompt_and_process_command(&mut ctx).await?;
-            }
-            CommandFlow::NavigateTo(target) => match target {
-                NavigationTarget::MainSection => {
-                    ctx.get_nav_context_mut().reset_navigation_to_main();
-                    res = NavigationSection::Main
-                        .prompt_and_process_command(&mut ctx)
-                        .await?;
-                }
-                NavigationTarget::PreviousSection => {
-                    let previous = ctx
-                        .get_nav_context_mut()
-                        .pop_and_get_previous()
-                        .expect("navigation stack is empty; expected a root section to exist");
+    let mut config = ScillaConfig::load()?;
+    if let Some(profile) = cli.profile {
+        // Only applies to this session; the saved `active-profile` is untouched
+        config.active_profile = Some(profile);
+    }
+    history::init(config.history.clone());
+    // Creating a first keypair must not require one to exist already
+    let mut ctx = if matches!(cli.command, Some(CliCommand::Keypair(_))) {
+        ScillaContext::offline(config)?
+    } else {
+        ScillaContext::try_from(config)?
+    };
+    ctx.set_output_format(cli.output);
+    ctx.set_interactive(cli.command.is_none());
+    ctx.set_dry_run(cli.dry_run);
+    ctx.set_sign_only(cli.sign_only);
+    ctx.set_blockhash(cli.blockhash);
+    ctx.set_overrides(CliOverrides {
+        skip_preview: cli.skip_preview,
+        transport: cli.transport,
+        priority_fee: cli.priority_fee,
+    });
+    if let Some(account) = cli.nonce {
+        let authority = cli
+            .nonce_authority
+            .map(|path| read_signer(&ctx, path))
+            .transpose()?;
+        ctx.set_durable_nonce(Some(DurableNonce { account, authority }));
+    }
 
-                    match previous {
-                        NavigationSection::Main => {
-                            res = NavigationSection::Main
-                                .prompt_and_process_command(&mut ctx)
-                                .await?;
-                        }
-                        _ => res = previous.prompt_and_process_command(&mut ctx).await?,
-                    }
-                }
-            },
-            CommandFlow::Exit => {
-                break;
+    if let Some(command) = cli.command {
+        let result = command.run(&mut ctx).await;
+        ctx.close_tpu_sender().await;
+        return match result {
+            Ok(()) => Ok(CommandFlow::Processed),
+            Err(e) if is_cancelled(&e) => Ok(CommandFlow::Cancelled),
+            Err(e) if is_dry_run(&e) || is_sign_only(&e) => Ok(CommandFlow::Processed),
+            Err(e) => {
+                eprintln!("{}", style(format!("Error : {e}")).red().bold());
+                Ok(CommandFlow::Failed)
             }
-        }
+        };
     }
 
+    run_menus(&mut ctx, &main_menu()).await?;
+
     Ok(CommandFlow::Exit)
 }
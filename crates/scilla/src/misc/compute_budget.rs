@@ -0,0 +1,210 @@
+use {
+    crate::{
+        config::PriorityFeeConfig,
+        constants::{COMPUTE_UNIT_LIMIT_MARGIN, MAX_COMPUTE_UNIT_LIMIT},
+        context::ScillaContext,
+    },
+    anyhow::bail,
+    solana_compute_budget_interface::ComputeBudgetInstruction,
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::config::RpcSimulateTransactionConfig,
+    solana_transaction::Transaction,
+    tokio::try_join,
+};
+
+/// Builds the `SetComputeUnitLimit` and `SetComputeUnitPrice` instructions to
+/// prepend to `instructions`, or none when priority fees are turned off.
+pub async fn compute_budget_instructions(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<Vec<Instruction>> {
+    let config = ctx.priority_fee();
+    if !config.enabled {
+        return Ok(Vec::new());
+    }
+    if ctx.is_sign_only() {
+        return sign_only_budget(config);
+    }
+
+    let message = Message::new(instructions, Some(ctx.pubkey()));
+    let writable: Vec<Pubkey> = message
+        .account_keys
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| message.is_maybe_writable(*idx, None))
+        .map(|(_, key)| *key)
+        .collect();
+
+    let units = async {
+        match config.compute_unit_limit {
+            Some(units) => Ok(Some(units)),
+            None => simulate_compute_units(ctx, instructions).await,
+        }
+    };
+    let (units, price) = try_join!(units, compute_unit_price(ctx, config, &writable))?;
+
+    let mut budget = Vec::with_capacity(2);
+    // A failing simulation says nothing about the units needed; leave the
+    // default limit and let the preview or the send report the failure
+    if let Some(units) = units {
+        budget.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+    }
+    budget.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+
+    Ok(budget)
+}
+
+/// Compute units `instructions` consume, plus a margin for state that changes
+/// before the transaction lands.
+async fn simulate_compute_units(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<Option<u32>> {
+    // Simulate with the budget instructions in place, so their own units are
+    // counted and the limit is not hit during simulation
+    let instructions = [
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT),
+            ComputeBudgetInstruction::set_compute_unit_price(0),
+        ],
+        instructions.to_vec(),
+    ]
+    .concat();
+    let tx = Transaction::new_unsigned(Message::new(&instructions, Some(ctx.pubkey())));
+
+    let simulation = ctx
+        .rpc()
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(ctx.rpc().commitment()),
+                ..Default::default()
+            },
+        )
+        .await?
+        .value;
+
+    if simulation.err.is_some() {
+        return Ok(None);
+    }
+
+    Ok(simulation.units_consumed.map(with_margin))
+}
+
+async fn compute_unit_price(
+    ctx: &ScillaContext,
+    config: &PriorityFeeConfig,
+    writable: &[Pubkey],
+) -> anyhow::Result<u64> {
+    let price = match config.compute_unit_price {
+        Some(price) => price,
+        None => {
+            let fees: Vec<u64> = ctx
+                .rpc()
+                .get_recent_prioritization_fees(writable)
+                .await?
+                .into_iter()
+                .map(|fee| fee.prioritization_fee)
+                .collect();
+            percentile(fees, config.percentile)
+        }
+    };
+
+    Ok(config
+        .max_compute_unit_price
+        .map_or(price, |max| price.min(max)))
+}
+
+/// Sign-only mode may run on a machine without network access, so nothing is
+/// simulated or looked up: the limit is only set when one is configured and
+/// the price has to be a fixed one.
+fn sign_only_budget(config: &PriorityFeeConfig) -> anyhow::Result<Vec<Instruction>> {
+    let Some(price) = config.compute_unit_price else {
+        bail!(
+            "Recent fees cannot be looked up in sign-only mode. Pass --compute-unit-price, or \
+             --no-priority-fee to sign without priority fees"
+        );
+    };
+
+    let mut budget = Vec::with_capacity(2);
+    if let Some(units) = config.compute_unit_limit {
+        budget.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+    }
+    let price = config
+        .max_compute_unit_price
+        .map_or(price, |max| price.min(max));
+    budget.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+
+    Ok(budget)
+}
+
+fn with_margin(units: u64) -> u32 {
+    let units = units + units * COMPUTE_UNIT_LIMIT_MARGIN / 100;
+    units.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// Nearest-rank percentile of `fees`, or 0 when there are none.
+fn percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+
+    fees.sort_unstable();
+    let percentile = percentile.min(100) as usize;
+    let rank = (percentile * fees.len()).div_ceil(100).max(1);
+    fees[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let fees = vec![500, 0, 100, 0, 2_000, 300, 0, 50, 1_000, 10];
+        assert_eq!(percentile(fees.clone(), 0), 0);
+        assert_eq!(percentile(fees.clone(), 50), 50);
+        assert_eq!(percentile(fees.clone(), 75), 500);
+        assert_eq!(percentile(fees.clone(), 100), 2_000);
+        // Out of range percentiles are treated as the maximum
+        assert_eq!(percentile(fees, 250), 2_000);
+        assert_eq!(percentile(Vec::new(), 75), 0);
+    }
+
+    #[test]
+    fn test_sign_only_budget() {
+        // Recent fees need the RPC
+        let mut config = PriorityFeeConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(sign_only_budget(&config).is_err());
+
+        config.compute_unit_price = Some(5_000);
+        assert_eq!(
+            sign_only_budget(&config).unwrap(),
+            [ComputeBudgetInstruction::set_compute_unit_price(5_000)]
+        );
+
+        config.compute_unit_limit = Some(300_000);
+        config.max_compute_unit_price = Some(1_000);
+        assert_eq!(
+            sign_only_budget(&config).unwrap(),
+            [
+                ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+                ComputeBudgetInstruction::set_compute_unit_price(1_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_unit_limit_margin() {
+        assert_eq!(with_margin(200_000), 220_000);
+        assert_eq!(with_margin(0), 0);
+        assert_eq!(with_margin(1_390_000), MAX_COMPUTE_UNIT_LIMIT);
+    }
+}
@@ -1,5 +1,20 @@
 use {
-    crate::{ScillaContext, constants::LAMPORTS_PER_SOL},
+    crate::{
+        ScillaContext,
+        commands::transaction::SimulationOutput,
+        config::Transport,
+        constants::LAMPORTS_PER_SOL,
+        error::ScillaError,
+        misc::{
+            compute_budget::compute_budget_instructions,
+            keystore::{Keystore, unlock_keystore},
+            offline::SignedTransactionOutput,
+            preview::confirm_transaction,
+            signer::SignerSource,
+            tpu::send_and_confirm_via_tpu,
+        },
+        ui::{render_output, suspend_spinner},
+    },
     anyhow::{Context, anyhow, bail},
     base64::Engine,
     bincode::Options,
@@ -9,12 +24,21 @@ use {
     solana_keypair::{EncodableKey, Keypair, Signature, Signer},
     solana_message::Message,
     solana_pubkey::Pubkey,
+    solana_rpc_client::rpc_client::SerializableTransaction,
+    solana_rpc_client_api::config::RpcSimulateTransactionConfig,
+    solana_signer::null_signer::NullSigner,
     solana_transaction::{Transaction, versioned::VersionedTransaction},
     solana_transaction_status::{
         EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
         UiTransactionEncoding,
     },
-    std::{path::Path, str::FromStr},
+    std::{
+        fmt,
+        fs::{self, OpenOptions},
+        io::Write,
+        path::Path,
+        str::FromStr,
+    },
     tokio::try_join,
 };
 
@@ -84,6 +108,55 @@ impl FromStr for SolAmount {
     }
 }
 
+/// A token amount as typed by the user, e.g. `1.5`. Kept as text until the
+/// mint's decimals are known, so no precision is lost to floats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAmount(String);
+
+impl TokenAmount {
+    /// The amount in the mint's base units.
+    pub fn to_base_units(&self, decimals: u8) -> anyhow::Result<u64> {
+        let (whole, fraction) = self.0.split_once('.').unwrap_or((&self.0, ""));
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > usize::from(decimals) {
+            bail!("Amount {} has more than {decimals} decimal places", self.0);
+        }
+
+        let digits = format!("{whole}{fraction:0<width$}", width = usize::from(decimals));
+        let amount = digits
+            .parse::<u64>()
+            .map_err(|_| anyhow!("Amount too large: {}", self.0))?;
+        if amount == 0 {
+            bail!("Amount must be greater than zero, got {}", self.0);
+        }
+        Ok(amount)
+    }
+}
+
+impl FromStr for TokenAmount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let amount = s.trim();
+        if amount.is_empty() {
+            bail!("Amount cannot be empty. Please enter a token amount");
+        }
+
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() && fraction.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            bail!("Invalid amount: {amount}. Must be a positive decimal number");
+        }
+        Ok(TokenAmount(amount.to_string()))
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 pub async fn check_minimum_balance(
     ctx: &ScillaContext,
     payer: &Pubkey,
@@ -111,25 +184,216 @@ pub fn lamports_to_sol(lamports: u64) -> f64 {
     lamports as f64 / LAMPORTS_PER_SOL as f64
 }
 
+/// Reads the keypair at `path`, asking for the password if it is an
+/// encrypted keystore that was not unlocked yet this session.
 pub fn read_keypair_from_path<P: AsRef<Path>>(path: P) -> anyhow::Result<Keypair> {
     let path = path.as_ref();
+    if let Some(keystore) = Keystore::read(path)? {
+        return unlock_keystore(path, &keystore)
+            .with_context(|| format!("Failed to unlock keystore {}", path.display()));
+    }
+
     Keypair::read_from_file(path)
         .map_err(|e| anyhow!("Failed to read keypair from {}: {}", path.display(), e))
 }
 
+/// Writes `contents` to `path` so that only the owner can read it, tightening
+/// the permissions of a file that already exists too. The contents go to a
+/// temporary file next to `path` first and replace it in one rename, so a
+/// crash or a full disk never leaves a key half written.
+pub fn write_private_file(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("{} is not a file path", path.display()))?;
+    let tmp_path = dir.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    // Left behind by an earlier run that died mid-write
+    let _ = fs::remove_file(&tmp_path);
+    let written = write_new_private_file(&tmp_path, contents)
+        .and_then(|()| fs::rename(&tmp_path, path).map_err(Into::into));
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    written
+}
+
+/// Creates `path` readable by the owner only and syncs `contents` to disk.
+fn write_new_private_file(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let mut options = OpenOptions::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Whether every user on this machine can read the file at `path`.
+pub fn is_world_readable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o004 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
 pub async fn build_and_send_tx(
     ctx: &ScillaContext,
     instruction: &[Instruction],
     signers: &[&dyn Signer],
 ) -> anyhow::Result<Signature> {
-    let recent_blockhash = ctx.rpc().get_latest_blockhash().await?;
-    let message = Message::new(instruction, Some(ctx.pubkey()));
+    let nonce_instruction = ctx
+        .durable_nonce()
+        .map(|nonce| nonce.advance_instruction(ctx));
+    let mut instructions: Vec<Instruction> = nonce_instruction.into_iter().collect();
+    instructions.extend_from_slice(instruction);
+
+    // Advancing the nonce has to stay the first instruction
+    let budget = compute_budget_instructions(ctx, &instructions).await?;
+    let budget_at = usize::from(ctx.durable_nonce().is_some());
+    instructions.splice(budget_at..budget_at, budget);
+
+    let message = Message::new(&instructions, Some(ctx.pubkey()));
+    if ctx.is_sign_only() {
+        return sign_only_tx(ctx, message, signers).await;
+    }
+    if ctx.is_dry_run() {
+        return dry_run_tx(ctx, message, signers).await;
+    }
+    if ctx.preview_transactions() {
+        // Nothing is signed until the preview has been confirmed
+        confirm_transaction(ctx, &message).await?;
+    }
+
+    let tx = sign_tx(ctx, message, signers).await?;
+    send_and_confirm(ctx, &tx).await
+}
+
+/// Sends a signed transaction through the configured [`Transport`] and waits
+/// for the RPC to confirm it.
+pub async fn send_and_confirm(
+    ctx: &ScillaContext,
+    tx: &impl SerializableTransaction,
+) -> anyhow::Result<Signature> {
+    match ctx.transport() {
+        Transport::Rpc => Ok(ctx.rpc().send_and_confirm_transaction(tx).await?),
+        Transport::Tpu => send_and_confirm_via_tpu(ctx, tx).await,
+    }
+}
+
+/// Signs `message` with `signers` against the given blockhash, the durable
+/// nonce or the latest blockhash, in that order. Signers standing in for keys
+/// held elsewhere leave their signature empty.
+async fn sign_tx(
+    ctx: &ScillaContext,
+    message: Message,
+    signers: &[&dyn Signer],
+) -> anyhow::Result<Transaction> {
+    let mut signers = signers.to_vec();
+    if let Some(authority) = ctx
+        .durable_nonce()
+        .and_then(|nonce| nonce.authority.as_ref())
+        && !signers.iter().any(|s| s.pubkey() == authority.pubkey())
+    {
+        signers.push(authority);
+    }
+
+    let recent_blockhash = match (ctx.blockhash(), ctx.durable_nonce()) {
+        (Some(blockhash), _) => blockhash,
+        (None, Some(nonce)) => nonce.blockhash(ctx).await?,
+        (None, None) => ctx.rpc().get_latest_blockhash().await?,
+    };
+
     let mut tx = Transaction::new_unsigned(message);
-    tx.try_sign(&signers.to_vec(), recent_blockhash)?;
+    tx.try_partial_sign(&signers, recent_blockhash)?;
+    if !ctx.is_sign_only() && !tx.is_signed() {
+        bail!("Not every signer of this transaction has signed it");
+    }
 
-    let signature = ctx.rpc().send_and_confirm_transaction(&tx).await?;
+    Ok(tx)
+}
+
+/// Signs `message` with the keypairs available here and shows it, with the
+/// signatures still missing, for the remaining signers. Always returns
+/// [`ScillaError::SignOnly`] so callers never report a signature.
+async fn sign_only_tx(
+    ctx: &ScillaContext,
+    message: Message,
+    signers: &[&dyn Signer],
+) -> anyhow::Result<Signature> {
+    let tx = sign_tx(ctx, message, signers).await?;
+    let output = SignedTransactionOutput::from(&VersionedTransaction::from(tx));
+
+    suspend_spinner(|| render_output(ctx, &output))?;
 
-    Ok(signature)
+    Err(ScillaError::SignOnly.into())
+}
+
+/// Loads the signer at `path`, a keypair file or a signer URI. In sign-only
+/// mode a pubkey can be given instead, for a key held on another machine; its
+/// signature is added later with "Add signatures and submit".
+pub fn read_signer<P: AsRef<Path>>(
+    ctx: &ScillaContext,
+    path: P,
+) -> anyhow::Result<Box<dyn Signer>> {
+    let source = SignerSource::parse(path.as_ref())?;
+    if ctx.is_sign_only()
+        && let SignerSource::File(path) = &source
+        && !path.exists()
+        && let Some(pubkey) = path.to_str().and_then(|s| Pubkey::from_str(s).ok())
+    {
+        return Ok(Box::new(NullSigner::new(&pubkey)));
+    }
+
+    source.load()
+}
+
+/// Signs `message` and simulates it instead of sending it, then shows the
+/// logs, compute units and would-be result. Always returns
+/// [`ScillaError::DryRun`] so callers never report a signature.
+async fn dry_run_tx(
+    ctx: &ScillaContext,
+    message: Message,
+    signers: &[&dyn Signer],
+) -> anyhow::Result<Signature> {
+    let tx = sign_tx(ctx, message, signers).await?;
+
+    let result = ctx
+        .rpc()
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: true,
+                commitment: Some(ctx.rpc().commitment()),
+                ..Default::default()
+            },
+        )
+        .await?
+        .value;
+
+    suspend_spinner(|| render_output(ctx, &SimulationOutput { result }))?;
+
+    Err(ScillaError::DryRun.into())
 }
 
 /// Fetches account data and current epoch info in parallel.
@@ -193,7 +457,7 @@ pub fn decode_base58(encoded: &str) -> anyhow::Result<Vec<u8>> {
     })
 }
 
-pub fn short_pubkey(pk: &Pubkey) -> String {
+pub fn short_pubkey(pk: &impl ToString) -> String {
     let s = pk.to_string();
     let prefix = &s[..4];
     let suffix = &s[s.len() - 3..];
@@ -285,6 +549,29 @@ mod tests {
         assert!(result > 0.0, "Should handle u64::MAX without panic");
         assert!(result < f64::INFINITY, "Should not overflow to infinity");
     }
+
+    #[test]
+    fn test_token_amount_to_base_units() -> anyhow::Result<()> {
+        let amount = |s: &str| s.parse::<TokenAmount>();
+
+        assert_eq!(amount("1.5")?.to_base_units(6)?, 1_500_000);
+        assert_eq!(amount("42")?.to_base_units(0)?, 42);
+        assert_eq!(amount(".25")?.to_base_units(2)?, 25);
+        assert_eq!(amount("3.10")?.to_base_units(1)?, 31);
+        assert_eq!(amount("18446744073709551615")?.to_base_units(0)?, u64::MAX);
+
+        assert!(amount("1.234")?.to_base_units(2).is_err());
+        assert!(amount("0.0")?.to_base_units(9).is_err());
+        assert!(amount("18446744073709551616")?.to_base_units(0).is_err());
+        assert!(amount("1000000000000")?.to_base_units(9).is_err());
+
+        for invalid in ["", ".", "-1", "1.2.3", "1e5", "abc"] {
+            assert!(amount(invalid).is_err(), "{invalid} should not parse");
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_decode_base64_memo_transaction() -> anyhow::Result<()> {
         let decoded = decode_base64(MEMO_BASE64_TX)?;
@@ -364,4 +651,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_write_private_file_replaces_the_file() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("id.json");
+        fs::write(&path, "old")?;
+
+        write_private_file(&path, "new")?;
+
+        assert_eq!(fs::read_to_string(&path)?, "new");
+        // Only the file itself is left, no temporary copy
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,357 @@
+use {
+    crate::{
+        misc::helpers::write_private_file,
+        ui::{print_error, suspend_spinner},
+    },
+    aes_gcm_siv::{
+        Aes256GcmSiv, KeyInit, Nonce,
+        aead::{Aead, OsRng, Payload, rand_core::RngCore},
+    },
+    anyhow::{Context, anyhow, bail},
+    base64::{Engine, engine::general_purpose::STANDARD},
+    inquire::{Password, validator::Validation},
+    serde::{Deserialize, Serialize},
+    sha2::Sha256,
+    solana_keypair::{Keypair, Signer},
+    solana_pubkey::Pubkey,
+    std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::Mutex,
+    },
+};
+
+/// Format version written to new keystores.
+const KEYSTORE_VERSION: u8 = 1;
+
+/// PBKDF2 rounds for new keystores, as recommended by OWASP for HMAC-SHA256.
+pub const KDF_ROUNDS: u32 = 600_000;
+
+/// PBKDF2 rounds accepted in a keystore. Fewer would make the password cheap
+/// to guess, more would keep Scilla busy unlocking it for minutes.
+pub const MIN_KDF_ROUNDS: u32 = 10_000;
+const MAX_KDF_ROUNDS: u32 = 10_000_000;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Passwords of the keystores unlocked so far, kept in memory until Scilla
+/// exits so each file is only asked for once.
+static PASSWORDS: Mutex<BTreeMap<PathBuf, String>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Kdf {
+    #[serde(rename = "pbkdf2-hmac-sha256")]
+    Pbkdf2HmacSha256,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Cipher {
+    #[serde(rename = "aes-256-gcm-siv")]
+    Aes256GcmSiv,
+}
+
+/// A keypair file encrypted with a password. The pubkey is kept in the clear
+/// and authenticated along with the secret key, so it cannot be swapped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keystore {
+    pub version: u8,
+    pub pubkey: String,
+    pub kdf: Kdf,
+    pub rounds: u32,
+    /// Base64 encoded
+    pub salt: String,
+    pub cipher: Cipher,
+    /// Base64 encoded
+    pub nonce: String,
+    /// The 64 keypair bytes followed by the tag, base64 encoded
+    pub ciphertext: String,
+}
+
+impl Keystore {
+    pub fn encrypt(keypair: &Keypair, password: &str, rounds: u32) -> anyhow::Result<Self> {
+        check_rounds(rounds)?;
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let pubkey = keypair.pubkey();
+        let ciphertext = cipher(password, &salt, rounds)
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &keypair.to_bytes(),
+                    aad: pubkey.as_ref(),
+                },
+            )
+            .map_err(|_| anyhow!("Failed to encrypt the keypair"))?;
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            pubkey: pubkey.to_string(),
+            kdf: Kdf::Pbkdf2HmacSha256,
+            rounds,
+            salt: STANDARD.encode(salt),
+            cipher: Cipher::Aes256GcmSiv,
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, password: &str) -> anyhow::Result<Keypair> {
+        self.unseal()?
+            .open(password)?
+            .ok_or_else(|| anyhow!("Wrong password"))
+    }
+
+    /// Decodes the stored fields, so a damaged file is told apart from a
+    /// wrong password.
+    fn unseal(&self) -> anyhow::Result<Sealed> {
+        if self.version != KEYSTORE_VERSION {
+            bail!("Unsupported keystore version {}", self.version);
+        }
+        check_rounds(self.rounds)?;
+        let nonce = STANDARD
+            .decode(&self.nonce)
+            .context("Invalid keystore nonce")?;
+        if nonce.len() != NONCE_LEN {
+            bail!("Invalid keystore nonce");
+        }
+
+        Ok(Sealed {
+            pubkey: self
+                .pubkey
+                .parse()
+                .map_err(|_| anyhow!("Invalid pubkey in keystore: {}", self.pubkey))?,
+            rounds: self.rounds,
+            salt: STANDARD
+                .decode(&self.salt)
+                .context("Invalid keystore salt")?,
+            nonce,
+            ciphertext: STANDARD
+                .decode(&self.ciphertext)
+                .context("Invalid keystore ciphertext")?,
+        })
+    }
+
+    /// Reads the keystore at `path`, or `None` when the file is missing or
+    /// holds a plain keypair.
+    pub fn read(path: &Path) -> anyhow::Result<Option<Self>> {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Ok(None);
+        };
+        // Plain keypair files are a JSON array of bytes
+        if !contents.trim_start().starts_with('{') {
+            return Ok(None);
+        }
+
+        serde_json::from_str(&contents)
+            .map(Some)
+            .with_context(|| format!("{} is not a valid keystore", path.display()))
+    }
+
+    /// Writes the keystore to `path`, readable by the owner only.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        write_private_file(path, &serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write keystore to {}", path.display()))
+    }
+}
+
+struct Sealed {
+    pubkey: Pubkey,
+    rounds: u32,
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl Sealed {
+    /// The keypair, or `None` when `password` is wrong. Authentication also
+    /// fails when the file was tampered with.
+    fn open(&self, password: &str) -> anyhow::Result<Option<Keypair>> {
+        let Ok(bytes) = cipher(password, &self.salt, self.rounds).decrypt(
+            Nonce::from_slice(&self.nonce),
+            Payload {
+                msg: &self.ciphertext,
+                aad: self.pubkey.as_ref(),
+            },
+        ) else {
+            return Ok(None);
+        };
+
+        let keypair = Keypair::try_from(bytes.as_slice())?;
+        if keypair.pubkey() != self.pubkey {
+            bail!("The keystore holds a different key than {}", self.pubkey);
+        }
+        Ok(Some(keypair))
+    }
+}
+
+fn check_rounds(rounds: u32) -> anyhow::Result<()> {
+    if !(MIN_KDF_ROUNDS..=MAX_KDF_ROUNDS).contains(&rounds) {
+        bail!(
+            "Keystores use between {MIN_KDF_ROUNDS} and {MAX_KDF_ROUNDS} PBKDF2 rounds, not \
+             {rounds}"
+        );
+    }
+
+    Ok(())
+}
+
+fn cipher(password: &str, salt: &[u8], rounds: u32) -> Aes256GcmSiv {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, &mut key);
+    Aes256GcmSiv::new(&key.into())
+}
+
+/// Cache entries are keyed by the canonical path, so `~/id.json` and
+/// `/home/me/id.json` share a password.
+fn cache_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Remembers `password` for the keystore at `path` for the rest of the
+/// session.
+pub fn remember_password(path: &Path, password: &str) {
+    if let Ok(mut passwords) = PASSWORDS.lock() {
+        passwords.insert(cache_key(path), password.to_string());
+    }
+}
+
+fn cached_password(path: &Path) -> Option<String> {
+    PASSWORDS.lock().ok()?.get(&cache_key(path)).cloned()
+}
+
+/// Decrypts the keystore at `path` with the password given earlier in the
+/// session, or asks for it until the right one is entered.
+pub fn unlock_keystore(path: &Path, keystore: &Keystore) -> anyhow::Result<Keypair> {
+    let sealed = keystore.unseal()?;
+    if let Some(password) = cached_password(path)
+        && let Some(keypair) = sealed.open(&password)?
+    {
+        return Ok(keypair);
+    }
+
+    let message = format!("Password for {}:", path.display());
+    loop {
+        let password = suspend_spinner(|| {
+            Password::new(&message)
+                .with_help_message(&format!("Encrypted keypair for {}", keystore.pubkey))
+                .without_confirmation()
+                .prompt()
+        })?;
+
+        match sealed.open(&password)? {
+            Some(keypair) => {
+                remember_password(path, &password);
+                return Ok(keypair);
+            }
+            None => print_error("Wrong password. Please try again."),
+        }
+    }
+}
+
+/// Asks for the password of a new keystore, twice.
+pub fn prompt_new_password() -> anyhow::Result<String> {
+    Ok(Password::new("New keystore password:")
+        .with_help_message("Needed every time the keypair is used. It cannot be recovered")
+        .with_custom_confirmation_message("Confirm password:")
+        .with_validator(|password: &str| {
+            Ok(if password.is_empty() {
+                Validation::Invalid("The password cannot be empty".into())
+            } else {
+                Validation::Valid
+            })
+        })
+        .prompt()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    // Few rounds keep the tests fast; the format is the same
+    const TEST_ROUNDS: u32 = MIN_KDF_ROUNDS;
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt(&keypair, "hunter2", TEST_ROUNDS).unwrap();
+        assert_eq!(keystore.pubkey, keypair.pubkey().to_string());
+
+        let decrypted = keystore.decrypt("hunter2").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+        assert!(keystore.decrypt("hunter3").is_err());
+
+        // Fresh salt and nonce every time
+        let again = Keystore::encrypt(&keypair, "hunter2", TEST_ROUNDS).unwrap();
+        assert_ne!(again.ciphertext, keystore.ciphertext);
+    }
+
+    #[test]
+    fn test_reject_swapped_pubkey() {
+        let keypair = Keypair::new();
+        let mut keystore = Keystore::encrypt(&keypair, "hunter2", TEST_ROUNDS).unwrap();
+        keystore.pubkey = Pubkey::new_unique().to_string();
+
+        assert!(keystore.decrypt("hunter2").is_err());
+    }
+
+    #[test]
+    fn test_reject_out_of_range_rounds() {
+        let keypair = Keypair::new();
+        let mut keystore = Keystore::encrypt(&keypair, "hunter2", TEST_ROUNDS).unwrap();
+
+        for rounds in [0, MIN_KDF_ROUNDS - 1, MAX_KDF_ROUNDS + 1, u32::MAX] {
+            keystore.rounds = rounds;
+            assert!(keystore.decrypt("hunter2").is_err());
+        }
+        assert!(Keystore::encrypt(&keypair, "hunter2", 0).is_err());
+    }
+
+    #[test]
+    fn test_read_tells_keystores_apart() {
+        let dir = TempDir::new().unwrap();
+        let keypair = Keypair::new();
+
+        let plain = dir.path().join("plain.json");
+        solana_keypair::write_keypair_file(&keypair, &plain).unwrap();
+        assert_eq!(Keystore::read(&plain).unwrap(), None);
+        assert_eq!(
+            Keystore::read(&dir.path().join("missing.json")).unwrap(),
+            None
+        );
+
+        let encrypted = dir.path().join("encrypted.json");
+        let keystore = Keystore::encrypt(&keypair, "hunter2", TEST_ROUNDS).unwrap();
+        keystore.write(&encrypted).unwrap();
+        assert_eq!(Keystore::read(&encrypted).unwrap(), Some(keystore));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&encrypted).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::write(&encrypted, "{\"version\": 1}").unwrap();
+        assert!(Keystore::read(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_cached_password_unlocks() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cached.json");
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt(&keypair, "hunter2", TEST_ROUNDS).unwrap();
+        keystore.write(&path).unwrap();
+
+        // No prompt comes up once the password is known
+        remember_password(&path, "hunter2");
+        let unlocked = unlock_keystore(&path, &keystore).unwrap();
+        assert_eq!(unlocked.pubkey(), keypair.pubkey());
+    }
+}
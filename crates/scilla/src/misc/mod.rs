@@ -1 +1,8 @@
+pub mod compute_budget;
 pub mod helpers;
+pub mod keystore;
+pub mod nonce;
+pub mod offline;
+pub mod preview;
+pub mod signer;
+pub mod tpu;
@@ -0,0 +1,93 @@
+use {
+    crate::{context::ScillaContext, misc::helpers::bincode_deserialize},
+    anyhow::bail,
+    solana_account::Account,
+    solana_hash::Hash,
+    solana_instruction::Instruction,
+    solana_nonce::{
+        state::{Data, State},
+        versions::Versions,
+    },
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_interface::instruction::advance_nonce_account,
+};
+
+/// A nonce account that transactions are built against instead of a recent
+/// blockhash, so they can be signed now and sent much later.
+pub struct DurableNonce {
+    pub account: Pubkey,
+    /// The nonce authority, when it is not the configured keypair
+    pub authority: Option<Box<dyn Signer>>,
+}
+
+impl DurableNonce {
+    pub fn authority_pubkey(&self, ctx: &ScillaContext) -> Pubkey {
+        self.authority
+            .as_ref()
+            .map_or(*ctx.pubkey(), |authority| authority.pubkey())
+    }
+
+    /// The instruction that has to come first in every transaction using the
+    /// nonce.
+    pub fn advance_instruction(&self, ctx: &ScillaContext) -> Instruction {
+        advance_nonce_account(&self.account, &self.authority_pubkey(ctx))
+    }
+
+    /// The blockhash currently stored in the nonce account, after checking
+    /// that the authority in use can advance it.
+    pub async fn blockhash(&self, ctx: &ScillaContext) -> anyhow::Result<Hash> {
+        let NonceAccount { data, legacy, .. } = load_nonce_account(ctx, &self.account).await?;
+        if legacy {
+            bail!(
+                "Nonce account {} uses the legacy format. Upgrade it before using it",
+                self.account
+            );
+        }
+
+        let authority = self.authority_pubkey(ctx);
+        if data.authority != authority {
+            bail!(
+                "{authority} is not the authority of nonce account {}, {} is",
+                self.account,
+                data.authority
+            );
+        }
+
+        Ok(data.blockhash())
+    }
+}
+
+/// An initialized nonce account as stored on chain.
+pub struct NonceAccount {
+    pub account: Account,
+    pub data: Data,
+    /// Created before durable nonces and blockhashes were separated, and
+    /// needs an `UpgradeNonceAccount` before it can be used
+    pub legacy: bool,
+}
+
+/// Fetches `pubkey` and decodes it as an initialized nonce account.
+pub async fn load_nonce_account(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+) -> anyhow::Result<NonceAccount> {
+    let account = ctx.rpc().get_account(pubkey).await?;
+
+    decode_nonce_account(account)
+}
+
+/// Decodes an already fetched account as an initialized nonce account.
+pub fn decode_nonce_account(account: Account) -> anyhow::Result<NonceAccount> {
+    let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+
+    let State::Initialized(data) = versions.state() else {
+        bail!("This account is not an initialized nonce account");
+    };
+
+    Ok(NonceAccount {
+        data: data.clone(),
+        legacy: matches!(versions, Versions::Legacy(_)),
+        account,
+    })
+}
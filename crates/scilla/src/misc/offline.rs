@@ -0,0 +1,261 @@
+use {
+    crate::output::{Color, CommandOutput, TableCell, TableView},
+    anyhow::{anyhow, bail},
+    base64::Engine,
+    console::style,
+    serde::Serialize,
+    solana_keypair::{Signature, Signer},
+    solana_pubkey::Pubkey,
+    solana_transaction::versioned::VersionedTransaction,
+    std::str::FromStr,
+};
+
+/// A signature collected on another machine, given as `PUBKEY=SIGNATURE`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PubkeySignature {
+    pub pubkey: Pubkey,
+    pub signature: Signature,
+}
+
+impl FromStr for PubkeySignature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pubkey, signature) = s
+            .trim()
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected PUBKEY=SIGNATURE, got {s}"))?;
+
+        Ok(Self {
+            pubkey: pubkey
+                .parse()
+                .map_err(|_| anyhow!("Invalid pubkey: {pubkey}"))?,
+            signature: signature
+                .parse()
+                .map_err(|_| anyhow!("Invalid signature: {signature}"))?,
+        })
+    }
+}
+
+/// A transaction signed by the keypairs available here, waiting for the
+/// signatures of keys held elsewhere.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTransactionOutput {
+    pub blockhash: String,
+    /// The transaction, base64 encoded
+    pub transaction: String,
+    pub signers: Vec<SignerOutput>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerOutput {
+    pub pubkey: String,
+    pub signature: Option<String>,
+}
+
+impl From<&VersionedTransaction> for SignedTransactionOutput {
+    fn from(tx: &VersionedTransaction) -> Self {
+        let signers = signer_keys(tx)
+            .iter()
+            .zip(&tx.signatures)
+            .map(|(pubkey, signature)| SignerOutput {
+                pubkey: pubkey.to_string(),
+                signature: (*signature != Signature::default()).then(|| signature.to_string()),
+            })
+            .collect();
+
+        Self {
+            blockhash: tx.message.recent_blockhash().to_string(),
+            transaction: base64::engine::general_purpose::STANDARD
+                .encode(bincode::serialize(tx).expect("transactions always serialize")),
+            signers,
+        }
+    }
+}
+
+impl CommandOutput for SignedTransactionOutput {
+    fn tables(&self) -> Vec<TableView> {
+        let mut summary = TableView::key_value("SIGNED TRANSACTION");
+        summary
+            .field("Blockhash", &self.blockhash)
+            .field("Encoding", "base64")
+            .field("Transaction", &self.transaction);
+
+        let mut signers = TableView::new("SIGNERS", ["Pubkey", "Signature"]);
+        for signer in &self.signers {
+            signers.row([
+                TableCell::new(&signer.pubkey),
+                match &signer.signature {
+                    Some(signature) => TableCell::new(signature).fg(Color::Green),
+                    None => TableCell::new("Missing").fg(Color::Yellow),
+                },
+            ]);
+        }
+
+        vec![summary, signers]
+    }
+
+    fn render_table(&self) {
+        for table in self.tables() {
+            table.print();
+        }
+
+        // Ready to paste into `--signer` on the machine that submits
+        let pairs: Vec<String> = self
+            .signers
+            .iter()
+            .filter_map(|signer| {
+                let signature = signer.signature.as_ref()?;
+                Some(format!("{}={signature}", signer.pubkey))
+            })
+            .collect();
+        if !pairs.is_empty() {
+            println!("{}", style("Signer pairs:").cyan().bold());
+            for pair in pairs {
+                println!("  {pair}");
+            }
+        }
+    }
+}
+
+/// The pubkeys that have to sign `tx`, in signature order.
+pub fn signer_keys(tx: &VersionedTransaction) -> &[Pubkey] {
+    let keys = tx.message.static_account_keys();
+    let required = tx.message.header().num_required_signatures as usize;
+    &keys[..required.min(keys.len())]
+}
+
+/// Signers of `tx` whose signature is still empty.
+pub fn missing_signers(tx: &VersionedTransaction) -> Vec<Pubkey> {
+    signer_keys(tx)
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| *pubkey)
+        .collect()
+}
+
+/// Puts `signature` in the slot of `pubkey`, after checking that it really
+/// signs this transaction's message.
+pub fn add_signature(
+    tx: &mut VersionedTransaction,
+    PubkeySignature { pubkey, signature }: PubkeySignature,
+) -> anyhow::Result<()> {
+    let position = signer_keys(tx)
+        .iter()
+        .position(|key| *key == pubkey)
+        .ok_or_else(|| anyhow!("{pubkey} is not a signer of this transaction"))?;
+
+    if !signature.verify(pubkey.as_ref(), &tx.message.serialize()) {
+        bail!("The signature for {pubkey} does not match this transaction");
+    }
+
+    tx.signatures[position] = signature;
+    Ok(())
+}
+
+/// Signs `tx` with a keypair available on this machine.
+pub fn sign_with(tx: &mut VersionedTransaction, signer: &dyn Signer) -> anyhow::Result<()> {
+    let signature = signer.try_sign_message(&tx.message.serialize())?;
+    add_signature(
+        tx,
+        PubkeySignature {
+            pubkey: signer.try_pubkey()?,
+            signature,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_hash::Hash, solana_keypair::Keypair, solana_message::Message,
+        solana_signer::null_signer::NullSigner, solana_system_interface::instruction::transfer,
+        solana_transaction::Transaction,
+    };
+
+    fn partially_signed(payer: &Keypair, authority: &Pubkey) -> VersionedTransaction {
+        let ix = transfer(authority, &Pubkey::new_unique(), 1);
+        let message = Message::new(&[ix], Some(&payer.pubkey()));
+        let mut tx = Transaction::new_unsigned(message);
+        tx.try_partial_sign(
+            &[payer as &dyn Signer, &NullSigner::new(authority)],
+            Hash::new_unique(),
+        )
+        .expect("partial signing succeeds");
+        tx.into()
+    }
+
+    #[test]
+    fn test_pubkey_signature_from_str() {
+        let keypair = Keypair::new();
+        let signature = keypair.sign_message(b"scilla");
+        let pair: PubkeySignature = format!("{}={signature}", keypair.pubkey())
+            .parse()
+            .expect("valid pair");
+        assert_eq!(pair.pubkey, keypair.pubkey());
+        assert_eq!(pair.signature, signature);
+
+        assert!("no-separator".parse::<PubkeySignature>().is_err());
+        assert!(
+            format!("{}=bad", keypair.pubkey())
+                .parse::<PubkeySignature>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_collect_missing_signature() {
+        let payer = Keypair::new();
+        let authority = Keypair::new();
+        let mut tx = partially_signed(&payer, &authority.pubkey());
+        assert_eq!(missing_signers(&tx), [authority.pubkey()]);
+
+        // Round-trips through the exported form
+        let output = SignedTransactionOutput::from(&tx);
+        assert!(output.signers[0].signature.is_some());
+        assert!(output.signers[1].signature.is_none());
+
+        let signature = authority.sign_message(&tx.message.serialize());
+        add_signature(
+            &mut tx,
+            PubkeySignature {
+                pubkey: authority.pubkey(),
+                signature,
+            },
+        )
+        .expect("signature matches");
+        assert!(missing_signers(&tx).is_empty());
+        let message = tx.message.serialize();
+        assert!(
+            signer_keys(&tx)
+                .iter()
+                .zip(&tx.signatures)
+                .all(|(pubkey, signature)| signature.verify(pubkey.as_ref(), &message))
+        );
+    }
+
+    #[test]
+    fn test_reject_foreign_signatures() {
+        let payer = Keypair::new();
+        let authority = Keypair::new();
+        let mut tx = partially_signed(&payer, &authority.pubkey());
+
+        let stranger = Keypair::new();
+        assert!(sign_with(&mut tx, &stranger).is_err());
+
+        // Right key, wrong message
+        let signature = authority.sign_message(b"something else");
+        let pair = PubkeySignature {
+            pubkey: authority.pubkey(),
+            signature,
+        };
+        assert!(add_signature(&mut tx, pair).is_err());
+        assert_eq!(missing_signers(&tx), [authority.pubkey()]);
+
+        sign_with(&mut tx, &authority).expect("authority signs");
+        assert!(missing_signers(&tx).is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+use {
+    crate::{
+        address_book::{AddressLabels, with_label},
+        constants::LAMPORTS_PER_SOL,
+        context::ScillaContext,
+        misc::helpers::lamports_to_sol,
+        output::{Color, CommandOutput, TableCell, TableView},
+        prompt::prompt_confirmation,
+        ui::suspend_spinner,
+    },
+    anyhow::Context,
+    inquire::InquireError,
+    serde::Serialize,
+    serde_json::Value,
+    solana_account_decoder::UiAccountEncoding,
+    solana_message::{AccountKeys, Message, compiled_instruction::CompiledInstruction},
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::config::{
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+    },
+    solana_transaction::Transaction,
+    tokio::try_join,
+};
+
+/// What a transaction is about to do, shown before anything is signed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionPreview {
+    pub fee_payer: String,
+    pub fee_lamports: u64,
+    pub signers: Vec<String>,
+    pub writable_accounts: Vec<String>,
+    pub instructions: Vec<InstructionPreview>,
+    pub balance_changes: Vec<BalanceChange>,
+    pub units_consumed: Option<u64>,
+    /// Why the simulation failed, if it did
+    pub simulation_error: Option<String>,
+    #[serde(skip)]
+    pub labels: AddressLabels,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionPreview {
+    pub program: String,
+    pub instruction: String,
+    pub details: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChange {
+    pub account: String,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl BalanceChange {
+    fn delta(&self) -> i128 {
+        self.after as i128 - self.before as i128
+    }
+}
+
+impl CommandOutput for TransactionPreview {
+    fn tables(&self) -> Vec<TableView> {
+        let label = |address: &String| with_label(address, &self.labels);
+        let list =
+            |addresses: &[String]| addresses.iter().map(label).collect::<Vec<_>>().join("\n");
+
+        let mut summary = TableView::key_value("TRANSACTION PREVIEW");
+        summary
+            .field("Fee Payer", label(&self.fee_payer))
+            .field("Fee", format!("{} SOL", lamports_to_sol(self.fee_lamports)))
+            .field("Signers", list(&self.signers))
+            .field("Writable Accounts", list(&self.writable_accounts))
+            .field(
+                "Simulation",
+                match &self.simulation_error {
+                    Some(err) => TableCell::new(format!("Failed: {err}")).fg(Color::Red),
+                    None => TableCell::new("Success").fg(Color::Green),
+                },
+            );
+        if let Some(units) = self.units_consumed {
+            summary.field("Compute Units", units);
+        }
+
+        let mut instructions =
+            TableView::new("INSTRUCTIONS", ["#", "Program", "Instruction", "Details"]);
+        for (idx, ix) in self.instructions.iter().enumerate() {
+            instructions.row([
+                TableCell::new(idx + 1),
+                TableCell::new(&ix.program),
+                TableCell::new(&ix.instruction),
+                TableCell::new(ix.details.join("\n")),
+            ]);
+        }
+
+        let mut balances = TableView::new(
+            "BALANCE CHANGES (SOL)",
+            ["Account", "Before", "After", "Change"],
+        );
+        for change in self.balance_changes.iter().filter(|c| c.delta() != 0) {
+            let delta = change.delta();
+            let color = if delta > 0 { Color::Green } else { Color::Red };
+            balances.row([
+                TableCell::new(label(&change.account)),
+                TableCell::new(lamports_to_sol(change.before)),
+                TableCell::new(lamports_to_sol(change.after)),
+                TableCell::new(format!("{:+}", delta as f64 / LAMPORTS_PER_SOL as f64)).fg(color),
+            ]);
+        }
+
+        [summary, instructions, balances]
+            .into_iter()
+            .filter(|table| !table.is_empty())
+            .collect()
+    }
+}
+
+/// Decodes `message` and simulates it against the current cluster state. The
+/// message is not signed, so nothing can be sent from here.
+pub async fn preview_transaction(
+    ctx: &ScillaContext,
+    message: &Message,
+) -> anyhow::Result<TransactionPreview> {
+    let mut message = message.clone();
+    message.recent_blockhash = ctx.rpc().get_latest_blockhash().await?;
+
+    let keys = &message.account_keys;
+    let signers: Vec<Pubkey> = keys
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| message.is_signer(*idx))
+        .map(|(_, key)| *key)
+        .collect();
+    let writable: Vec<Pubkey> = keys
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| message.is_maybe_writable(*idx, None))
+        .map(|(_, key)| *key)
+        .collect();
+
+    let tx = Transaction::new_unsigned(message.clone());
+    let simulation_config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        commitment: Some(ctx.rpc().commitment()),
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: writable.iter().map(ToString::to_string).collect(),
+        }),
+        ..Default::default()
+    };
+
+    let (fee_lamports, before, simulation) = try_join!(
+        async {
+            ctx.rpc()
+                .get_fee_for_message(&message)
+                .await
+                .map_err(anyhow::Error::from)
+        },
+        async {
+            ctx.rpc()
+                .get_multiple_accounts(&writable)
+                .await
+                .map_err(anyhow::Error::from)
+        },
+        async {
+            ctx.rpc()
+                .simulate_transaction_with_config(&tx, simulation_config)
+                .await
+                .map_err(anyhow::Error::from)
+        },
+    )?;
+    let simulation = simulation.value;
+
+    // Post-simulation accounts are only returned when the simulation succeeds
+    let balance_changes = match &simulation.accounts {
+        Some(after) => writable
+            .iter()
+            .zip(before)
+            .zip(after)
+            .map(|((account, before), after)| BalanceChange {
+                account: account.to_string(),
+                before: before.map_or(0, |account| account.lamports),
+                after: after.as_ref().map_or(0, |account| account.lamports),
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let account_keys = AccountKeys::new(keys, None);
+    let instructions = message
+        .instructions
+        .iter()
+        .map(|ix| decode_instruction(&account_keys, ix))
+        .collect();
+
+    let signers: Vec<String> = signers.iter().map(ToString::to_string).collect();
+    let writable: Vec<String> = writable.iter().map(ToString::to_string).collect();
+    let labels = ctx
+        .address_book()
+        .labels(signers.iter().chain(&writable).map(String::as_str));
+
+    Ok(TransactionPreview {
+        fee_payer: keys[0].to_string(),
+        fee_lamports,
+        signers,
+        writable_accounts: writable,
+        instructions,
+        balance_changes,
+        units_consumed: simulation.units_consumed,
+        simulation_error: simulation.err.map(|err| err.to_string()),
+        labels,
+    })
+}
+
+/// Shows the preview of `message` and waits for an explicit confirmation.
+/// Declining cancels the command the same way Esc does. The preview goes to
+/// stderr so that JSON or CSV results on stdout can still be piped.
+pub async fn confirm_transaction(ctx: &ScillaContext, message: &Message) -> anyhow::Result<()> {
+    let preview = preview_transaction(ctx, message).await?;
+
+    let confirmed = suspend_spinner(|| {
+        for table in preview.tables() {
+            table.eprint();
+        }
+        prompt_confirmation("Sign and send this transaction?")
+    })
+    .context("Could not confirm the transaction. Pass --skip-preview to send without confirming")?;
+    if !confirmed {
+        return Err(InquireError::OperationCanceled.into());
+    }
+
+    Ok(())
+}
+
+/// Decodes instructions of the programs Scilla knows about (system, stake,
+/// vote, SPL token, ...) into their name and arguments.
+fn decode_instruction(account_keys: &AccountKeys, ix: &CompiledInstruction) -> InstructionPreview {
+    let program_id = account_keys[ix.program_id_index as usize];
+    if program_id == solana_compute_budget_interface::id()
+        && let Some(preview) = decode_compute_budget(&ix.data)
+    {
+        return preview;
+    }
+
+    // The same parsers the RPC uses for `jsonParsed`, now flagged as unstable
+    #[allow(deprecated)]
+    let parsed =
+        solana_transaction_status::parse_instruction::parse(&program_id, ix, account_keys, None);
+    let Ok(parsed) = parsed else {
+        return InstructionPreview {
+            program: program_id.to_string(),
+            instruction: "Unknown".to_string(),
+            details: vec![format!(
+                "{} accounts, {} bytes of data",
+                ix.accounts.len(),
+                ix.data.len()
+            )],
+        };
+    };
+
+    let (instruction, details) = match parsed.parsed {
+        Value::Object(mut fields) => {
+            let instruction = match fields.remove("type") {
+                Some(Value::String(name)) => name,
+                _ => "-".to_string(),
+            };
+            let details = match fields.remove("info") {
+                Some(Value::Object(info)) => info
+                    .into_iter()
+                    .map(|(field, value)| format!("{field}: {}", display_value(value)))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            (instruction, details)
+        }
+        // Memos are parsed into their text
+        value => ("memo".to_string(), vec![display_value(value)]),
+    };
+
+    InstructionPreview {
+        program: parsed.program,
+        instruction,
+        details,
+    }
+}
+
+/// The RPC parsers skip the compute budget program, so decode the two
+/// instructions Scilla adds itself.
+fn decode_compute_budget(data: &[u8]) -> Option<InstructionPreview> {
+    let (instruction, detail) = match data {
+        [2, units @ ..] => (
+            "setComputeUnitLimit",
+            format!("units: {}", u32::from_le_bytes(units.try_into().ok()?)),
+        ),
+        [3, price @ ..] => (
+            "setComputeUnitPrice",
+            format!(
+                "microLamports: {}",
+                u64::from_le_bytes(price.try_into().ok()?)
+            ),
+        ),
+        _ => return None,
+    };
+
+    Some(InstructionPreview {
+        program: "compute-budget".to_string(),
+        instruction: instruction.to_string(),
+        details: vec![detail],
+    })
+}
+
+fn display_value(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_compute_budget_interface::ComputeBudgetInstruction,
+        solana_keypair::{Keypair, Signer},
+        solana_system_interface::instruction::transfer,
+    };
+
+    #[test]
+    fn test_decode_system_transfer() {
+        let from = Keypair::new().pubkey();
+        let to = Pubkey::new_unique();
+        let message = Message::new(&[transfer(&from, &to, 1_500_000_000)], Some(&from));
+        let account_keys = AccountKeys::new(&message.account_keys, None);
+
+        let decoded = decode_instruction(&account_keys, &message.instructions[0]);
+        assert_eq!(decoded.program, "system");
+        assert_eq!(decoded.instruction, "transfer");
+        assert!(
+            decoded
+                .details
+                .contains(&"lamports: 1500000000".to_string())
+        );
+        assert!(decoded.details.contains(&format!("destination: {to}")));
+    }
+
+    #[test]
+    fn test_decode_compute_budget() {
+        let payer = Pubkey::new_unique();
+        let message = Message::new(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(220_000),
+                ComputeBudgetInstruction::set_compute_unit_price(5_000),
+            ],
+            Some(&payer),
+        );
+        let account_keys = AccountKeys::new(&message.account_keys, None);
+
+        let limit = decode_instruction(&account_keys, &message.instructions[0]);
+        assert_eq!(limit.program, "compute-budget");
+        assert_eq!(limit.instruction, "setComputeUnitLimit");
+        assert_eq!(limit.details, ["units: 220000"]);
+
+        let price = decode_instruction(&account_keys, &message.instructions[1]);
+        assert_eq!(price.instruction, "setComputeUnitPrice");
+        assert_eq!(price.details, ["microLamports: 5000"]);
+    }
+
+    #[test]
+    fn test_decode_unknown_program() {
+        let program = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let ix = solana_instruction::Instruction::new_with_bytes(program, &[1, 2, 3], vec![]);
+        let message = Message::new(&[ix], Some(&payer));
+        let account_keys = AccountKeys::new(&message.account_keys, None);
+
+        let decoded = decode_instruction(&account_keys, &message.instructions[0]);
+        assert_eq!(decoded.program, program.to_string());
+        assert_eq!(decoded.instruction, "Unknown");
+        assert_eq!(decoded.details, ["0 accounts, 3 bytes of data"]);
+    }
+}
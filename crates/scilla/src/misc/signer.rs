@@ -0,0 +1,391 @@
+use {
+    crate::{
+        commands::keypair::{derive_keypair, parse_keypair, prompt_passphrase, prompt_seed_phrase},
+        config::expand_tilde,
+        misc::helpers::read_keypair_from_path,
+        ui::suspend_spinner,
+    },
+    anyhow::{Context, anyhow, bail},
+    base64::{Engine, engine::general_purpose::STANDARD},
+    serde::{Deserialize, Serialize},
+    solana_derivation_path::DerivationPath,
+    solana_keypair::{Keypair, Signature, Signer},
+    solana_pubkey::Pubkey,
+    solana_signer::SignerError,
+    std::{
+        env, fmt,
+        io::{self, IsTerminal, Read, Write},
+        path::{Path, PathBuf},
+        process::{Command, Stdio},
+        str::FromStr,
+        sync::Mutex,
+    },
+};
+
+/// Stdin can only be read once, so the keypair piped in is kept for every
+/// `stdin:` signer of the session.
+static STDIN_KEYPAIR: Mutex<Option<String>> = Mutex::new(None);
+
+/// Where a signer comes from. Accepted anywhere a keypair path is asked for:
+///
+/// - `file:PATH`, or just `PATH`: a keypair file, plain or encrypted
+/// - `prompt:`: a seed phrase typed in, optionally with a derivation path as in
+///   `prompt:?key=0/0`
+/// - `stdin:`: a keypair piped into Scilla
+/// - `env:VAR`: a keypair held in an environment variable
+/// - `exec:COMMAND`: an external signer, see [`ExecSigner`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignerSource {
+    File(PathBuf),
+    Prompt(Option<DerivationPath>),
+    Stdin,
+    Env(String),
+    Exec(String),
+}
+
+impl FromStr for SignerSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let Some((scheme, rest)) = s.split_once(':') else {
+            return Ok(SignerSource::File(expand_tilde(s)));
+        };
+        // `file://path` and `file:path` mean the same
+        let rest = rest.strip_prefix("//").unwrap_or(rest);
+
+        match scheme {
+            "file" => Ok(SignerSource::File(expand_tilde(rest))),
+            "prompt" => {
+                let derivation_path = match rest.strip_prefix('?') {
+                    Some(query) => {
+                        let key = query
+                            .strip_prefix("key=")
+                            .ok_or_else(|| anyhow!("Expected prompt:?key=PATH, got {s}"))?;
+                        Some(
+                            DerivationPath::from_key_str(key)
+                                .map_err(|e| anyhow!("Invalid derivation path {key}: {e}"))?,
+                        )
+                    }
+                    None if rest.is_empty() => None,
+                    None => bail!("Expected prompt: or prompt:?key=PATH, got {s}"),
+                };
+                Ok(SignerSource::Prompt(derivation_path))
+            }
+            "stdin" if rest.is_empty() => Ok(SignerSource::Stdin),
+            "env" if !rest.is_empty() => Ok(SignerSource::Env(rest.to_string())),
+            "exec" if !rest.trim().is_empty() => Ok(SignerSource::Exec(rest.trim().to_string())),
+            "stdin" | "env" | "exec" => bail!("Invalid signer URI: {s}"),
+            // Anything else is a path that happens to hold a colon
+            _ => Ok(SignerSource::File(expand_tilde(s))),
+        }
+    }
+}
+
+impl fmt::Display for SignerSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerSource::File(path) => write!(f, "{}", path.display()),
+            SignerSource::Prompt(None) => f.write_str("prompt:"),
+            SignerSource::Prompt(Some(path)) => write!(f, "prompt:{}", path.get_query()),
+            SignerSource::Stdin => f.write_str("stdin:"),
+            SignerSource::Env(var) => write!(f, "env:{var}"),
+            SignerSource::Exec(command) => write!(f, "exec:{command}"),
+        }
+    }
+}
+
+impl SignerSource {
+    /// Parses a keypair path as given to Scilla, which may be a signer URI.
+    pub fn parse(path: &Path) -> anyhow::Result<Self> {
+        path.to_string_lossy().parse()
+    }
+
+    /// The local file holding the key, if the signer is a keypair file.
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            SignerSource::File(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn load(&self) -> anyhow::Result<Box<dyn Signer>> {
+        Ok(match self {
+            SignerSource::File(path) => Box::new(read_keypair_from_path(path)?),
+            SignerSource::Prompt(derivation_path) => {
+                let (seed_phrase, passphrase) = suspend_spinner(|| -> anyhow::Result<_> {
+                    Ok((prompt_seed_phrase()?, prompt_passphrase()?))
+                })?;
+                Box::new(derive_keypair(
+                    &seed_phrase,
+                    &passphrase,
+                    derivation_path.clone(),
+                )?)
+            }
+            SignerSource::Stdin => Box::new(parse_keypair(&read_stdin()?)?),
+            SignerSource::Env(var) => Box::new(env_keypair(var, |var| env::var(var))?),
+            SignerSource::Exec(command) => Box::new(ExecSigner::new(command)?),
+        })
+    }
+}
+
+/// Parses the keypair held in the environment variable `var`, as read by
+/// `lookup`.
+fn env_keypair(
+    var: &str,
+    lookup: impl FnOnce(&str) -> Result<String, env::VarError>,
+) -> anyhow::Result<Keypair> {
+    let value = lookup(var).with_context(|| format!("Cannot read ${var}"))?;
+    parse_keypair(&value).with_context(|| format!("Invalid keypair in ${var}"))
+}
+
+/// Reads the keypair at `path`, or from whichever signer URI it holds.
+pub fn load_signer<P: AsRef<Path>>(path: P) -> anyhow::Result<Box<dyn Signer>> {
+    SignerSource::parse(path.as_ref())?.load()
+}
+
+/// Everything piped into Scilla, read on first use.
+pub fn read_stdin() -> anyhow::Result<String> {
+    let mut cached = STDIN_KEYPAIR
+        .lock()
+        .map_err(|_| anyhow!("Failed to read stdin"))?;
+    if let Some(contents) = cached.as_ref() {
+        return Ok(contents.clone());
+    }
+
+    let mut stdin = io::stdin();
+    if stdin.is_terminal() {
+        bail!("stdin: needs a keypair piped into Scilla, e.g. `cat id.json | scilla ...`");
+    }
+    let mut contents = String::new();
+    stdin.read_to_string(&mut contents)?;
+
+    Ok(cached.insert(contents).clone())
+}
+
+/// Requests written to an external signer, one JSON object per line.
+#[derive(Debug, Serialize)]
+#[serde(tag = "method", rename_all = "camelCase")]
+enum ExecRequest {
+    Pubkey,
+    Sign {
+        /// The message to sign, base64 encoded
+        message: String,
+    },
+}
+
+/// The signer's answer, one JSON object on a line of its stdout.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecResponse {
+    pubkey: Option<String>,
+    signature: Option<String>,
+    error: Option<String>,
+}
+
+/// A signer run as an external program, e.g. a wrapper around a hardware
+/// wallet or a remote signing service.
+///
+/// The program is started once per request, gets a JSON line on stdin and
+/// answers with a JSON line on stdout:
+///
+/// - `{"method":"pubkey"}` → `{"pubkey":"<base58>"}`
+/// - `{"method":"sign","message":"<base64>"}` → `{"signature":"<base58>"}`
+///
+/// Either can be answered with `{"error":"<reason>"}` instead. Its stderr is
+/// passed through, so the program can talk to the user.
+#[derive(Debug)]
+pub struct ExecSigner {
+    program: String,
+    args: Vec<String>,
+    pubkey: Pubkey,
+}
+
+impl ExecSigner {
+    /// Starts `command`, split into a program and its arguments the way a
+    /// POSIX shell would, quotes and escapes included, to ask for its pubkey.
+    /// Nothing else of the shell applies: no variables, globs or pipes.
+    pub fn new(command: &str) -> anyhow::Result<Self> {
+        let mut words = shell_words::split(command)
+            .with_context(|| format!("Invalid exec: command {command}"))?
+            .into_iter();
+        let program = words
+            .next()
+            .ok_or_else(|| anyhow!("exec: needs a command to run"))?;
+        let mut signer = Self {
+            program,
+            args: words.collect(),
+            pubkey: Pubkey::default(),
+        };
+
+        let pubkey = signer
+            .request(&ExecRequest::Pubkey)?
+            .pubkey
+            .ok_or_else(|| anyhow!("{} answered without a pubkey", signer.program))?;
+        signer.pubkey = pubkey
+            .parse()
+            .map_err(|_| anyhow!("{} answered with an invalid pubkey", signer.program))?;
+
+        Ok(signer)
+    }
+
+    fn request(&self, request: &ExecRequest) -> anyhow::Result<ExecResponse> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", self.program))?;
+
+        {
+            let mut stdin = child.stdin.take().expect("stdin is piped");
+            writeln!(stdin, "{}", serde_json::to_string(request)?)?;
+        }
+        let output = suspend_spinner(|| child.wait_with_output())?;
+        if !output.status.success() {
+            bail!("{} exited with {}", self.program, output.status);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .ok_or_else(|| anyhow!("{} did not answer", self.program))?;
+        let response: ExecResponse = serde_json::from_str(line)
+            .with_context(|| format!("{} answered with invalid JSON", self.program))?;
+        if let Some(error) = response.error {
+            bail!("{}: {error}", self.program);
+        }
+
+        Ok(response)
+    }
+}
+
+impl Signer for ExecSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let response = self
+            .request(&ExecRequest::Sign {
+                message: STANDARD.encode(message),
+            })
+            .map_err(|e| SignerError::Connection(format!("{e:#}")))?;
+
+        let signature: Signature = response
+            .signature
+            .ok_or_else(|| SignerError::Protocol("answered without a signature".to_string()))?
+            .parse()
+            .map_err(|_| SignerError::Protocol("answered with an invalid signature".to_string()))?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::KeypairPubkeyMismatch);
+        }
+
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_parse_signer_source() {
+        assert_eq!(
+            "id.json".parse::<SignerSource>().unwrap(),
+            SignerSource::File(PathBuf::from("id.json"))
+        );
+        assert_eq!(
+            "file:///tmp/id.json".parse::<SignerSource>().unwrap(),
+            SignerSource::File(PathBuf::from("/tmp/id.json"))
+        );
+        assert_eq!(
+            "prompt:".parse::<SignerSource>().unwrap(),
+            SignerSource::Prompt(None)
+        );
+        assert_eq!(
+            "prompt://?key=0/0".parse::<SignerSource>().unwrap(),
+            SignerSource::Prompt(Some(DerivationPath::from_key_str("0/0").unwrap()))
+        );
+        assert_eq!(
+            "stdin:".parse::<SignerSource>().unwrap(),
+            SignerSource::Stdin
+        );
+        assert_eq!(
+            "env:PAYER".parse::<SignerSource>().unwrap(),
+            SignerSource::Env("PAYER".to_string())
+        );
+        assert_eq!(
+            "exec:my-signer --slot 1".parse::<SignerSource>().unwrap(),
+            SignerSource::Exec("my-signer --slot 1".to_string())
+        );
+
+        assert!("env:".parse::<SignerSource>().is_err());
+        assert!("prompt:?key=nope".parse::<SignerSource>().is_err());
+
+        // Round-trips through its display form
+        for uri in [
+            "prompt:",
+            "prompt:?key=0/1",
+            "stdin:",
+            "env:PAYER",
+            "exec:a b",
+        ] {
+            let source = uri.parse::<SignerSource>().unwrap();
+            assert_eq!(source.to_string().parse::<SignerSource>().unwrap(), source);
+        }
+    }
+
+    #[test]
+    fn test_load_from_env() {
+        let keypair = Keypair::new();
+        let lookup = |var: &str| match var {
+            "PAYER" => Ok(keypair.to_base58_string()),
+            "INVALID" => Ok("not a keypair".to_string()),
+            _ => Err(env::VarError::NotPresent),
+        };
+
+        let loaded = env_keypair("PAYER", lookup).unwrap();
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+        assert!(env_keypair("MISSING", lookup).is_err());
+        assert!(env_keypair("INVALID", lookup).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_signer() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let keypair = Keypair::new();
+        let message = b"scilla";
+        let signature = keypair.sign_message(message);
+
+        // Answers like a real signer would for this one message. The space in
+        // its name has to be quoted in the command
+        let script = dir.path().join("my signer.sh");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nread request\ncase \"$request\" in\n  *pubkey*) echo \
+                 '{{\"pubkey\":\"{}\"}}' ;;\n  *{}*) echo '{{\"signature\":\"{signature}\"}}' \
+                 ;;\n  *) echo '{{\"error\":\"unknown message\"}}' ;;\nesac\n",
+                keypair.pubkey(),
+                STANDARD.encode(message),
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let signer = load_signer(format!("exec:'{}'", script.display())).unwrap();
+        assert_eq!(signer.pubkey(), keypair.pubkey());
+        assert_eq!(signer.try_sign_message(message).unwrap(), signature);
+        assert!(signer.try_sign_message(b"other").is_err());
+    }
+}
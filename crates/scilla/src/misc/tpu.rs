@@ -0,0 +1,141 @@
+use {
+    crate::context::ScillaContext,
+    anyhow::bail,
+    futures::{Stream, stream},
+    solana_commitment_config::CommitmentConfig,
+    solana_rpc_client::{nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction},
+    solana_signature::Signature,
+    solana_tpu_client_next::{
+        Client, ClientBuilder, TransactionSender,
+        node_address_service::{LeaderTpuCacheServiceConfig, NodeAddressService, SlotEvent},
+    },
+    std::{
+        net::UdpSocket,
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+    tokio::time::{interval, sleep},
+    tokio_util::sync::{CancellationToken, DropGuard},
+};
+
+/// How often the current slot is polled to follow the leader schedule.
+const SLOT_POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// How often the transaction is sent again while it is not confirmed.
+const RESEND_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the RPC is asked whether the transaction landed.
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Roughly how long a recent blockhash stays valid.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Leaders each transaction is sent to, starting with the current one.
+const LEADER_FANOUT: usize = 2;
+
+/// Sends transactions over QUIC to the TPU of the upcoming leaders, found
+/// through the configured RPC. Tracking the leaders and opening connections
+/// is costly, so one is started per command and shared by all of its
+/// transactions, see [`ScillaContext::tpu_sender`].
+pub struct TpuSender {
+    sender: TransactionSender,
+    client: Client,
+    // Stops the leader tracking and the connections however the sender goes
+    _cancel: DropGuard,
+}
+
+impl TpuSender {
+    pub async fn start(rpc: &RpcClient) -> anyhow::Result<Self> {
+        let rpc = Arc::new(RpcClient::new_with_commitment(rpc.url(), rpc.commitment()));
+        let cancel = CancellationToken::new();
+        let leaders_cancel = cancel.child_token();
+        let client_cancel = cancel.child_token();
+        let cancel = cancel.drop_guard();
+
+        let leaders = NodeAddressService::run(
+            rpc.clone(),
+            slot_events(rpc),
+            LeaderTpuCacheServiceConfig::default(),
+            leaders_cancel,
+        )
+        .await?;
+        let (sender, client) = ClientBuilder::new(Box::new(leaders))
+            .cancel_token(client_cancel)
+            .bind_socket(UdpSocket::bind("0.0.0.0:0")?)
+            .leader_send_fanout(LEADER_FANOUT)
+            .build()?;
+
+        Ok(Self {
+            sender,
+            client,
+            _cancel: cancel,
+        })
+    }
+
+    /// Stops the scheduler first, which stops the leader tracking in turn.
+    pub async fn shutdown(self) -> anyhow::Result<()> {
+        drop(self.sender);
+        self.client.shutdown().await?;
+        Ok(())
+    }
+}
+
+/// Sends `tx` to the upcoming leaders and waits for the RPC to confirm it.
+/// The transaction is resent until it lands or its blockhash would have
+/// expired.
+pub async fn send_and_confirm_via_tpu(
+    ctx: &ScillaContext,
+    tx: &impl SerializableTransaction,
+) -> anyhow::Result<Signature> {
+    let sender = &ctx.tpu_sender().await?.sender;
+    let signature = *tx.get_signature();
+    let wire_transaction = bincode::serialize(tx)?;
+    let deadline = Instant::now() + CONFIRM_TIMEOUT;
+
+    let mut last_sent: Option<Instant> = None;
+    while Instant::now() < deadline {
+        if last_sent.is_none_or(|sent| sent.elapsed() >= RESEND_INTERVAL) {
+            sender
+                .send_transactions_in_batch(vec![wire_transaction.clone()])
+                .await?;
+            last_sent = Some(Instant::now());
+        }
+
+        sleep(CONFIRM_POLL_INTERVAL).await;
+
+        if let Some(status) = ctx
+            .rpc()
+            .get_signature_status_with_commitment(&signature, ctx.rpc().commitment())
+            .await?
+        {
+            status?;
+            return Ok(signature);
+        }
+    }
+
+    bail!(
+        "Transaction {signature} was not confirmed within {}s. It may still land; check it with \
+         Transaction > Check Transaction Confirmation",
+        CONFIRM_TIMEOUT.as_secs()
+    )
+}
+
+/// Slot updates for the leader tracking, polled from the RPC since Scilla
+/// only knows an HTTP endpoint.
+fn slot_events(rpc: Arc<RpcClient>) -> impl Stream<Item = SlotEvent> + Send + 'static {
+    stream::unfold(
+        (rpc, interval(SLOT_POLL_INTERVAL)),
+        |(rpc, mut ticks)| async move {
+            loop {
+                ticks.tick().await;
+                // A missed poll only delays the next leader update
+                if let Ok(slot) = rpc
+                    .get_slot_with_commitment(CommitmentConfig::processed())
+                    .await
+                {
+                    return Some((SlotEvent::Start(slot), (rpc, ticks)));
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,439 @@
+pub use comfy_table::Color;
+use {
+    clap::ValueEnum,
+    comfy_table::{Attribute, Cell, Table, presets::UTF8_FULL},
+    console::style,
+    serde::Serialize,
+    serde_json::Value,
+    std::fmt,
+};
+
+/// How command results are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable tables
+    #[default]
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// Single-line JSON
+    JsonCompact,
+    /// Comma-separated values, one block per table
+    Csv,
+    /// GitHub-flavored Markdown tables
+    Markdown,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            OutputFormat::JsonCompact => "json-compact",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Markdown => "markdown",
+        };
+        f.write_str(label)
+    }
+}
+
+impl OutputFormat {
+    pub fn render<T: CommandOutput>(&self, output: &T) -> anyhow::Result<()> {
+        match self {
+            OutputFormat::Table => output.render_table(),
+            OutputFormat::Json | OutputFormat::JsonCompact => {
+                println!("{}", self.format(output)?)
+            }
+            // Already newline-terminated
+            OutputFormat::Csv | OutputFormat::Markdown => print!("{}", self.format(output)?),
+        }
+
+        Ok(())
+    }
+
+    /// Formats `output` as plain text without any terminal styling, e.g. for
+    /// writing it to a file. Falls back to CSV for [`OutputFormat::Table`].
+    pub fn format<T: CommandOutput>(&self, output: &T) -> anyhow::Result<String> {
+        let formatted = match self {
+            OutputFormat::Json => serde_json::to_string_pretty(output)?,
+            OutputFormat::JsonCompact => serde_json::to_string(output)?,
+            OutputFormat::Table | OutputFormat::Csv => {
+                join_tables(output_tables(output)?.iter().map(TableView::to_csv))
+            }
+            OutputFormat::Markdown => {
+                join_tables(output_tables(output)?.iter().map(TableView::to_markdown))
+            }
+        };
+
+        Ok(formatted)
+    }
+
+    /// File extension used when exporting in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Table | OutputFormat::Csv => "csv",
+            OutputFormat::Json | OutputFormat::JsonCompact => "json",
+            OutputFormat::Markdown => "md",
+        }
+    }
+}
+
+fn join_tables(tables: impl Iterator<Item = String>) -> String {
+    tables.collect::<Vec<_>>().join("\n")
+}
+
+/// Outputs that are just a message have no tables of their own, so they are
+/// exported as a field/value table of their JSON representation.
+fn output_tables<T: CommandOutput>(output: &T) -> anyhow::Result<Vec<TableView>> {
+    let tables = output.tables();
+    if !tables.is_empty() {
+        return Ok(tables);
+    }
+
+    let mut table = TableView::key_value(None);
+    if let Value::Object(fields) = serde_json::to_value(output)? {
+        for (field, value) in fields {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            table.field(field, value);
+        }
+    }
+
+    Ok(vec![table])
+}
+
+/// Flattens the fields of a JSON object into label/value pairs, nested
+/// objects joined with ` › `.
+pub fn flatten_fields(prefix: Option<&str>, value: &Value, fields: &mut Vec<(String, String)>) {
+    let Value::Object(map) = value else {
+        return;
+    };
+
+    for (key, value) in map {
+        let field = match prefix {
+            Some(prefix) => format!("{prefix} › {}", humanize(key)),
+            None => humanize(key),
+        };
+        match value {
+            Value::Object(_) => flatten_fields(Some(&field), value, fields),
+            Value::Null => fields.push((field, "None".to_string())),
+            Value::String(text) => fields.push((field, text.clone())),
+            other => fields.push((field, other.to_string())),
+        }
+    }
+}
+
+/// Turns a camelCase key into a label, e.g. `withheldAmount` into
+/// `Withheld Amount`.
+pub fn humanize(key: &str) -> String {
+    let mut label = String::with_capacity(key.len() + 4);
+    for (idx, ch) in key.chars().enumerate() {
+        if idx == 0 {
+            label.extend(ch.to_uppercase());
+        } else {
+            if ch.is_uppercase() {
+                label.push(' ');
+            }
+            label.push(ch);
+        }
+    }
+    label
+}
+
+/// The result of a command. Serialized as-is for the JSON formats and laid out
+/// with [`CommandOutput::tables`] for the others.
+pub trait CommandOutput: Serialize {
+    /// Tabular views of the result, shared by the table, CSV and Markdown
+    /// renderers. Empty for results that are just a message.
+    fn tables(&self) -> Vec<TableView> {
+        Vec::new()
+    }
+
+    /// Prints the result for the [`OutputFormat::Table`] format. Outputs
+    /// without tables override this to print their message.
+    fn render_table(&self) {
+        for table in self.tables() {
+            table.print();
+        }
+    }
+
+    /// Whether the result shows secret key material, such as a seed phrase.
+    /// Such results are never offered for export to a file.
+    fn has_secrets(&self) -> bool {
+        false
+    }
+}
+
+/// A cell of a [`TableView`]. The color is only used when drawing to the
+/// terminal.
+#[derive(Debug, Clone)]
+pub struct TableCell {
+    text: String,
+    color: Option<Color>,
+}
+
+impl TableCell {
+    pub fn new(text: impl fmt::Display) -> Self {
+        Self {
+            text: text.to_string(),
+            color: None,
+        }
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl From<String> for TableCell {
+    fn from(text: String) -> Self {
+        Self { text, color: None }
+    }
+}
+
+impl From<&str> for TableCell {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&String> for TableCell {
+    fn from(text: &String) -> Self {
+        Self::new(text)
+    }
+}
+
+macro_rules! impl_table_cell_from_display {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for TableCell {
+                fn from(value: $ty) -> Self {
+                    Self::new(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_table_cell_from_display!(u8, u16, u32, u64, usize, i64, i128, f64, bool);
+
+/// A titled table that can be drawn to the terminal or exported as CSV or
+/// Markdown.
+#[derive(Debug, Clone)]
+pub struct TableView {
+    title: Option<String>,
+    header: Vec<String>,
+    rows: Vec<Vec<TableCell>>,
+}
+
+impl TableView {
+    pub fn new<I, S>(title: impl Into<Option<&'static str>>, header: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            title: title.into().map(str::to_string),
+            header: header.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// A two-column table with a `Field`/`Value` header.
+    pub fn key_value(title: impl Into<Option<&'static str>>) -> Self {
+        Self::new(title, ["Field", "Value"])
+    }
+
+    pub fn row<I, C>(&mut self, cells: I) -> &mut Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<TableCell>,
+    {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Adds a row to a [`TableView::key_value`] table.
+    pub fn field(&mut self, field: impl Into<String>, value: impl Into<TableCell>) -> &mut Self {
+        self.rows
+            .push(vec![TableCell::from(field.into()), value.into()]);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Draws the table to stdout, preceded by its title.
+    pub fn print(&self) {
+        if let Some(title) = &self.title {
+            println!("\n{}", style(title).green().bold());
+        }
+        println!("{}", self.to_comfy());
+    }
+
+    /// Draws the table to stderr, for views that must stay out of the
+    /// command's result, such as a transaction preview.
+    pub fn eprint(&self) {
+        if let Some(title) = &self.title {
+            eprintln!("\n{}", style(title).green().bold());
+        }
+        eprintln!("{}", self.to_comfy());
+    }
+
+    fn to_comfy(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(self.header.iter().map(|header| {
+                Cell::new(header)
+                    .add_attribute(Attribute::Bold)
+                    .fg(Color::Cyan)
+            }));
+
+        for row in &self.rows {
+            table.add_row(row.iter().map(|cell| match cell.color {
+                Some(color) => Cell::new(&cell.text).fg(color),
+                None => Cell::new(&cell.text),
+            }));
+        }
+
+        table
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for line in std::iter::once(self.header.iter().map(String::as_str).collect::<Vec<_>>())
+            .chain(
+                self.rows
+                    .iter()
+                    .map(|row| row.iter().map(|cell| cell.text.as_str()).collect()),
+            )
+        {
+            let line = line.into_iter().map(csv_escape).collect::<Vec<_>>();
+            csv.push_str(&line.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        if let Some(title) = &self.title {
+            markdown.push_str(&format!("### {title}\n\n"));
+        }
+
+        let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        markdown.push_str(&line(
+            self.header.iter().map(|h| markdown_escape(h)).collect(),
+        ));
+        markdown.push_str(&line(
+            self.header.iter().map(|_| "---".to_string()).collect(),
+        ));
+        for row in &self.rows {
+            markdown.push_str(&line(
+                row.iter().map(|cell| markdown_escape(&cell.text)).collect(),
+            ));
+        }
+        markdown
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Result of a command whose only interesting output is a transaction
+/// signature.
+#[derive(Debug, Serialize)]
+pub struct SignatureOutput {
+    #[serde(skip)]
+    label: &'static str,
+    pub signature: String,
+}
+
+impl SignatureOutput {
+    pub fn new(label: &'static str, signature: impl ToString) -> Self {
+        Self {
+            label,
+            signature: signature.to_string(),
+        }
+    }
+}
+
+impl CommandOutput for SignatureOutput {
+    fn render_table(&self) {
+        println!(
+            "{} {}",
+            style(self.label).green().bold(),
+            style(&self.signature).cyan()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_output_serializes_without_label() -> anyhow::Result<()> {
+        let output = SignatureOutput::new("Signature:", "abc");
+
+        assert_eq!(serde_json::to_string(&output)?, r#"{"signature":"abc"}"#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_humanize() {
+        assert_eq!(humanize("withheldAmount"), "Withheld Amount");
+        assert_eq!(humanize("epoch"), "Epoch");
+    }
+
+    #[test]
+    fn test_table_view_csv_escapes_fields() {
+        let mut table = TableView::new("TITLE", ["Name", "Note"]);
+        table
+            .row(["plain", "has, comma"])
+            .row(["quote \"x\"", "line\nbreak"]);
+
+        assert_eq!(
+            table.to_csv(),
+            "Name,Note\nplain,\"has, comma\"\n\"quote \"\"x\"\"\",\"line\nbreak\"\n"
+        );
+    }
+
+    #[test]
+    fn test_table_view_markdown() {
+        let mut table = TableView::key_value("STAKE");
+        table.field("Lamports", 42u64).field("Pipe", "a|b");
+
+        assert_eq!(
+            table.to_markdown(),
+            "### STAKE\n\n| Field | Value |\n| --- | --- |\n| Lamports | 42 |\n| Pipe | a\\|b |\n"
+        );
+    }
+
+    #[test]
+    fn test_message_output_exports_as_key_value_table() -> anyhow::Result<()> {
+        let output = SignatureOutput::new("Signature:", "abc");
+
+        assert_eq!(
+            OutputFormat::Csv.format(&output)?,
+            "Field,Value\nsignature,abc\n"
+        );
+
+        Ok(())
+    }
+}
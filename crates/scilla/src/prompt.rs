@@ -1,199 +1,118 @@
 use {
     crate::{
-        commands::{
-            Command,
-            account::AccountCommand,
-            cluster::ClusterCommand,
-            config::ConfigCommand,
-            main_command::MainCommand,
-            navigation::NavigationTarget,
-            program::{ProgramCommand, ProgramShared},
-            stake::StakeCommand,
-            transaction::TransactionCommand,
-            vote::VoteCommand,
-        },
+        commands::navigation::{Menu, PaletteEntry},
         constants::{DEVNET_RPC, MAINNET_RPC, TESTNET_RPC},
         context::ScillaContext,
+        history::{self, HistoryKind},
+        misc::{
+            helpers::short_pubkey,
+            keystore::{Keystore, unlock_keystore},
+            signer::SignerSource,
+        },
+        output::OutputFormat,
         ui::print_error,
     },
-    console::style,
-    inquire::{Confirm, InquireError, Select, Text},
+    anyhow::{anyhow, bail},
+    inquire::{
+        Autocomplete, Confirm, CustomUserError, InquireError, Select, Text,
+        autocompletion::Replacement,
+    },
+    solana_pubkey::Pubkey,
     solana_transaction_status::UiTransactionEncoding,
-    std::{fmt::Display, path::PathBuf, process::exit, str::FromStr},
+    std::{fmt::Display, path::PathBuf, str::FromStr},
 };
-pub fn prompt_main_section() -> anyhow::Result<impl Command> {
-    let command = Select::new(
-        "Choose a command group:",
-        vec![
-            MainCommand::Account,
-            MainCommand::Cluster,
-            MainCommand::Stake,
-            MainCommand::Program,
-            MainCommand::Vote,
-            MainCommand::Transaction,
-            MainCommand::ScillaConfig,
-            MainCommand::Exit,
-        ],
-    )
-    .prompt()?;
-
-    Ok(command)
-}
-
-pub fn prompt_account_section() -> anyhow::Result<AccountCommand> {
-    let choice = Select::new(
-        "Account Command:",
-        vec![
-            AccountCommand::FetchAccount,
-            AccountCommand::Balance,
-            AccountCommand::Transfer,
-            AccountCommand::Airdrop,
-            AccountCommand::LargestAccounts,
-            AccountCommand::NonceAccount,
-            AccountCommand::Rent,
-            AccountCommand::GoBack,
-        ],
-    )
-    .with_page_size(10)
-    .prompt()?;
-
-    Ok(choice)
-}
-
-pub fn prompt_cluster_section() -> anyhow::Result<ClusterCommand> {
-    let choice = Select::new(
-        "Cluster Command:",
-        vec![
-            ClusterCommand::EpochInfo,
-            ClusterCommand::CurrentSlot,
-            ClusterCommand::BlockHeight,
-            ClusterCommand::BlockTime,
-            ClusterCommand::Validators,
-            ClusterCommand::ClusterVersion,
-            ClusterCommand::SupplyInfo,
-            ClusterCommand::Inflation,
-            ClusterCommand::GoBack,
-        ],
-    )
-    .prompt()?;
-
-    Ok(choice)
-}
-
-pub fn prompt_stake_section() -> anyhow::Result<StakeCommand> {
-    let choice = Select::new(
-        "Stake Command:",
-        vec![
-            StakeCommand::Create,
-            StakeCommand::Delegate,
-            StakeCommand::Deactivate,
-            StakeCommand::Withdraw,
-            StakeCommand::Merge,
-            StakeCommand::Split,
-            StakeCommand::Show,
-            StakeCommand::History,
-            StakeCommand::GoBack,
-        ],
-    )
-    .prompt()?;
-
-    Ok(choice)
-}
-
-pub fn prompt_program_section() -> anyhow::Result<ProgramCommand> {
-    let choice = Select::new(
-        "Program Command:",
-        vec![
-            ProgramCommand::ProgramLegacy,
-            ProgramCommand::ProgramV4,
-            ProgramCommand::GoBack,
-        ],
-    )
-    .with_page_size(10)
-    .prompt()?;
-
-    Ok(choice)
-}
 
-pub fn prompt_program_section_shared() -> anyhow::Result<ProgramShared> {
-    let choice = Select::new(
-        "Program Action:",
-        vec![
-            ProgramShared::Deploy,
-            ProgramShared::Upgrade,
-            ProgramShared::Build,
-            ProgramShared::Close,
-            ProgramShared::Extend,
-            ProgramShared::GoBack,
-        ],
-    )
-    .with_page_size(10)
-    .prompt()?;
-
-    Ok(choice)
-}
-
-pub fn prompt_vote_section() -> anyhow::Result<VoteCommand> {
-    let choice = Select::new(
-        "Vote Command:",
-        vec![
-            VoteCommand::CreateVoteAccount,
-            VoteCommand::AuthorizeVoter,
-            VoteCommand::WithdrawFromVoteAccount,
-            VoteCommand::ShowVoteAccount,
-            VoteCommand::CloseVoteAccount,
-            VoteCommand::GoBack,
-        ],
-    )
-    .prompt()?;
-
-    Ok(choice)
-}
-
-pub fn prompt_transaction_section() -> anyhow::Result<TransactionCommand> {
-    let choice = Select::new(
-        "Transaction Command:",
-        vec![
-            TransactionCommand::CheckConfirmation,
-            TransactionCommand::FetchStatus,
-            TransactionCommand::FetchTransaction,
-            TransactionCommand::SendTransaction,
-            TransactionCommand::SimulateTransaction,
-            TransactionCommand::GoBack,
-        ],
-    )
-    .prompt()?;
-
-    Ok(choice)
+/// What was picked from a menu shown by [`prompt_menu`].
+pub enum MenuChoice {
+    Entry(usize),
+    /// Index into the palette passed to [`prompt_menu`].
+    Palette(usize),
+    Back,
+    Exit,
 }
 
-pub fn prompt_config_section() -> anyhow::Result<ConfigCommand> {
-    let choice = Select::new(
-        "ScillaConfig Command:",
-        vec![
-            ConfigCommand::Show,
-            ConfigCommand::Edit,
-            ConfigCommand::GoBack,
-        ],
-    )
-    .prompt()?;
+/// Typing this at the start of the menu filter searches `palette` instead of
+/// the menu's own entries.
+const PALETTE_PREFIX: char = '/';
+
+/// Lists the entries of `menu` followed by "Go back", or "Exit" at the main
+/// menu. The prompt shows the active profile and where the menu sits in the
+/// tree, e.g. `[mainnet] Main › Program › V4:`.
+///
+/// Commands in `palette` stay hidden until the filter starts with `/`, and
+/// are then fuzzy-matched against the rest of the filter.
+pub fn prompt_menu(
+    ctx: &ScillaContext,
+    breadcrumb: &str,
+    menu: &Menu,
+    nested: bool,
+    palette: &[PaletteEntry],
+) -> anyhow::Result<MenuChoice> {
+    let mut labels: Vec<String> = menu.entries().iter().map(ToString::to_string).collect();
+    labels.push(if nested { "Go back" } else { "Exit" }.to_string());
+    let visible = labels.len();
+    labels.extend(palette.iter().map(|entry| entry.label.clone()));
+    let total = labels.len() as i64;
+
+    let scorer = |input: &str, option: &String, label: &str, index: usize| {
+        let query = match (input.strip_prefix(PALETTE_PREFIX), index < visible) {
+            (None, true) => input,
+            (Some(query), false) => query,
+            _ => return None,
+        };
+        // Equal scores keep menu order
+        Select::<String>::DEFAULT_SCORER(query, option, label, index)
+            .map(|score| score * total - index as i64)
+    };
+
+    let mut status = ctx.profile().to_string();
+    if ctx.is_dry_run() {
+        status.push_str(" · dry run");
+    }
+    if ctx.is_sign_only() {
+        status.push_str(" · sign only");
+    }
+    if ctx.durable_nonce().is_some() {
+        status.push_str(" · durable nonce");
+    }
+    let message = format!("[{status}] {breadcrumb}:");
+    let mut prompt = Select::new(&message, labels)
+        .with_page_size(visible)
+        .with_scorer(&scorer);
+    if !palette.is_empty() {
+        prompt = prompt.with_help_message(
+            "↑↓ to move, enter to select, type to filter, / to search all commands",
+        );
+    }
 
-    Ok(choice)
+    Ok(match prompt.raw_prompt()?.index {
+        index if index < menu.entries().len() => MenuChoice::Entry(index),
+        index if index >= visible => MenuChoice::Palette(index - visible),
+        _ if nested => MenuChoice::Back,
+        _ => MenuChoice::Exit,
+    })
 }
 
-pub fn prompt_input_data<T>(msg: &str) -> T
+/// Prompts until the input parses as `T`. Pubkeys, signatures and amounts are
+/// remembered and offered as suggestions the next time.
+pub fn prompt_input_data<T>(msg: &str) -> anyhow::Result<T>
 where
-    T: FromStr,
+    T: FromStr + 'static,
     T::Err: std::fmt::Display,
 {
+    let kind = HistoryKind::of::<T>();
+
     loop {
-        let input = match Text::new(msg).prompt() {
+        let input = match InputCompleter::for_kind(kind)
+            .attach(Text::new(msg))
+            .prompt()
+        {
             Ok(v) => v,
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
-                    exit(0);
+                    return Err(e.into());
                 }
+                InquireError::NotTTY => return Err(not_tty_error(msg)),
                 _ => {
                     print_error(format!("Invalid input: {e}. Please try again."));
                     continue;
@@ -202,24 +121,29 @@ where
         };
 
         match input.parse::<T>() {
-            Ok(value) => return value,
+            Ok(value) => {
+                if let Some(kind) = kind {
+                    history::record(kind, &input);
+                }
+                return Ok(value);
+            }
             Err(e) => print_error(format!("Parse error : {e}. Please try again.")),
         }
     }
 }
 
-pub fn prompt_select_data<T>(msg: &str, options: Vec<T>) -> T
+pub fn prompt_select_data<T>(msg: &str, options: Vec<T>) -> anyhow::Result<T>
 where
     T: Display + Clone,
 {
     loop {
         match Select::new(msg, options.clone()).prompt() {
-            Ok(v) => return v,
+            Ok(v) => return Ok(v),
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
-                    exit(0);
+                    return Err(e.into());
                 }
+                InquireError::NotTTY => return Err(not_tty_error(msg)),
                 _ => {
                     print_error(format!("Invalid Choice: {e}. Please try again."));
                     continue;
@@ -229,21 +153,26 @@ where
     }
 }
 
-pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
+pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> anyhow::Result<PathBuf> {
     let default_path = ctx.keypair_path().display().to_string();
 
     loop {
-        let input = match Text::new(msg)
-            .with_default(&default_path)
-            .with_help_message("Press Enter to use the default keypair")
-            .prompt()
-        {
+        let completer = InputCompleter::for_kind(Some(HistoryKind::KeypairPath));
+        let prompt = Text::new(msg).with_default(&default_path);
+        let prompt = match completer.help_message() {
+            Some(_) => completer.attach(prompt),
+            None => prompt.with_help_message(
+                "Press Enter to use the default keypair. Signer URIs such as env:VAR work too",
+            ),
+        };
+
+        let input = match prompt.prompt() {
             Ok(v) => v,
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
-                    exit(0);
+                    return Err(e.into());
                 }
+                InquireError::NotTTY => return Err(not_tty_error(msg)),
                 _ => {
                     print_error(format!("Invalid input: {e}. Please try again."));
                     continue;
@@ -257,17 +186,171 @@ pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
             &input
         };
 
-        match PathBuf::from_str(input) {
-            Ok(value) => return value,
+        match SignerSource::from_str(input) {
+            Ok(source) => {
+                history::record(HistoryKind::KeypairPath, input);
+                // Ask for the password now rather than once the command runs
+                if let Some(path) = source.file()
+                    && let Some(keystore) = Keystore::read(path)?
+                {
+                    unlock_keystore(path, &keystore)?;
+                }
+                return Ok(PathBuf::from(input));
+            }
             Err(e) => {
-                print_error(format!("Invalid path: {e}. Please try again."));
+                print_error(format!("{e}. Please try again."));
             }
         }
     }
 }
 
-pub fn prompt_confirmation(msg: &str) -> bool {
-    Confirm::new(msg).prompt().unwrap_or(false)
+/// Suggests recently entered values for a prompt kind and, in pubkey prompts,
+/// `@alias` entries from the address book once the input starts with `@`.
+#[derive(Clone, Default)]
+struct InputCompleter {
+    history: Vec<String>,
+    aliases: Vec<(String, String)>,
+}
+
+impl InputCompleter {
+    fn for_kind(kind: Option<HistoryKind>) -> Self {
+        Self {
+            history: kind.map(history::recent).unwrap_or_default(),
+            aliases: Vec::new(),
+        }
+    }
+
+    fn with_aliases(mut self, ctx: &ScillaContext) -> Self {
+        self.aliases = ctx
+            .address_book()
+            .iter()
+            .map(|(alias, pubkey)| (alias.clone(), short_pubkey(pubkey)))
+            .collect();
+        self
+    }
+
+    fn help_message(&self) -> Option<&'static str> {
+        match (self.history.is_empty(), self.aliases.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some("↑↓ to pick a recent value, Tab to complete"),
+            (true, false) => Some("Type @ to pick a saved address, Tab to complete"),
+            (false, false) => Some("↑↓ for recent values, @ for saved addresses, Tab to complete"),
+        }
+    }
+
+    /// Adds the completer to `prompt` when there is anything to suggest.
+    fn attach<'a, 'b>(self, prompt: Text<'a, 'b>) -> Text<'a, 'b> {
+        match self.help_message() {
+            Some(help) => prompt.with_help_message(help).with_autocomplete(self),
+            None => prompt,
+        }
+    }
+
+    fn matches(&self, input: &str) -> Vec<String> {
+        let input = input.trim();
+        if let Some(query) = input.strip_prefix('@')
+            && !self.aliases.is_empty()
+        {
+            let query = query.to_lowercase();
+            return self
+                .aliases
+                .iter()
+                .filter(|(alias, _)| alias.to_lowercase().contains(&query))
+                .map(|(alias, pubkey)| format!("@{alias} ({pubkey})"))
+                .collect();
+        }
+
+        let query = input.to_lowercase();
+        self.history
+            .iter()
+            .filter(|entry| entry.to_lowercase().contains(&query))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Autocomplete for InputCompleter {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        Ok(self.matches(input))
+    }
+
+    fn get_completion(
+        &mut self,
+        input: &str,
+        highlighted_suggestion: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        let suggestion = highlighted_suggestion.or_else(|| {
+            let mut matches = self.matches(input);
+            (matches.len() == 1).then(|| matches.remove(0))
+        });
+
+        // Alias suggestions carry the short pubkey for display only
+        Ok(suggestion.map(|s| match s.strip_prefix('@') {
+            Some(rest) => format!("@{}", rest.split_whitespace().next().unwrap_or_default()),
+            None => s,
+        }))
+    }
+}
+
+/// Like [`prompt_input_data`] for pubkeys, but also accepts `@alias` from the
+/// address book and suggests matching aliases as you type.
+pub fn prompt_pubkey(msg: &str, ctx: &ScillaContext) -> anyhow::Result<Pubkey> {
+    let book = ctx.address_book();
+
+    loop {
+        let completer = InputCompleter::for_kind(Some(HistoryKind::Pubkey)).with_aliases(ctx);
+        let input = match completer.attach(Text::new(msg)).prompt() {
+            Ok(v) => v,
+            Err(e) => match e {
+                InquireError::OperationInterrupted | InquireError::OperationCanceled => {
+                    return Err(e.into());
+                }
+                InquireError::NotTTY => return Err(not_tty_error(msg)),
+                _ => {
+                    print_error(format!("Invalid input: {e}. Please try again."));
+                    continue;
+                }
+            },
+        };
+
+        // Accepting a highlighted suggestion submits it with the short pubkey
+        // attached, so only the alias itself is resolved
+        let input = input.split_whitespace().next().unwrap_or_default();
+        match book.resolve(input) {
+            Ok(pubkey) => {
+                history::record(HistoryKind::Pubkey, input);
+                return Ok(pubkey);
+            }
+            Err(e) => print_error(format!("Parse error : {e}. Please try again.")),
+        }
+    }
+}
+
+/// Prompts cannot be shown without a terminal (e.g. when running a
+/// subcommand from a script), so treat the missing value as a hard error
+/// instead of retrying forever. `main` reports it and exits with a failure
+/// code.
+fn not_tty_error(msg: &str) -> anyhow::Error {
+    anyhow!(
+        "Missing input for \"{}\" and no terminal available to prompt for it. Pass it as an \
+         argument instead.",
+        msg.trim().trim_end_matches(':')
+    )
+}
+
+/// Asks a yes/no question. Cancelling the prompt answers no, while a prompt
+/// that cannot be shown, e.g. without a terminal, is an error rather than a
+/// silent no.
+pub fn prompt_confirmation(msg: &str) -> anyhow::Result<bool> {
+    match Confirm::new(msg).prompt() {
+        Ok(confirmed) => Ok(confirmed),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(false),
+        Err(InquireError::NotTTY) => bail!(
+            "No terminal available to answer \"{}\"",
+            msg.trim().trim_end_matches(':')
+        ),
+        Err(e) => Err(e.into()),
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -306,21 +389,41 @@ pub fn prompt_network_rpc_url() -> anyhow::Result<String> {
     Ok(network.rpc_url().to_string())
 }
 
-pub fn prompt_go_back() -> NavigationTarget {
-    let choice = Select::new(
-        "Go Back to menu or last section",
-        vec!["Main Section", "Previous Section"],
-    )
-    .prompt()
-    .unwrap();
+/// Asks whether to export the output just shown. Declining or cancelling the
+/// prompt skips the export.
+pub fn prompt_export_format() -> Option<OutputFormat> {
+    let choice = Select::new("Export to file?", vec!["No", "CSV", "Markdown", "JSON"])
+        .prompt()
+        .ok()?;
+
     match choice {
-        "Main Section" => NavigationTarget::MainSection,
-        "Previous Section" => NavigationTarget::PreviousSection,
-        _ => unreachable!(),
+        "CSV" => Some(OutputFormat::Csv),
+        "Markdown" => Some(OutputFormat::Markdown),
+        "JSON" => Some(OutputFormat::Json),
+        _ => None,
+    }
+}
+
+/// Asks where to export to. Cancelling the prompt skips the export.
+pub fn prompt_export_path(format: OutputFormat) -> Option<PathBuf> {
+    let default_path = format!("scilla-export.{}", format.extension());
+
+    loop {
+        match Text::new("Export path:")
+            .with_default(&default_path)
+            .prompt()
+        {
+            Ok(path) if !path.trim().is_empty() => return Some(PathBuf::from(path.trim())),
+            Ok(_) => return Some(PathBuf::from(&default_path)),
+            Err(InquireError::OperationInterrupted | InquireError::OperationCanceled) => {
+                return None;
+            }
+            Err(e) => print_error(format!("Invalid input: {e}. Please try again.")),
+        }
     }
 }
 
-pub fn prompt_encoding_options() -> UiTransactionEncoding {
+pub fn prompt_encoding_options() -> anyhow::Result<UiTransactionEncoding> {
     prompt_select_data(
         "Select encoding format:",
         vec![
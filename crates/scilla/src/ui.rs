@@ -1,9 +1,65 @@
 use {
+    crate::{
+        context::ScillaContext,
+        error::{is_cancelled, is_dry_run, is_sign_only},
+        output::{CommandOutput, OutputFormat},
+        prompt::{prompt_export_format, prompt_export_path},
+    },
     console::style,
-    indicatif::{ProgressBar, ProgressStyle},
+    indicatif::{MultiProgress, ProgressBar, ProgressStyle},
+    std::{fs, sync::Mutex},
 };
 
-pub async fn show_spinner<F, T>(message: &str, fut: F)
+/// The spinner drawn by [`show_spinner`], if one is running.
+static ACTIVE_SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Runs `f` with the active spinner hidden, for prompts that come up while a
+/// command is running behind one.
+pub fn suspend_spinner<R>(f: impl FnOnce() -> R) -> R {
+    let spinner = ACTIVE_SPINNER.lock().ok().and_then(|active| active.clone());
+
+    match spinner {
+        Some(spinner) => spinner.suspend(f),
+        None => f(),
+    }
+}
+
+fn set_active_spinner(spinner: Option<ProgressBar>) {
+    if let Ok(mut active) = ACTIVE_SPINNER.lock() {
+        *active = spinner;
+    }
+}
+
+/// A progress bar for commands that send many transactions, drawn below the
+/// active spinner.
+pub fn progress_bar(len: u64, message: &str) -> ProgressBar {
+    let bar = ProgressBar::new(len)
+        .with_style(
+            ProgressStyle::with_template("{msg} [{bar:30.cyan/blue}] {pos}/{len}")
+                .unwrap()
+                .progress_chars("=> "),
+        )
+        .with_message(message.to_string());
+
+    match ACTIVE_SPINNER.lock().ok().and_then(|active| active.clone()) {
+        Some(spinner) => {
+            let multi = MultiProgress::new();
+            multi.add(spinner);
+            multi.add(bar)
+        }
+        None => bar,
+    }
+}
+
+/// Awaits `fut` behind a spinner. Errors, including a prompt cancelled while
+/// the spinner was suspended, are reported on the spinner line and `None` is
+/// returned. Outside the interactive menus a failure is returned instead, so
+/// the process can exit with an error.
+pub async fn show_spinner<F, T>(
+    ctx: &ScillaContext,
+    message: &str,
+    fut: F,
+) -> anyhow::Result<Option<T>>
 where
     F: std::future::Future<Output = anyhow::Result<T>>,
 {
@@ -15,17 +71,104 @@ where
     );
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
     spinner.set_message(message.to_string());
+    set_active_spinner(Some(spinner.clone()));
 
     let result = fut.await;
+    set_active_spinner(None);
 
-    match &result {
-        Ok(_) => spinner.finish_with_message("✅ Done"),
+    match result {
+        Ok(value) => {
+            spinner.finish_with_message("✅ Done");
+            Ok(Some(value))
+        }
+        Err(e) if !ctx.is_interactive() && !is_handled(&e) => {
+            spinner.finish_and_clear();
+            Err(e)
+        }
         Err(e) => {
-            spinner.finish_with_message(format!("{}", style(format!("Error : {e}")).red().bold()))
+            let message = if is_cancelled(&e) {
+                style("Operation cancelled.").yellow().to_string()
+            } else if is_dry_run(&e) {
+                style("🧪 Dry run complete, nothing was sent")
+                    .cyan()
+                    .to_string()
+            } else if is_sign_only(&e) {
+                style("✍️  Signed only, nothing was sent")
+                    .cyan()
+                    .to_string()
+            } else {
+                style(format!("Error : {e}")).red().bold().to_string()
+            };
+            // The spinner is not drawn when stderr is not a terminal, so the
+            // error would otherwise be lost when piping output
+            if spinner.is_hidden() {
+                eprintln!("{message}");
+            }
+            spinner.finish_with_message(message);
+            Ok(None)
         }
     }
 }
 
+/// Runs a command behind a spinner and renders its result in the session's
+/// output format.
+pub async fn render_with_spinner<F, T>(
+    ctx: &ScillaContext,
+    message: &str,
+    fut: F,
+) -> anyhow::Result<()>
+where
+    F: std::future::Future<Output = anyhow::Result<T>>,
+    T: CommandOutput,
+{
+    if let Some(output) = show_spinner(ctx, message, fut).await? {
+        render_output(ctx, &output)?;
+    }
+
+    Ok(())
+}
+
+/// Renders a command result in the session's output format. In the
+/// interactive menus, tables without secrets can then be exported to a file.
+pub fn render_output<T: CommandOutput>(ctx: &ScillaContext, output: &T) -> anyhow::Result<()> {
+    let format = ctx.output_format();
+    format.render(output)?;
+
+    if ctx.is_interactive()
+        && format == OutputFormat::Table
+        && !output.has_secrets()
+        && !output.tables().is_empty()
+    {
+        export_output(output)?;
+    }
+
+    Ok(())
+}
+
+fn export_output<T: CommandOutput>(output: &T) -> anyhow::Result<()> {
+    let Some(format) = prompt_export_format() else {
+        return Ok(());
+    };
+    let Some(path) = prompt_export_path(format) else {
+        return Ok(());
+    };
+
+    fs::write(&path, format.format(output)?)?;
+    println!(
+        "{} {}",
+        style("Exported to").green().bold(),
+        style(path.display()).cyan()
+    );
+
+    Ok(())
+}
+
+/// Whether `err` ends a command without it having failed: a cancelled prompt,
+/// or a transaction that was only simulated or signed.
+fn is_handled(err: &anyhow::Error) -> bool {
+    is_cancelled(err) || is_dry_run(err) || is_sign_only(err)
+}
+
 pub fn print_error(message: impl std::fmt::Display) {
     println!("{}", style(message).red().bold());
 }